@@ -7,7 +7,7 @@ use egui::epaint::{PathShape, PathStroke};
 use egui::{pos2, Color32, Painter, Pos2, Rect, Sense};
 use endgame_direction::{Direction, DirectionSet};
 use endgame_grid::Color::{Four, One, Three, Two};
-use endgame_grid::{Coord, DirectionType, Shape, ShapeContainer, SizedGrid};
+use endgame_grid::{outline, Coord, DirectionType, Shape, ShapeContainer, SizedGrid};
 use itertools::Itertools;
 use std::collections::HashMap;
 use std::f32::consts::PI;
@@ -810,6 +810,41 @@ impl<SZ: SizedGrid> GridRenderContext<SZ> {
         }
     }
 
+    /// Render only the outline of the given shape, including the outlines
+    /// of any holes, as closed paths with the given border style.  Unlike
+    /// `render_shape`, the outline is drawn as continuous paths rather than
+    /// as the edges of individual cells, so the corners are joined cleanly.
+    pub fn render_shape_outline<S>(&self, shape: &S, style: &CellPrimitiveBorderStyle)
+    where
+        S: Shape<SZ::Coord>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        if let CellPrimitiveBorderStyle::None = style {
+            return;
+        }
+        for outline in outline::outlines(&self.szg, shape) {
+            for polygon in std::iter::once(&outline.exterior).chain(outline.holes.iter()) {
+                let points = polygon
+                    .iter()
+                    .map(|v| self.transform.transform_pos(glam_vec2_to_egui_pos2(*v)))
+                    .collect::<Vec<_>>();
+                self.painter.add(PathShape {
+                    points,
+                    closed: true,
+                    fill: Color32::TRANSPARENT,
+                    stroke: PathStroke {
+                        width: style.width(),
+                        color: Solid(style.color()),
+                        kind: egui::StrokeKind::Middle,
+                    },
+                });
+            }
+        }
+    }
+
     pub fn render_shape_container<V, SC: ShapeContainer<SZ::Coord, V>>(
         &self,
         shape_container: &SC,
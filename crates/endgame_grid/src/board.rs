@@ -0,0 +1,164 @@
+//! Bounded boards over grids.
+//!
+//! Grids are infinite, so game rules typically need to check whether each
+//! coordinate they move to is still on the board.  A `Board` pairs a `Shape`
+//! with its coordinate system, and provides movement and iteration that
+//! stops at the edges of the shape, so that rules can directly ask which
+//! moves are legal from a coordinate.
+
+use crate::{AllowedCoordIterRange, Coord, DirectionType, Shape};
+use endgame_direction::{Direction, DirectionSet};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A board made up of the coordinates of a `Shape`.  Moving from a
+/// coordinate of the board to one outside it is treated the same as moving
+/// in a `Direction` that is not allowed.  Movement from coordinates that
+/// are not on the board is never possible.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Board<C: Coord, S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    shape: S,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _marker: PhantomData<C>,
+}
+
+impl<C: Coord, S> Board<C, S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    /// Construct a new `Board` consisting of the coordinates of the given
+    /// `Shape`.
+    pub fn new(shape: S) -> Self {
+        Board {
+            shape,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Obtain the `Shape` of the board.
+    pub fn shape(&self) -> &S {
+        &self.shape
+    }
+
+    /// Is the given coordinate on the board?
+    pub fn contains(&self, coord: &C) -> bool {
+        self.shape.contains(coord)
+    }
+
+    /// Iterate over the coordinates of the board.
+    pub fn iter(&self) -> S::Iterator<'_> {
+        self.shape.iter()
+    }
+
+    /// Move from the coordinate in the given `Direction`.  Returns `None`
+    /// if the `Direction` is not allowed, or either coordinate is not on the
+    /// board.
+    pub fn move_in_direction(
+        &self,
+        coord: &C,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<C> {
+        if !self.contains(coord) {
+            return None;
+        }
+        coord
+            .move_in_direction(dir_type, dir)
+            .filter(|next| self.contains(next))
+    }
+
+    /// Move from the coordinate along the given axis.  Returns `None` if
+    /// either coordinate is not on the board.
+    pub fn move_on_axis(&self, coord: &C, axis: C::Axes, positive: bool) -> Option<C> {
+        if !self.contains(coord) {
+            return None;
+        }
+        Some(coord.move_on_axis(axis, positive)).filter(|next| self.contains(next))
+    }
+
+    /// Which `Direction`s lead from the coordinate to another coordinate on
+    /// the board?  This is empty for coordinates not on the board.
+    pub fn allowed_directions(&self, coord: &C, dir_type: DirectionType) -> DirectionSet {
+        coord
+            .allowed_directions(dir_type)
+            .iter()
+            .filter(|dir| self.move_in_direction(coord, dir_type, *dir).is_some())
+            .collect()
+    }
+
+    /// Is it possible to move from the coordinate in the given `Direction`
+    /// while remaining on the board?
+    pub fn allowed_direction(&self, coord: &C, dir_type: DirectionType, dir: Direction) -> bool {
+        self.move_in_direction(coord, dir_type, dir).is_some()
+    }
+
+    /// Produce an iterator over the neighbours of the coordinate on the
+    /// board, in directions of the given `DirectionType`.
+    pub fn neighbours(&self, coord: &C, dir_type: DirectionType) -> impl Iterator<Item=C> {
+        coord
+            .allowed_directions(dir_type)
+            .iter()
+            .filter_map(|dir| self.move_in_direction(coord, dir_type, dir))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Produce an iterator that steps from the coordinate in the given
+    /// `Direction`, as with `Coord::direction_iterator`, stopping upon
+    /// reaching the edge of the board.  The iterator is empty if the
+    /// coordinate is not on the board.
+    pub fn direction_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        coord: &C,
+        dir_type: DirectionType,
+        dir: Direction,
+        range: RB,
+    ) -> impl Iterator<Item=C> {
+        coord
+            .direction_iterator(dir_type, dir, range)
+            .take_while(|next| self.contains(next))
+    }
+
+    /// Produce an iterator that steps from the coordinate along the given
+    /// axis, as with `Coord::axis_iterator`, stopping upon reaching the edge
+    /// of the board.  The iterator is empty if the coordinate is not on the
+    /// board.
+    pub fn axis_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        coord: &C,
+        axis: C::Axes,
+        positive: bool,
+        range: RB,
+    ) -> impl Iterator<Item=C> {
+        coord
+            .axis_iterator(axis, positive, range)
+            .take_while(|next| self.contains(next))
+    }
+}
+
+impl<C: Coord, S> From<S> for Board<C, S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    fn from(shape: S) -> Self {
+        Board::new(shape)
+    }
+}
@@ -0,0 +1,287 @@
+//! Canonical forms of shapes up to symmetry.
+//!
+//! Two shapes are congruent if one can be rotated, reflected and
+//! translated onto the other.  Opening books, transposition tables and
+//! duplicate detection all benefit from representing each class of
+//! congruent shapes by a single canonical form.  The canonical form is
+//! found by trying every `Transform` of the grid, translating each image
+//! such that its least coordinate, in the order of array offsets, lies at
+//! the origin, and keeping the image whose array offsets are least.  This
+//! depends only upon the coordinates, so it is deterministic across runs.
+//!
+//! The rotations of a triangular grid `Transform` are about the center of
+//! a triangle, so they never exchange upward and downward facing
+//! triangles.  The half turns about the edges and vertices of triangles
+//! that do are provided by `CanonicalCoord::half_turn`.
+
+use crate::symmetry::{map_container, map_shape, Isometry, SymmetricCoord, Transform};
+use crate::{ArrayOffsetCoord, ModuleCoord, Shape, ShapeContainer};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Coordinates whose grids can be translated so that any coordinate lies at
+/// the origin.  The order of array offsets must be preserved by these
+/// translations.
+pub trait CanonicalCoord: SymmetricCoord + ArrayOffsetCoord {
+    /// Translate the coordinate by the translation of the grid that moves
+    /// the `anchor` coordinate to the origin.  For triangular grids, where
+    /// translations cannot change the orientation of a triangle, a
+    /// downward facing `anchor` is instead moved to the downward facing
+    /// triangle alongside the origin in the same array row.
+    fn translate_from(&self, anchor: &Self) -> Self;
+
+    /// For grids where a half turn is not among the rotations of a
+    /// `Transform`, produce the coordinate reached by a half turn that maps
+    /// the grid onto itself.  Otherwise `None`.
+    fn half_turn(&self) -> Option<Self> {
+        None
+    }
+}
+
+/// The symmetries of the grid under which shapes are considered to be
+/// equivalent.  These are named after the corresponding kinds of
+/// polyforms.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Equivalence {
+    /// Shapes are equivalent up to translation.
+    Fixed,
+    /// Shapes are equivalent up to translation and rotation.
+    OneSided,
+    /// Shapes are equivalent up to translation, rotation and reflection.
+    #[default]
+    Free,
+}
+
+impl Display for Equivalence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Equivalence::*;
+        match self {
+            Fixed => write!(f, "Fixed"),
+            OneSided => write!(f, "OneSided"),
+            Free => write!(f, "Free"),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The canonical form of a `Shape` or `ShapeContainer`.  The `form` is
+/// obtained from the original by making a `CanonicalCoord::half_turn`, if
+/// `half_turn` is set, applying the `transform`, and then translating the
+/// grid to move the `anchor` coordinate, of the transformed original, to
+/// the origin.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Canonical<C: CanonicalCoord, T> {
+    pub form: T,
+    pub half_turn: bool,
+    pub transform: Transform<C>,
+    pub anchor: C,
+}
+
+impl<C: CanonicalCoord, T> Canonical<C, T> {
+    /// Map a coordinate of the original to its place in the canonical
+    /// form.
+    pub fn apply(&self, coord: &C) -> C {
+        let coord = if self.half_turn {
+            coord.half_turn().expect("Half turns should only be made where supported")
+        } else {
+            *coord
+        };
+        self.transform.apply(&coord).translate_from(&self.anchor)
+    }
+}
+
+impl<MC: CanonicalCoord + ModuleCoord, T> Canonical<MC, T>
+where
+        for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+        for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    /// The `Isometry` that maps the original onto the canonical form.
+    /// Grids satisfying `ModuleCoord` never require a separate half turn.
+    pub fn isometry(&self) -> Isometry<MC> {
+        Isometry::new(self.transform, -self.anchor)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Find the canonical form of a `Shape` up to rotation, reflection and
+/// translation.
+pub fn canonicalize<C: CanonicalCoord, S>(shape: &S) -> Canonical<C, S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    canonicalize_with(shape, Equivalence::Free)
+}
+
+/// Find the canonical form of a `Shape` up to the symmetries of the given
+/// `Equivalence`.
+pub fn canonicalize_with<C: CanonicalCoord, S>(
+    shape: &S,
+    equivalence: Equivalence,
+) -> Canonical<C, S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let entries: Vec<(C, ())> = shape.iter().map(|coord| (*coord, ())).collect();
+    let found = canonical_transform(&entries, equivalence);
+    Canonical {
+        form: map_shape(shape, |coord| found.apply(coord)),
+        half_turn: found.half_turn,
+        transform: found.transform,
+        anchor: found.anchor,
+    }
+}
+
+/// Find the canonical form of a `ShapeContainer` up to rotation,
+/// reflection and translation.  The values are taken into account, so
+/// containers are only congruent if their values coincide as well as their
+/// coordinates.
+pub fn canonicalize_container<C: CanonicalCoord, V, SC>(container: &SC) -> Canonical<C, SC>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash + Ord,
+    SC: ShapeContainer<C, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    let entries: Vec<(C, &V)> = container.iter().map(|(coord, value)| (*coord, value)).collect();
+    let found = canonical_transform(&entries, Equivalence::Free);
+    Canonical {
+        form: map_container(container, |coord| found.apply(coord)),
+        half_turn: found.half_turn,
+        transform: found.transform,
+        anchor: found.anchor,
+    }
+}
+
+/// Can one `Shape` be rotated, reflected and translated onto the other?
+pub fn is_congruent<C: CanonicalCoord, S>(shape: &S, other: &S) -> bool
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    shape.len() == other.len() && canonicalize(shape).form == canonicalize(other).form
+}
+
+/// Can one `ShapeContainer` be rotated, reflected and translated onto the
+/// other, such that the values coincide?
+pub fn is_container_congruent<C: CanonicalCoord, V, SC>(container: &SC, other: &SC) -> bool
+where
+    V: Debug + Clone + PartialEq + Eq + Hash + Ord,
+    SC: ShapeContainer<C, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    container.len() == other.len()
+        && canonicalize_container(container).form == canonicalize_container(other).form
+}
+
+/// Enumerate the distinct images of a `Shape` under the symmetries of the
+/// given `Equivalence`, each translated as with `canonicalize_with` under
+/// `Equivalence::Fixed`.  So for example, the `Free` orientations of the
+/// L tetromino are its eight rotations and reflections.
+pub fn orientations<C: CanonicalCoord, S>(shape: &S, equivalence: Equivalence) -> Vec<S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let mut result: Vec<S> = Vec::new();
+    for symmetry in symmetries(equivalence) {
+        let image = map_shape(shape, |coord| symmetry.apply(coord));
+        let image = canonicalize_with(&image, Equivalence::Fixed).form;
+        if !result.contains(&image) {
+            result.push(image);
+        }
+    }
+    result
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to find the half turn, `Transform` and anchor that
+/// produce the least sorted array offsets, paired with the given keys.  Ties
+/// are broken by the order of `Transform::all`, so that the result is
+/// deterministic.  The `form` of the result is left empty.
+fn canonical_transform<C: CanonicalCoord, K: Ord + Clone>(
+    entries: &[(C, K)],
+    equivalence: Equivalence,
+) -> Canonical<C, ()> {
+    let mut best = None;
+    let mut best_keys: Vec<((isize, isize), K)> = Vec::new();
+    for mut canonical in symmetries(equivalence) {
+        let image: Vec<(C, K)> = entries
+            .iter()
+            .map(|(coord, key)| (canonical.apply(coord), key.clone()))
+            .collect();
+        // The image is relative to the origin, so its least coordinate is
+        // the anchor.
+        let least = image
+            .iter()
+            .map(|(coord, _)| *coord)
+            .min_by_key(|coord| coord.grid_to_array_offset());
+        let Some(least) = least else {
+            return canonical;
+        };
+        canonical.anchor = least;
+        let mut keys: Vec<((isize, isize), K)> = image
+            .into_iter()
+            .map(|(coord, key)| (coord.translate_from(&least).grid_to_array_offset(), key))
+            .collect();
+        keys.sort();
+        if best.is_none() || keys < best_keys {
+            best = Some(canonical);
+            best_keys = keys;
+        }
+    }
+    best.expect("There should always be at least one Transform")
+}
+
+/// Internal helper to enumerate the symmetries of the given `Equivalence`,
+/// as combinations of a half turn and a `Transform` anchored at the origin.
+fn symmetries<C: CanonicalCoord>(equivalence: Equivalence) -> Vec<Canonical<C, ()>> {
+    use Equivalence::*;
+    let origin = C::array_offset_to_grid((0, 0));
+    let half_turns = match equivalence {
+        Fixed => vec![false],
+        OneSided | Free if origin.half_turn().is_some() => vec![false, true],
+        OneSided | Free => vec![false],
+    };
+    let transforms = match equivalence {
+        Fixed => vec![Transform::identity()],
+        OneSided => Transform::rotations(),
+        Free => Transform::all(),
+    };
+    half_turns
+        .into_iter()
+        .flat_map(|half_turn| {
+            transforms.iter().map(move |transform| Canonical {
+                form: (),
+                half_turn,
+                transform: *transform,
+                anchor: origin,
+            })
+        })
+        .collect()
+}
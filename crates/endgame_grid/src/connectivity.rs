@@ -0,0 +1,135 @@
+//! Flood fills and connected components of coordinates.
+//!
+//! Two coordinates are connected if it is possible to step from one to the
+//! other between neighbouring coordinates, in directions of a given
+//! `DirectionType`, without leaving the region of interest.  Using
+//! `DirectionType::Face` this gives the groups of stones in Go, while using
+//! `DirectionType::Vertex` additionally connects diagonal neighbours, as
+//! with the "eight-way" connectivity of square grids.
+
+use crate::shape::HashShape;
+use crate::{Coord, DirectionType, Shape, ShapeContainer};
+use std::collections::HashSet;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Find all the coordinates connected to the `seed` for which the given
+/// `predicate` holds, stepping between neighbouring coordinates in
+/// directions of the given `DirectionType`.  Should the predicate not hold
+/// for the seed itself, the result is empty.
+///
+/// As grids are infinite, the predicate is responsible for bounding the
+/// fill, for example `|coord| board.contains(coord)` for a `Shape` of the
+/// board.  Should the predicate hold for infinitely many connected
+/// coordinates, the fill will not terminate.
+pub fn flood_fill<C: Coord, P>(seed: &C, dir_type: DirectionType, mut predicate: P) -> HashShape<C>
+where
+    P: FnMut(&C) -> bool,
+{
+    let mut result = HashShape::new();
+    if !predicate(seed) {
+        return result;
+    }
+    result.insert(seed.clone());
+    let mut frontier = vec![seed.clone()];
+    // Coordinates for which the predicate failed, so as to avoid
+    // evaluating it repeatedly.
+    let mut rejected = HashSet::new();
+    while let Some(coord) = frontier.pop() {
+        for dir in coord.allowed_directions(dir_type).iter() {
+            let Some(next) = coord.move_in_direction(dir_type, dir) else {
+                continue;
+            };
+            if result.contains(&next) || rejected.contains(&next) {
+                continue;
+            }
+            if predicate(&next) {
+                result.insert(next.clone());
+                frontier.push(next);
+            } else {
+                rejected.insert(next);
+            }
+        }
+    }
+    result
+}
+
+/// Split a `Shape` into its connected components, stepping between
+/// neighbouring coordinates in directions of the given `DirectionType`.
+/// The components are disjoint, and their union is the original shape.
+/// They are produced in no particular order.
+pub fn connected_components<C: Coord, S>(shape: &S, dir_type: DirectionType) -> Vec<S>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let mut components = Vec::new();
+    let mut visited: HashSet<C> = HashSet::new();
+    for coord in shape.iter() {
+        if visited.contains(coord) {
+            continue;
+        }
+        let mut component = S::new();
+        for member in flood_fill(coord, dir_type, |c| shape.contains(c)) {
+            visited.insert(member.clone());
+            component.insert(member);
+        }
+        components.push(component);
+    }
+    components
+}
+
+/// Is the given `Shape` connected, stepping between neighbouring
+/// coordinates in directions of the given `DirectionType`?  The empty shape
+/// is considered to be connected.
+pub fn is_connected<C: Coord, S>(shape: &S, dir_type: DirectionType) -> bool
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    match shape.iter().next() {
+        Some(coord) => flood_fill(coord, dir_type, |c| shape.contains(c)).len() == shape.len(),
+        None => true,
+    }
+}
+
+/// Split a `ShapeContainer` into connected regions of coordinates holding
+/// equal values, stepping between neighbouring coordinates in directions of
+/// the given `DirectionType`.  Each region is produced along with its
+/// value.  The regions are disjoint, and their union is the shape of the
+/// original container.  They are produced in no particular order.
+pub fn value_components<C: Coord, V, SC>(
+    container: &SC,
+    dir_type: DirectionType,
+) -> Vec<(V, SC::Shape)>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    SC: ShapeContainer<C, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    let mut regions = Vec::new();
+    let mut visited: HashSet<C> = HashSet::new();
+    for (coord, value) in container.iter() {
+        if visited.contains(coord) {
+            continue;
+        }
+        let mut region = SC::Shape::new();
+        for member in flood_fill(coord, dir_type, |c| container.get(c) == Some(value)) {
+            visited.insert(member.clone());
+            region.insert(member);
+        }
+        regions.push((value.clone(), region));
+    }
+    regions
+}
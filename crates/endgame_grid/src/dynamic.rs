@@ -17,11 +17,16 @@
 
 use crate::shape::HashShape;
 use crate::{hex, square, AllowedCoordIterRange, DirectionType};
-use crate::{triangle, Color, Shape};
+use crate::{triangle, Color, Metric, Shape};
+use crate::canonical::Equivalence;
+use crate::notation::ParseCoordError;
+use crate::polyform::polyforms;
+use crate::visibility::SightCell;
 use endgame_direction::{Direction, DirectionSet};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -82,6 +87,20 @@ impl Display for Kind {
     }
 }
 
+impl FromStr for Kind {
+    type Err = ParseCoordError;
+
+    /// Parse the name of a `Kind`, as produced by `Display`, in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Kind::*;
+        let name = s.trim();
+        [Square, Hex, Triangle]
+            .into_iter()
+            .find(|kind| kind.to_string().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseCoordError::Kind(s.to_string()))
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
@@ -159,6 +178,124 @@ impl Coord {
         };
         HashShape::from_iter(coords)
     }
+
+    /// Produce a rectangle of the given `width` and `height` for the given
+    /// kind of grid, with the origin in one corner.
+    pub fn rectangle(kind: Kind, width: usize, height: usize) -> HashShape<Coord> {
+        use Kind::*;
+        match kind {
+            Square => Coord::lift(square::Coord::rectangle(width, height)),
+            Hex => Coord::lift(hex::Coord::rectangle(width, height)),
+            Triangle => Coord::lift(triangle::Coord::rectangle(width, height)),
+        }
+    }
+
+    /// Produce a parallelogram with the origin in one corner, whose sides
+    /// run along the two given axes.  The kind of grid is determined by the
+    /// axes, which must be of the same kind.
+    pub fn parallelogram(
+        first: Axes,
+        first_len: usize,
+        second: Axes,
+        second_len: usize,
+    ) -> HashShape<Coord> {
+        match (first, second) {
+            (Axes::Square(a), Axes::Square(b)) => {
+                Coord::lift(square::Coord::parallelogram(a, first_len, b, second_len))
+            }
+            (Axes::Hex(a), Axes::Hex(b)) => {
+                Coord::lift(hex::Coord::parallelogram(a, first_len, b, second_len))
+            }
+            (Axes::Triangle(a), Axes::Triangle(b)) => {
+                Coord::lift(triangle::Coord::parallelogram(a, first_len, b, second_len))
+            }
+            _ => panic!(
+                "Cannot create parallelogram for different kinds of Axes: {} vs {}",
+                first.kind(),
+                second.kind()
+            ),
+        }
+    }
+
+    /// Produce a triangle with sides of `size` coordinates for the given
+    /// kind of grid, with the origin in one corner.
+    pub fn triangle(kind: Kind, size: usize) -> HashShape<Coord> {
+        use Kind::*;
+        match kind {
+            Square => Coord::lift(square::Coord::triangle(size)),
+            Hex => Coord::lift(hex::Coord::triangle(size)),
+            Triangle => Coord::lift(triangle::Coord::triangle(size)),
+        }
+    }
+
+    /// Produce a hexagon of the given `radius` for the given kind of grid,
+    /// centered upon the origin.
+    pub fn hexagon(kind: Kind, radius: usize) -> HashShape<Coord> {
+        use Kind::*;
+        match kind {
+            Square => Coord::lift(square::Coord::hexagon(radius)),
+            Hex => Coord::lift(hex::Coord::hexagon(radius)),
+            Triangle => Coord::lift(triangle::Coord::hexagon(radius)),
+        }
+    }
+
+    /// Produce the coordinates at the given `radius` from the origin under
+    /// the given `Metric` for the given kind of grid.
+    pub fn metric_ring(kind: Kind, metric: Metric, radius: usize) -> HashShape<Coord> {
+        use Kind::*;
+        match kind {
+            Square => Coord::lift(square::Coord::metric_ring(metric, radius)),
+            Hex => Coord::lift(hex::Coord::metric_ring(metric, radius)),
+            Triangle => Coord::lift(triangle::Coord::metric_ring(metric, radius)),
+        }
+    }
+
+    /// Produce the coordinates within the given `radius` of the origin under
+    /// the given `Metric` for the given kind of grid.
+    pub fn metric_range(kind: Kind, metric: Metric, radius: usize) -> HashShape<Coord> {
+        use Kind::*;
+        match kind {
+            Square => Coord::lift(square::Coord::metric_range(metric, radius)),
+            Hex => Coord::lift(hex::Coord::metric_range(metric, radius)),
+            Triangle => Coord::lift(triangle::Coord::metric_range(metric, radius)),
+        }
+    }
+
+    /// Enumerate the polyforms of the given kind of grid made of the given
+    /// number of coordinates, which are distinct under the given
+    /// `Equivalence`.  See `polyform::polyforms`.
+    pub fn polyforms(kind: Kind, size: usize, equivalence: Equivalence) -> Vec<HashShape<Coord>> {
+        use Kind::*;
+        match kind {
+            Square => polyforms::<square::Coord>(size, equivalence)
+                .into_iter()
+                .map(Coord::lift)
+                .collect(),
+            Hex => polyforms::<hex::Coord>(size, equivalence)
+                .into_iter()
+                .map(Coord::lift)
+                .collect(),
+            Triangle => polyforms::<triangle::Coord>(size, equivalence)
+                .into_iter()
+                .map(Coord::lift)
+                .collect(),
+        }
+    }
+
+    /// Display the coordinate tagged with its `Kind`, as in `Hex(1,-2)`,
+    /// so that it may be parsed again with `FromStr`.
+    pub fn tagged(&self) -> Tagged<'_> {
+        Tagged(self)
+    }
+
+    /// Internal helper to convert a shape of a particular kind of
+    /// coordinate into a shape of dynamic coordinates.
+    fn lift<C>(shape: HashShape<C>) -> HashShape<Coord>
+    where
+        C: crate::Coord + Into<Coord>,
+    {
+        shape.into_iter().map(Into::into).collect()
+    }
 }
 
 impl Display for Coord {
@@ -172,6 +309,42 @@ impl Display for Coord {
     }
 }
 
+/// A helper for displaying a `Coord` tagged with its `Kind`, as in
+/// `Hex(1,-2)`, produced by `Coord::tagged`.
+#[derive(Debug, Clone, Copy)]
+pub struct Tagged<'a>(&'a Coord);
+
+impl Display for Tagged<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.kind())?;
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    /// Parse a coordinate tagged with its `Kind`, as produced by
+    /// `Coord::tagged`.  The untagged syntax of `Display` cannot be parsed,
+    /// as square and hexagonal coordinates would be indistinguishable.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let (kind, rest) = trimmed.split_at(trimmed.find('(').unwrap_or(trimmed.len()));
+        if rest.is_empty() {
+            return Err(ParseCoordError::Syntax {
+                text: s.to_string(),
+                expected: "a coordinate tagged with its kind, such as Hex(1,-2)",
+            });
+        }
+        use Kind::*;
+        Ok(match kind.parse()? {
+            Square => Coord::Square(rest.parse()?),
+            Hex => Coord::Hex(rest.parse()?),
+            Triangle => Coord::Triangle(rest.parse()?),
+        })
+    }
+}
+
 impl From<square::Coord> for Coord {
     fn from(value: square::Coord) -> Self {
         Coord::Square(value)
@@ -302,6 +475,34 @@ impl crate::Coord for Coord {
         }
     }
 
+    fn vertex_distance(&self, other: &Self) -> usize {
+        use Coord::*;
+        match (self, other) {
+            (Square(a), Square(b)) => a.vertex_distance(b),
+            (Hex(a), Hex(b)) => a.vertex_distance(b),
+            (Triangle(a), Triangle(b)) => a.vertex_distance(b),
+            _ => panic!(
+                "Cannot compute distance between different kinds of Coords: {} vs {}",
+                self.kind(),
+                other.kind()
+            ),
+        }
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        use Coord::*;
+        match (self, other) {
+            (Square(a), Square(b)) => a.euclidean_distance(b),
+            (Hex(a), Hex(b)) => a.euclidean_distance(b),
+            (Triangle(a), Triangle(b)) => a.euclidean_distance(b),
+            _ => panic!(
+                "Cannot compute distance between different kinds of Coords: {} vs {}",
+                self.kind(),
+                other.kind()
+            ),
+        }
+    }
+
     fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
         use Coord::*;
         match self {
@@ -371,6 +572,34 @@ impl crate::Coord for Coord {
         }
     }
 
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        use Coord::*;
+        match (self, other) {
+            (Square(a), Square(b)) => CoordIter::Square(a.line_iterator(b)),
+            (Hex(a), Hex(b)) => CoordIter::Hex(a.line_iterator(b)),
+            (Triangle(a), Triangle(b)) => CoordIter::Triangle(a.line_iterator(b)),
+            _ => panic!(
+                "Cannot create line iterator for different kinds of Coords: {} vs {}",
+                self.kind(),
+                other.kind()
+            ),
+        }
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        use Coord::*;
+        match (self, other) {
+            (Square(a), Square(b)) => CoordIter::Square(a.supercover_iterator(b)),
+            (Hex(a), Hex(b)) => CoordIter::Hex(a.supercover_iterator(b)),
+            (Triangle(a), Triangle(b)) => CoordIter::Triangle(a.supercover_iterator(b)),
+            _ => panic!(
+                "Cannot create line iterator for different kinds of Coords: {} vs {}",
+                self.kind(),
+                other.kind()
+            ),
+        }
+    }
+
     fn axis_iterator<RB: AllowedCoordIterRange>(
         &self,
         axis: Self::Axes,
@@ -465,6 +694,64 @@ impl crate::Coord for Coord {
     }
 }
 
+impl crate::parts::PartCoord for Coord {
+    fn boundary(&self) -> Vec<(Direction, Direction)> {
+        use Coord::*;
+        match self {
+            Square(coord) => coord.boundary(),
+            Hex(coord) => coord.boundary(),
+            Triangle(coord) => coord.boundary(),
+        }
+    }
+}
+
+impl crate::visibility::SightCoord for Coord {
+    fn sight_lines(&self, other: &Self) -> [Vec<Self>; 2] {
+        use Coord::*;
+        match (self, other) {
+            (Square(a), Square(b)) => {
+                a.sight_lines(b).map(|line| line.into_iter().map(Square).collect())
+            }
+            (Hex(a), Hex(b)) => a.sight_lines(b).map(|line| line.into_iter().map(Hex).collect()),
+            (Triangle(a), Triangle(b)) => {
+                a.sight_lines(b).map(|line| line.into_iter().map(Triangle).collect())
+            }
+            _ => panic!(
+                "Cannot create sight lines for different kinds of Coords: {} vs {}",
+                self.kind(),
+                other.kind()
+            ),
+        }
+    }
+
+    fn sight_sectors(&self) -> Vec<[[i64; 2]; 2]> {
+        use Coord::*;
+        match self {
+            Square(coord) => coord.sight_sectors(),
+            Hex(coord) => coord.sight_sectors(),
+            Triangle(coord) => coord.sight_sectors(),
+        }
+    }
+
+    fn sight_row(&self, sector: usize, depth: usize) -> Vec<SightCell<Self>> {
+        use Coord::*;
+        fn lift<C>(row: Vec<SightCell<C>>, wrap: fn(C) -> Coord) -> Vec<SightCell<Coord>> {
+            row.into_iter()
+                .map(|cell| SightCell {
+                    coord: wrap(cell.coord),
+                    center: cell.center,
+                    vertices: cell.vertices,
+                })
+                .collect()
+        }
+        match self {
+            Square(coord) => lift(coord.sight_row(sector, depth), Square),
+            Hex(coord) => lift(coord.sight_row(sector, depth), Hex),
+            Triangle(coord) => lift(coord.sight_row(sector, depth), Triangle),
+        }
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 // TODO Cannot implement a dynamic version of ModuleCoord, as it currently
@@ -489,6 +776,16 @@ impl SizedGrid {
         }
     }
 
+    /// Construct a new `SizedGrid` of the given kind and inradius, laying
+    /// out hexagonal grids in the given orientation.  Other kinds of grid
+    /// only have one orientation, so it is ignored for them.
+    pub fn with_orientation(kind: Kind, inradius: f32, orientation: hex::Orientation) -> Self {
+        match kind {
+            Kind::Hex => hex::SizedGrid::with_orientation(inradius, orientation).into(),
+            _ => SizedGrid::new(kind, inradius),
+        }
+    }
+
     pub fn kind(&self) -> Kind {
         use SizedGrid::*;
         match self {
@@ -594,6 +891,42 @@ impl crate::SizedGrid for SizedGrid {
         }
     }
 
+    fn angle_to_direction(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        angle: f32,
+    ) -> Direction {
+        use SizedGrid::*;
+        match (self, coord) {
+            (Square(grid), Coord::Square(coord)) => grid.angle_to_direction(coord, dir_type, angle),
+            (Hex(grid), Coord::Hex(coord)) => grid.angle_to_direction(coord, dir_type, angle),
+            (Triangle(grid), Coord::Triangle(coord)) => {
+                grid.angle_to_direction(coord, dir_type, angle)
+            }
+            _ => {
+                panic!("Expected matching Coord type for SizedGrid");
+            }
+        }
+    }
+
+    fn direction_angle(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<f32> {
+        use SizedGrid::*;
+        match (self, coord) {
+            (Square(grid), Coord::Square(coord)) => grid.direction_angle(coord, dir_type, dir),
+            (Hex(grid), Coord::Hex(coord)) => grid.direction_angle(coord, dir_type, dir),
+            (Triangle(grid), Coord::Triangle(coord)) => grid.direction_angle(coord, dir_type, dir),
+            _ => {
+                panic!("Expected matching Coord type for SizedGrid");
+            }
+        }
+    }
+
     fn screen_rect_to_grid(
         &self,
         min: crate::Point,
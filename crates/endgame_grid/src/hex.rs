@@ -1,12 +1,16 @@
 use crate::shape::HashShape;
-use crate::utils::{vertices_to_edges, ModuleCoordIter};
-use crate::{AllowedCoordIterRange, Color, DirectionType, ModuleCoord, Point};
+use crate::notation::{format_label, parse_component, parse_label, parse_tuple, ParseCoordError};
+use crate::utils::{vertices_to_edges, Cover, ModuleCoordIter};
+use crate::visibility::SightCell;
+use crate::{AllowedCoordIterRange, Color, DirectionType, Metric, ModuleCoord, Point};
 use endgame_direction::{Direction, DirectionSet};
 use glam::{ivec2, IVec2, IVec3, Mat2, Vec2, Vec3, Vec3Swizzles};
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32::consts::{PI, TAU};
 use std::fmt::Display;
+use std::str::FromStr;
 use std::ops::Neg;
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -33,6 +37,76 @@ impl Display for Axes {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The offset coordinate schemes for hexagonal grids, as described by
+/// <https://www.redblobgames.com/grids/hexagons/#coordinates-offset>.
+/// Each scheme arranges the coordinates into rows and columns, shifting
+/// either every other row or every other column by half a hexagon.
+///
+/// The row based schemes suit pointy-topped layouts, and the column based
+/// schemes flat-topped layouts.  Rows and columns increase in the same
+/// directions as the axial r and q coordinates respectively.  As the y-axis
+/// points upwards, the shifted columns in the column based schemes are
+/// shifted upwards, rather than downwards as on screens where the y-axis
+/// points downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OffsetScheme {
+    /// Odd rows are shifted by half a hexagon in the direction of
+    /// increasing columns.
+    OddR,
+    /// Even rows are shifted by half a hexagon in the direction of
+    /// increasing columns.
+    EvenR,
+    /// Odd columns are shifted by half a hexagon in the direction of
+    /// increasing rows.
+    OddQ,
+    /// Even columns are shifted by half a hexagon in the direction of
+    /// increasing rows.  This is the scheme used for array offsets.
+    EvenQ,
+}
+
+impl Display for OffsetScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use OffsetScheme::*;
+        let str = match self {
+            OddR => "odd-r",
+            EvenR => "even-r",
+            OddQ => "odd-q",
+            EvenQ => "even-q",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+/// The doubled coordinate schemes for hexagonal grids, as described by
+/// <https://www.redblobgames.com/grids/hexagons/#coordinates-doubled>.
+/// Rather than shifting alternate rows or columns, one of the components is
+/// doubled, so that only coordinates whose components sum to an even number
+/// are valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DoubledScheme {
+    /// Columns advance by two between horizontal neighbours, suiting
+    /// pointy-topped layouts.
+    Width,
+    /// Rows advance by two between vertical neighbours, suiting
+    /// flat-topped layouts.
+    Height,
+}
+
+impl Display for DoubledScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use DoubledScheme::*;
+        let str = match self {
+            Width => "doubled-width",
+            Height => "doubled-height",
+        };
+        write!(f, "{}", str)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// For a hexagonal grid, it is possible to move in the same face directions
 /// from any coordinate.
 const ALLOWED_FACE_DIRECTIONS: DirectionSet = {
@@ -51,9 +125,12 @@ const ALLOWED_VERTEX_DIRECTIONS: DirectionSet = {
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 /// This implementation is based upon the axial coordinate system as described
 /// by <https://www.redblobgames.com/grids/hexagons/>.
-/// It uses a flat-topped hexagonal grid with even-q offset coordinates.
+/// It uses a flat-topped hexagonal grid with even-q offset coordinates for
+/// array offsets.  Conversions for the other offset and doubled coordinate
+/// schemes are provided by `from_offset`, `to_offset`, `from_doubled` and
+/// `to_doubled`.  The `Direction`s of the coordinates assume a flat-topped
+/// layout, while a `SizedGrid` may also be laid out pointy-topped.
 ///
-// TODO Add support for pointy top orientation?
 // IVec2::x is the same as the axial q and IVec2::y is the axial r.
 pub struct Coord(glam::IVec2);
 
@@ -98,6 +175,77 @@ impl Coord {
         Coord(ivec2(coord.x, coord.z))
     }
 
+    /// Construct a new `Coord` from a column and row, as the x and y
+    /// components respectively, in the given offset scheme.
+    pub const fn from_offset(scheme: OffsetScheme, offset: IVec2) -> Self {
+        use OffsetScheme::*;
+        let (col, row) = (offset.x, offset.y);
+        match scheme {
+            OddR => Coord::new(col - (row - (row & 1)) / 2, row),
+            EvenR => Coord::new(col - (row + (row & 1)) / 2, row),
+            OddQ => Coord::new(col, row - (col - (col & 1)) / 2),
+            EvenQ => Coord::new(col, row - (col + (col & 1)) / 2),
+        }
+    }
+
+    /// Convert the coordinate to a column and row, as the x and y components
+    /// respectively, in the given offset scheme.
+    pub const fn to_offset(&self, scheme: OffsetScheme) -> IVec2 {
+        use OffsetScheme::*;
+        let (q, r) = (self.0.x, self.0.y);
+        match scheme {
+            OddR => ivec2(q + (r - (r & 1)) / 2, r),
+            EvenR => ivec2(q + (r + (r & 1)) / 2, r),
+            OddQ => ivec2(q, r + (q - (q & 1)) / 2),
+            EvenQ => ivec2(q, r + (q + (q & 1)) / 2),
+        }
+    }
+
+    /// Produce a label for the coordinate from its column and row in the
+    /// given offset scheme, with the column lettered and the row numbered
+    /// from one.  So the origin is `a1` in every scheme.  Coordinates with a
+    /// negative column or row have no label.
+    pub fn to_label(&self, scheme: OffsetScheme) -> Option<String> {
+        let offset = self.to_offset(scheme);
+        format_label(offset.x, offset.y)
+    }
+
+    /// Parse a label in the given offset scheme, as produced by `to_label`.
+    /// Columns may be lettered in either case.
+    pub fn from_label(scheme: OffsetScheme, label: &str) -> Result<Self, ParseCoordError> {
+        let (col, row) = parse_label(label)?;
+        Ok(Coord::from_offset(scheme, ivec2(col, row)))
+    }
+
+    /// Construct a new `Coord` from a column and row, as the x and y
+    /// components respectively, in the given doubled scheme.  Panics if the
+    /// components do not sum to an even number.
+    pub fn from_doubled(scheme: DoubledScheme, doubled: IVec2) -> Self {
+        use DoubledScheme::*;
+        assert_eq!(
+            doubled.element_sum().rem_euclid(2),
+            0,
+            "Doubled coordinates must sum to an even number, got {}",
+            doubled
+        );
+        let (col, row) = (doubled.x, doubled.y);
+        match scheme {
+            Width => Coord::new((col - row) / 2, row),
+            Height => Coord::new(col, (row - col) / 2),
+        }
+    }
+
+    /// Convert the coordinate to a column and row, as the x and y components
+    /// respectively, in the given doubled scheme.
+    pub const fn to_doubled(&self, scheme: DoubledScheme) -> IVec2 {
+        use DoubledScheme::*;
+        let (q, r) = (self.0.x, self.0.y);
+        match scheme {
+            Width => ivec2(2 * q + r, r),
+            Height => ivec2(q, 2 * r + q),
+        }
+    }
+
     /// Helper function for rounding floating point hex axial coordinates to
     /// the nearest integral hex axial coordinate.
     fn hex_round(cube: Vec3) -> IVec3 {
@@ -125,6 +273,41 @@ impl Coord {
         IVec3::new(rx as i32, ry as i32, rz as i32)
     }
 
+    /// The positions of the center of the coordinate along the lanes of a
+    /// finer triangular lattice, as walked by lines between coordinates.
+    /// The edges of each hexagon, along with the spokes from its center to
+    /// its vertices, are all grid lines of the finer lattice.
+    pub(crate) fn fine_lanes(&self) -> [i64; 3] {
+        let cube = self.to_cubical().as_i64vec3();
+        [cube.y - cube.z, cube.z - cube.x, cube.x - cube.y]
+    }
+
+    /// Do the indices along the lanes describe a triangle of the finer
+    /// triangular lattice?
+    pub(crate) fn is_fine_triangle([u, v, w]: &[i64; 3]) -> bool {
+        matches!(u + v + w, 1 | 2)
+    }
+
+    /// Find the coordinate containing a triangle of the finer triangular
+    /// lattice.  Each fine triangle lies within a single hexagon, and the
+    /// center of that hexagon is the one vertex of the triangle whose first
+    /// two lanes agree modulo three.
+    pub(crate) fn from_fine_triangle([u, v, w]: [i64; 3]) -> Self {
+        let vertices = if u + v + w == 2 {
+            [[u, v - 1, w - 1], [u - 1, v, w - 1], [u - 1, v - 1, w]]
+        } else {
+            [[u, v, w - 1], [u, v - 1, w], [u - 1, v, w]]
+        };
+        let [u, v, w] = vertices
+            .into_iter()
+            .find(|[u, v, _]| (u - v).rem_euclid(3) == 0)
+            .expect("Exactly one vertex should be the center of a hexagon");
+        let z = (v - u) / 3;
+        let y = u + z;
+        let x = w + y;
+        Coord::from_cubical(IVec3::new(x as i32, y as i32, z as i32))
+    }
+
     pub fn ring(radius: usize) -> HashShape<Coord> {
         if radius == 0 {
             return HashShape::from([Coord::default()]);
@@ -160,6 +343,69 @@ impl Coord {
 
         HashShape::from_iter(coords.into_iter())
     }
+
+    /// Produce a rectangle of the given `width` and `height`, with the
+    /// origin in one corner.  Each of the `width` columns of hexagons runs
+    /// along the `Q` axis, with alternate columns offset by half a hexagon,
+    /// such that the hexagons lie within a rectangle in screen space.
+    pub fn rectangle(width: usize, height: usize) -> HashShape<Coord> {
+        let mut coords = Vec::new();
+        for q in 0..width as i32 {
+            // Shift each column back to compensate for the slant of the
+            // `R` axis.
+            let offset = q >> 1;
+            for r in -offset..height as i32 - offset {
+                coords.push(Coord::new(q, r));
+            }
+        }
+        HashShape::from_iter(coords)
+    }
+
+    /// Produce a parallelogram extending from the origin in the positive
+    /// direction along each of the two given axes, with `first_len`
+    /// coordinates along the `first` axis and `second_len` along the
+    /// `second`.
+    pub fn parallelogram(
+        first: Axes,
+        first_len: usize,
+        second: Axes,
+        second_len: usize,
+    ) -> HashShape<Coord> {
+        crate::utils::parallelogram(Coord::default(), first, first_len, second, second_len)
+    }
+
+    /// Produce a triangle with sides of `size` coordinates, with the origin
+    /// in one corner.
+    pub fn triangle(size: usize) -> HashShape<Coord> {
+        let isize = size as i32;
+        let mut coords = Vec::new();
+        for q in 0..isize {
+            for r in 0..isize - q {
+                coords.push(Coord::new(q, r));
+            }
+        }
+        HashShape::from_iter(coords)
+    }
+
+    /// Produce a hexagon of the given `radius` centered on the origin.  On
+    /// hexagonal grids this is the same as the `range`.
+    pub fn hexagon(radius: usize) -> HashShape<Coord> {
+        Coord::range(radius)
+    }
+
+    /// Produce the coordinates at the given `radius` from the origin under
+    /// the given `Metric`.  With `Metric::Face` this is the same as `ring`.
+    pub fn metric_ring(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_ring(Coord::default(), metric, radius)
+    }
+
+    /// Produce the coordinates within the given `radius` of the origin under
+    /// the given `Metric`.  These form a hexagon the same as `range` with
+    /// `Metric::Face`, a larger hexagon rotated by thirty degrees with
+    /// `Metric::Vertex`, and a disc with `Metric::Euclidean`.
+    pub fn metric_range(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_range(Coord::default(), metric, radius)
+    }
 }
 
 impl Default for Coord {
@@ -174,6 +420,17 @@ impl Display for Coord {
     }
 }
 
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    /// Parse an axial coordinate of the form `(q,r)`, as produced by
+    /// `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [q, r] = parse_tuple(s, "a hex coordinate of the form (q,r)")?;
+        Ok(Coord::new(parse_component(q)?, parse_component(r)?))
+    }
+}
+
 impl std::ops::Neg for Coord {
     type Output = Self;
 
@@ -281,6 +538,20 @@ impl crate::Coord for Coord {
         (diff.x.abs() + diff.y.abs() + (diff.x + diff.y).abs()) as usize / 2
     }
 
+    fn vertex_distance(&self, other: &Self) -> usize {
+        // Each vertex step changes the difference between a pair of
+        // cubical components by three, and the others by at most that.
+        let diff = other.to_cubical() - self.to_cubical();
+        let spread = (diff - diff.yzx()).abs().max_element();
+        (spread + 2) as usize / 3
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        // The two axes of the stored coordinates are sixty degrees apart.
+        let diff = (other.0 - self.0).as_vec2();
+        (diff.x * diff.x + diff.x * diff.y + diff.y * diff.y).sqrt()
+    }
+
     fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
         // We can ignore the coordinate, as angle to direction mapping
         // is the same for any coordinate.
@@ -394,6 +665,20 @@ impl crate::Coord for Coord {
         )
     }
 
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        // Hexagons that are vertex neighbours do not touch, so the line
+        // is the same as the path.
+        self.path_iterator(other)
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        let valid = |lanes: &[i64; 3]| Coord::is_fine_triangle(lanes);
+        crate::utils::lane_walk(self.fine_lanes(), other.fine_lanes(), 1, valid, Cover::Super)
+            .into_iter()
+            .map(Coord::from_fine_triangle)
+            .unique()
+    }
+
     fn axis_iterator<RB: AllowedCoordIterRange>(
         &self,
         axis: Axes,
@@ -508,6 +793,115 @@ impl ModuleCoord for Coord {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+impl crate::topology::WrappingCoord for Coord {
+    const COLUMN_MULTIPLE: usize = 2;
+}
+
+impl crate::canonical::CanonicalCoord for Coord {
+    fn translate_from(&self, anchor: &Self) -> Self {
+        *self - *anchor
+    }
+}
+
+impl crate::symmetry::SymmetricCoord for Coord {
+    const ROTATIONS: usize = 6;
+    const REFLECTION_AXIS: Axes = Axes::Q;
+
+    fn reflection_steps(axis: Axes) -> usize {
+        use Axes::*;
+        match axis {
+            Q => 0,
+            R => 4,
+            S => 2,
+        }
+    }
+}
+
+impl crate::text::TextCoord for Coord {
+    // Hexagons are drawn pointy-topped, so rows follow the odd-r offset
+    // scheme, with odd rows shifted right by half a hexagon.
+    fn to_text_offset(&self) -> (i32, i32) {
+        let offset = self.to_offset(OffsetScheme::OddR);
+        (offset.x, offset.y)
+    }
+
+    fn from_text_offset(offset: (i32, i32)) -> Self {
+        Coord::from_offset(OffsetScheme::OddR, ivec2(offset.0, offset.1))
+    }
+
+    fn indent(row: i32) -> usize {
+        row.rem_euclid(2) as usize
+    }
+}
+
+impl crate::parts::PartCoord for Coord {
+    fn boundary(&self) -> Vec<(Direction, Direction)> {
+        use Direction::*;
+        vec![
+            (NorthWest, North),
+            (NorthEast, NorthEast),
+            (East, SouthEast),
+            (SouthEast, South),
+            (SouthWest, SouthWest),
+            (West, NorthWest),
+        ]
+    }
+}
+
+impl crate::visibility::SightCoord for Coord {
+    fn sight_lines(&self, other: &Self) -> [Vec<Self>; 2] {
+        let (start, end) = (self.fine_lanes(), other.fine_lanes());
+        let valid = |lanes: &[i64; 3]| Coord::is_fine_triangle(lanes);
+        let shift = crate::utils::triangular_shift(std::array::from_fn(|k| end[k] - start[k]));
+        [shift, shift.map(|s| -s)].map(|shift| {
+            crate::utils::lane_walk(start, end, 1, valid, Cover::Displaced(shift))
+                .into_iter()
+                .map(Coord::from_fine_triangle)
+                .unique()
+                .collect()
+        })
+    }
+
+    fn sight_sectors(&self) -> Vec<[[i64; 2]; 2]> {
+        vec![[[2, -1], [1, 1]]; 6]
+    }
+
+    fn sight_row(&self, sector: usize, depth: usize) -> Vec<SightCell<Self>> {
+        // Each sextant is scanned as if it lay about the first axis of the
+        // stored coordinates, between the vertex directions either side,
+        // and then turned into place.  Segments within it only ever step
+        // to the neighbours at (1, 0), (0, 1) and (1, -1), so the rows are
+        // where twice the first component plus the second is constant, and
+        // no segment crosses two coordinates of the same row.  Scaled by
+        // three, the vertices of each hexagon lie at integer positions.
+        let n = depth as i32 + 2;
+        let extent = (n + 2) / 3;
+        (-extent..=extent)
+            .filter(|r| (n - r) % 2 == 0)
+            .map(|r| {
+                let q = (n - r) / 2;
+                // Turning by a sixth takes (q, r) to (-r, q + r).
+                let offset = (0..sector).fold(ivec2(q, r), |o, _| ivec2(-o.y, o.x + o.y));
+                let center = [3 * q as i64, 3 * r as i64];
+                let vertices = [[2, -1], [1, 1], [-1, 2], [-2, 1], [-1, -1], [1, -2]];
+                SightCell {
+                    coord: Coord(self.0 + offset),
+                    center,
+                    vertices: vertices.map(|[x, y]| [center[0] + x, center[1] + y]).to_vec(),
+                }
+            })
+            .collect()
+    }
+}
+
+impl crate::ArrayOffsetCoord for Coord {
+    fn array_offset_to_grid(array_offset: (isize, isize)) -> Self {
+        Coord::array_offset_to_grid(array_offset)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug, Clone)]
 pub struct HexLineIter {
     start: Vec3,
@@ -552,24 +946,78 @@ impl Iterator for HexLineIter {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// The orientations in which a hexagonal grid may be laid out in screen
+/// space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Orientation {
+    /// Hexagons have a flat edge at the top, with neighbours directly above
+    /// and below.  This is the orientation assumed by the `Direction`s of
+    /// `Coord`.
+    #[default]
+    FlatTop,
+    /// Hexagons have a vertex at the top, with neighbours directly to the
+    /// left and right.  The layout is that of `FlatTop` rotated clockwise
+    /// by thirty degrees, so that the rows of the axial r coordinate are
+    /// horizontal.
+    PointyTop,
+}
+
+impl Orientation {
+    /// The rotation in radians from the `FlatTop` layout to this
+    /// orientation.
+    pub fn rotation(&self) -> f32 {
+        match self {
+            Orientation::FlatTop => 0.0,
+            Orientation::PointyTop => -PI / 6.0,
+        }
+    }
+}
+
+impl Display for Orientation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Orientation::*;
+        match self {
+            FlatTop => write!(f, "FlatTop"),
+            PointyTop => write!(f, "PointyTop"),
+        }
+    }
+}
+
 /// Regular hexagonal grids with cells of specific size.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub struct SizedGrid {
     inradius: f32,
+    orientation: Orientation,
 }
 
 impl SizedGrid {
-    /// Construct a new `HexSizedGrid` with the given inradius.
+    /// Construct a new `HexSizedGrid` with the given inradius, in the
+    /// `FlatTop` orientation.
     pub fn new(inradius: f32) -> Self {
-        SizedGrid { inradius }
+        SizedGrid::with_orientation(inradius, Orientation::FlatTop)
+    }
+
+    /// Construct a new `HexSizedGrid` with the given inradius and
+    /// orientation.
+    pub fn with_orientation(inradius: f32, orientation: Orientation) -> Self {
+        SizedGrid {
+            inradius,
+            orientation,
+        }
+    }
+
+    /// The orientation in which the grid is laid out.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
     }
 
     /// The conversion matrix from hex axial coordinates to screen space.
-    // TODO Allow this to be constant?
-    fn conversion_matrix() -> Mat2 {
+    fn conversion_matrix(&self) -> Mat2 {
+        let rotation = self.orientation.rotation();
         Mat2::from_cols(
-            Vec2::from_angle(PI / 6.0f32) * 3.0f32.sqrt(),
-            Vec2::from_angle(PI / 2.0f32) * 3.0f32.sqrt(),
+            Vec2::from_angle(PI / 6.0f32 + rotation) * 3.0f32.sqrt(),
+            Vec2::from_angle(PI / 2.0f32 + rotation) * 3.0f32.sqrt(),
         )
     }
 }
@@ -592,26 +1040,38 @@ impl crate::SizedGrid for SizedGrid {
 
     fn vertices(&self, coord: &Self::Coord) -> Vec<Point> {
         let center = self.grid_to_screen(coord);
+        // Start from the first vertex with a non-negative angle, so that the
+        // vertices are ordered by angle about the center.
+        let start = match self.orientation {
+            Orientation::FlatTop => 0.0,
+            Orientation::PointyTop => PI / 6.0,
+        };
         (0..6)
-            .map(|i| center + Vec2::from_angle(i as f32 * PI / 3.0) * self.circumradius())
+            .map(|i| {
+                center + Vec2::from_angle(start + i as f32 * PI / 3.0) * self.circumradius()
+            })
             .collect()
     }
 
     fn edges(&self, coord: &Self::Coord) -> HashMap<Direction, (Point, Point)> {
         use Direction::*;
+        let directions = match self.orientation {
+            Orientation::FlatTop => [NorthEast, North, NorthWest, SouthWest, South, SouthEast],
+            Orientation::PointyTop => [North, NorthWest, SouthWest, South, SouthEast, NorthEast],
+        };
         HashMap::from_iter(
-            [NorthEast, North, NorthWest, SouthWest, South, SouthEast]
+            directions
                 .into_iter()
                 .zip(vertices_to_edges(self.vertices(coord).as_slice())),
         )
     }
 
     fn grid_to_screen(&self, coord: &Self::Coord) -> Point {
-        self.circumradius() * Self::conversion_matrix() * coord.0.as_vec2()
+        self.circumradius() * self.conversion_matrix() * coord.0.as_vec2()
     }
 
     fn screen_to_grid(&self, point: Point) -> Self::Coord {
-        let grid = Self::conversion_matrix().inverse() * point / self.circumradius();
+        let grid = self.conversion_matrix().inverse() * point / self.circumradius();
         Coord::from_cubical(Coord::hex_round(Vec3::new(
             grid.x,
             -grid.x - grid.y,
@@ -628,32 +1088,83 @@ impl crate::SizedGrid for SizedGrid {
             return None;
         };
 
+        use Direction::*;
+        let step = |coord: Coord, dir: Direction| {
+            <Coord as crate::Coord>::move_in_direction(&coord, DirectionType::Face, dir)
+                .expect("Face directions should always be possible for a hexagonal grid.")
+        };
         let mut min_coord = self.screen_to_grid(min);
         let mut max_coord = self.screen_to_grid(max);
-        // Expand to ensure full coverage of the rectangle.
-        min_coord = <Coord as crate::Coord>::move_in_direction(
-            &min_coord,
-            DirectionType::Face,
-            Direction::SouthWest,
-        )
-            .expect("Moving SouthEast should always be possible for a hexagonal grid.");
-        max_coord = <Coord as crate::Coord>::move_in_direction(
-            &max_coord,
-            DirectionType::Face,
-            Direction::NorthEast,
+        match self.orientation {
+            Orientation::FlatTop => {
+                // Expand to ensure full coverage of the rectangle.
+                min_coord = step(min_coord, SouthWest);
+                max_coord = step(max_coord, NorthEast);
+
+                // Rows zigzag to the right, while staying level.
+                Some(GridIterator {
+                    min,
+                    max,
+                    row_coord: min_coord,
+                    current_coord: min_coord,
+                    end_r: max_coord.0.y + ((max_coord.0.x - min_coord.0.x) / 2) + 1,
+                    row_index: 0,
+                    row_length: (max_coord.0.x - min_coord.0.x + 1) as usize,
+                    row_steps: [SouthEast, NorthEast],
+                    row_number: 0,
+                    row_advances: [North, North],
+                    sized_grid: *self,
+                })
+            }
+            Orientation::PointyTop => {
+                // Expand to ensure full coverage of the rectangle, moving
+                // left and down, or right and up, in screen space.
+                min_coord = step(step(min_coord, SouthWest), South);
+                max_coord = step(step(max_coord, NorthEast), North);
+
+                // Rows are level, but successive rows zigzag upwards.  The
+                // doubled width column is the horizontal position in units of
+                // half a hexagon.
+                let width = max_coord.to_doubled(DoubledScheme::Width).x
+                    - min_coord.to_doubled(DoubledScheme::Width).x;
+                Some(GridIterator {
+                    min,
+                    max,
+                    row_coord: min_coord,
+                    current_coord: min_coord,
+                    end_r: max_coord.0.y,
+                    row_index: 0,
+                    row_length: (width / 2 + 2) as usize,
+                    row_steps: [NorthEast, NorthEast],
+                    row_number: 0,
+                    row_advances: [NorthWest, North],
+                    sized_grid: *self,
+                })
+            }
+        }
+    }
+
+    fn angle_to_direction(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        angle: f32,
+    ) -> Direction {
+        <Coord as crate::Coord>::angle_to_direction(
+            coord,
+            dir_type,
+            angle - self.orientation.rotation(),
         )
-            .expect("Moving NorthWest should always be possible for a hexagonal grid.");
-
-        Some(GridIterator {
-            min: min,
-            max: max,
-            row_coord: min_coord,
-            current_coord: min_coord,
-            end_r: max_coord.0.y + ((max_coord.0.x - min_coord.0.x) / 2) + 1,
-            row_index: 0,
-            row_length: (max_coord.0.x - min_coord.0.x + 1) as usize,
-            sized_grid: self.clone(),
-        })
+    }
+
+    fn direction_angle(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<f32> {
+        <Coord as crate::Coord>::direction_angle(coord, dir_type, dir)
+            .map(|angle| (angle + self.orientation.rotation()).rem_euclid(TAU))
     }
 }
 
@@ -667,6 +1178,11 @@ struct GridIterator {
     end_r: i32,
     row_index: usize,
     row_length: usize,
+    /// The directions alternately taken to advance along a row.
+    row_steps: [Direction; 2],
+    row_number: usize,
+    /// The directions alternately taken to advance to the next row.
+    row_advances: [Direction; 2],
     sized_grid: SizedGrid,
 }
 
@@ -683,11 +1199,7 @@ impl Iterator for GridIterator {
             let c = self.current_coord;
 
             // Advance to the next coordinate.
-            let dir = if self.row_index % 2 == 0 {
-                Direction::SouthEast
-            } else {
-                Direction::NorthEast
-            };
+            let dir = self.row_steps[self.row_index % 2];
             self.current_coord = <Coord as crate::Coord>::move_in_direction(
                 &self.current_coord,
                 DirectionType::Face,
@@ -700,9 +1212,10 @@ impl Iterator for GridIterator {
                 self.row_coord = <Coord as crate::Coord>::move_in_direction(
                     &self.row_coord,
                     DirectionType::Face,
-                    Direction::North,
+                    self.row_advances[self.row_number % 2],
                 )
                     .expect("Direction should be valid");
+                self.row_number += 1;
                 self.row_index = 0;
                 self.current_coord = self.row_coord;
             }
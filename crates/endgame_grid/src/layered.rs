@@ -0,0 +1,417 @@
+//! Grids stacked in levels, such as the floors of a dungeon.
+//!
+//! `Coord` wraps the coordinates of any planar grid with an integer level.
+//! Movement in `Direction`s, along with rotation and reflection, remains
+//! within a level, while `LayeredCoord::move_vertically` moves directly up
+//! or down between levels, like taking a staircase.  Distances account for
+//! the levels travelled, with the levels spaced as far apart as the centers
+//! of coordinates neighbouring across a face.
+//!
+//! As the searches of `pathfinding` and `connectivity` step in
+//! `Direction`s, they only explore a single level.
+//!
+//! For true three dimensional lattices, where every step is a translation,
+//! see `cubic::Coord` and `prism::Coord`.
+
+use crate::shape::{HashShape, HashShapeContainer};
+use crate::notation::ParseCoordError;
+use crate::{AllowedCoordIterRange, Color, DirectionType, Shape, ShapeContainer};
+use endgame_direction::{Direction, DirectionSet};
+use itertools::Either;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The two ways of moving between levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Vertical {
+    Up,
+    Down,
+}
+
+impl Vertical {
+    /// The change in level from moving in this direction.
+    pub fn step(self) -> i32 {
+        match self {
+            Vertical::Up => 1,
+            Vertical::Down => -1,
+        }
+    }
+}
+
+impl Display for Vertical {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Vertical::*;
+        match self {
+            Up => write!(f, "Up"),
+            Down => write!(f, "Down"),
+        }
+    }
+}
+
+impl std::ops::Not for Vertical {
+    type Output = Self;
+
+    /// Produce the opposite `Vertical` direction.
+    fn not(self) -> Self::Output {
+        use Vertical::*;
+        match self {
+            Up => Down,
+            Down => Up,
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Coordinates that combine the coordinate of a planar grid with a level.
+pub trait LayeredCoord: crate::Coord {
+    /// The type of the coordinates within a single level.
+    type Planar: crate::Coord;
+
+    /// The coordinate within the level.
+    fn planar(&self) -> Self::Planar;
+
+    /// The level of the coordinate, increasing upwards.
+    fn level(&self) -> i32;
+
+    /// Construct the coordinate at the given planar coordinate and level.
+    fn from_planar(planar: Self::Planar, level: i32) -> Self;
+
+    /// Produce the coordinate directly above or below this one.
+    fn move_vertically(&self, dir: Vertical) -> Self {
+        Self::from_planar(self.planar(), self.level() + dir.step())
+    }
+}
+
+/// Extract the values of a single level of a `ShapeContainer` into a
+/// container of planar coordinates.
+pub fn layer<LC: LayeredCoord, V, SC>(
+    container: &SC,
+    level: i32,
+) -> HashShapeContainer<LC::Planar, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    SC: ShapeContainer<LC, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    container
+        .iter()
+        .filter(|(coord, _)| coord.level() == level)
+        .map(|(coord, value)| (coord.planar(), value.clone()))
+        .collect()
+}
+
+/// Combine containers of planar coordinates, each paired with its level,
+/// into a single container.  This is the inverse of taking the `layer` at
+/// each level.
+pub fn stack<LC: LayeredCoord, V, I, SC>(layers: I) -> SC
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    I: IntoIterator<Item=(i32, HashShapeContainer<LC::Planar, V>)>,
+    SC: FromIterator<(LC, V)>,
+{
+    layers
+        .into_iter()
+        .flat_map(|(level, container)| {
+            container
+                .into_iter()
+                .map(move |(planar, value)| (LC::from_planar(planar, level), value))
+        })
+        .collect()
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The axes of a layered grid are those of the planar grid, along with
+/// the vertical axis between levels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Axes<A> {
+    Planar(A),
+    Level,
+}
+
+impl<A: Display> Display for Axes<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Axes::*;
+        match self {
+            Planar(axis) => write!(f, "{}", axis),
+            Level => write!(f, "Level"),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A coordinate of a planar grid on a given level.
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Coord<C: crate::Coord> {
+    planar: C,
+    level: i32,
+}
+
+impl<C: crate::Coord> Coord<C> {
+    /// Construct a new `Coord` from a planar coordinate and a level.
+    pub const fn new(planar: C, level: i32) -> Self {
+        Coord { planar, level }
+    }
+
+    /// Produce the ring of coordinates at the given `radius` from the
+    /// origin, given a function producing the rings of the planar grid.
+    /// Each level contributes the planar ring whose radius is what remains
+    /// after travelling to that level.
+    pub fn ring(radius: usize, planar_ring: impl Fn(usize) -> HashShape<C>) -> HashShape<Self> {
+        Coord::stack_shapes(radius, planar_ring)
+    }
+
+    /// Produce the coordinates within the given `radius` of the origin,
+    /// given a function producing the ranges of the planar grid.  Each
+    /// level contributes the planar range whose radius is what remains
+    /// after travelling to that level.
+    pub fn range(radius: usize, planar_range: impl Fn(usize) -> HashShape<C>) -> HashShape<Self> {
+        Coord::stack_shapes(radius, planar_range)
+    }
+
+    /// Internal helper for combining planar shapes, shrinking with the
+    /// distance of each level from the origin.
+    fn stack_shapes(radius: usize, planar: impl Fn(usize) -> HashShape<C>) -> HashShape<Self> {
+        let iradius = radius as i32;
+        let mut coords = Vec::new();
+        for level in -iradius..=iradius {
+            let shape = planar(radius - level.unsigned_abs() as usize);
+            coords.extend(shape.iter().map(|coord| Coord::new(coord.clone(), level)));
+        }
+        HashShape::from_iter(coords)
+    }
+}
+
+impl<C: crate::Coord> LayeredCoord for Coord<C> {
+    type Planar = C;
+
+    fn planar(&self) -> C {
+        self.planar.clone()
+    }
+
+    fn level(&self) -> i32 {
+        self.level
+    }
+
+    fn from_planar(planar: C, level: i32) -> Self {
+        Coord::new(planar, level)
+    }
+}
+
+impl<C: crate::Coord> Display for Coord<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}@{}", self.planar, self.level)
+    }
+}
+
+impl<C> FromStr for Coord<C>
+where
+    C: crate::Coord + FromStr<Err=ParseCoordError>,
+{
+    type Err = ParseCoordError;
+
+    /// Parse a coordinate of the form `planar@level`, as produced by
+    /// `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (planar, level) = s.rsplit_once('@').ok_or_else(|| ParseCoordError::Syntax {
+            text: s.to_string(),
+            expected: "a layered coordinate of the form planar@level",
+        })?;
+        let level = crate::notation::parse_component(level.trim())?;
+        Ok(Coord::new(planar.parse()?, level))
+    }
+}
+
+impl<C: crate::Coord> crate::Coord for Coord<C> {
+    type Axes = Axes<C::Axes>;
+
+    fn is_origin(&self) -> bool {
+        self.planar.is_origin() && self.level == 0
+    }
+
+    fn distance(&self, other: &Self) -> usize {
+        self.planar.distance(&other.planar) + self.level.abs_diff(other.level) as usize
+    }
+
+    fn vertex_distance(&self, other: &Self) -> usize {
+        // Diagonal steps remain within a level, so do not shorten the
+        // climb between levels.
+        self.planar.vertex_distance(&other.planar) + self.level.abs_diff(other.level) as usize
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        let planar = self.planar.euclidean_distance(&other.planar);
+        planar.hypot((other.level - self.level) as f32)
+    }
+
+    fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
+        self.planar.angle_to_direction(dir_type, angle)
+    }
+
+    fn direction_angle(&self, dir_type: DirectionType, dir: Direction) -> Option<f32> {
+        self.planar.direction_angle(dir_type, dir)
+    }
+
+    fn move_in_direction(&self, dir_type: DirectionType, dir: Direction) -> Option<Self> {
+        let planar = self.planar.move_in_direction(dir_type, dir)?;
+        Some(Coord::new(planar, self.level))
+    }
+
+    fn move_on_axis(&self, axis: Self::Axes, positive: bool) -> Self {
+        match axis {
+            Axes::Planar(axis) => Coord::new(self.planar.move_on_axis(axis, positive), self.level),
+            Axes::Level => {
+                let dir = if positive { Vertical::Up } else { Vertical::Down };
+                self.move_vertically(dir)
+            }
+        }
+    }
+
+    fn direction_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        dir_type: DirectionType,
+        dir: Direction,
+        range: RB,
+    ) -> impl Iterator<Item=Self> {
+        let level = self.level;
+        self.planar
+            .direction_iterator(dir_type, dir, range)
+            .map(move |planar| Coord::new(planar, level))
+    }
+
+    fn path_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        let planar: Vec<C> = self.planar.path_iterator(&other.planar).collect();
+        interleave(planar, self.level, other.level)
+            .into_iter()
+            .map(|(planar, level)| Coord::new(planar, level))
+    }
+
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        let planar: Vec<C> = self.planar.line_iterator(&other.planar).collect();
+        interleave(planar, self.level, other.level)
+            .into_iter()
+            .map(|(planar, level)| Coord::new(planar, level))
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        // As the planar coordinates do not reveal where the segment crosses
+        // between them, conservatively include every level between the two
+        // for each planar coordinate, ordered by their progress along the
+        // segment.
+        let planar: Vec<C> = self.planar.supercover_iterator(&other.planar).collect();
+        let steps = planar.len() as i64 - 1;
+        let climb = self.level.abs_diff(other.level) as i64;
+        let step = (other.level - self.level).signum();
+        let mut coords = Vec::new();
+        for (index, coord) in planar.into_iter().enumerate() {
+            for rung in 0..=climb {
+                let progress = index as i64 * climb + rung * steps;
+                let level = self.level + rung as i32 * step;
+                coords.push((progress, Coord::new(coord.clone(), level)));
+            }
+        }
+        coords.sort_by_key(|(progress, _)| *progress);
+        coords.into_iter().map(|(_, coord)| coord)
+    }
+
+    fn axis_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        axis: Self::Axes,
+        positive: bool,
+        range: RB,
+    ) -> impl Iterator<Item=Self> {
+        let (planar, level) = (self.planar.clone(), self.level);
+        match axis {
+            Axes::Planar(axis) => Either::Left(
+                self.planar
+                    .axis_iterator(axis, positive, range)
+                    .map(move |planar| Coord::new(planar, level)),
+            ),
+            Axes::Level => {
+                let step = if positive { 1 } else { -1 };
+                Either::Right((0..).map_while(move |index: usize| {
+                    let level = level + step * index as i32;
+                    (!range.complete(index)).then(|| Coord::new(planar.clone(), level))
+                }))
+            }
+        }
+    }
+
+    fn allowed_direction(&self, dir_type: DirectionType, dir: Direction) -> bool {
+        self.planar.allowed_direction(dir_type, dir)
+    }
+
+    fn allowed_directions(&self, dir_type: DirectionType) -> DirectionSet {
+        self.planar.allowed_directions(dir_type)
+    }
+
+    fn grid_to_array_offset(&self) -> (isize, isize) {
+        // Stack the levels far enough apart that the planar offsets of
+        // coordinates with `i32` components do not overlap.  This assumes
+        // that `isize` is 64 bits, and that levels are within ±2³⁰.
+        let (x, y) = self.planar.grid_to_array_offset();
+        (x, y + ((self.level as isize) << 33))
+    }
+
+    fn to_color(&self) -> Color {
+        // Shifting the colors on each level keeps vertical neighbours
+        // distinct, whichever of the four colors the planar grid uses.
+        let planar = self.planar.to_color() as i32 - 1;
+        let num = ((planar + self.level).rem_euclid(4) + 1) as usize;
+        num.try_into().expect("Unexpected fill color index: {num}")
+    }
+
+    fn rotate_clockwise(&self) -> Self {
+        Coord::new(self.planar.rotate_clockwise(), self.level)
+    }
+
+    fn rotate_counterclockwise(&self) -> Self {
+        Coord::new(self.planar.rotate_counterclockwise(), self.level)
+    }
+
+    fn reflect(&self, axis: Self::Axes) -> Self {
+        match axis {
+            Axes::Planar(axis) => Coord::new(self.planar.reflect(axis), self.level),
+            Axes::Level => Coord::new(self.planar.clone(), -self.level),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Combine the coordinates of a planar path with the climb from level
+/// `from` to level `to`, interleaving the vertical steps evenly along the
+/// path so that each step is either planar or vertical.
+pub(crate) fn interleave<C: Clone>(planar: Vec<C>, from: i32, to: i32) -> Vec<(C, i32)> {
+    let steps = planar.len() as i64 - 1;
+    let climb = from.abs_diff(to) as i64;
+    let step = (to - from).signum();
+    let mut coords = Vec::new();
+    let (mut index, mut level) = (0, from);
+    coords.push((planar[0].clone(), from));
+    for _ in 0..steps + climb {
+        let (moved, climbed) = (index as i64, from.abs_diff(level) as i64);
+        // Climb when the climb has made less progress than the path, by
+        // comparing the midpoints of their next steps.
+        let behind = (2 * climbed + 1) * steps < (2 * moved + 1) * climb;
+        if climbed < climb && (moved == steps || behind) {
+            level += step;
+        } else {
+            index += 1;
+        }
+        coords.push((planar[index].clone(), level));
+    }
+    coords
+}
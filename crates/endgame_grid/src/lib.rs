@@ -44,6 +44,53 @@ impl std::ops::Not for DirectionType {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// A selector for the different ways of measuring the distance between
+/// two coordinates of a grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Metric {
+    /// The number of steps in face directions, as given by
+    /// `Coord::distance`.  On square grids this is the "Manhattan"
+    /// distance.
+    #[default]
+    Face,
+    /// The number of steps in either face or vertex directions, as given by
+    /// `Coord::vertex_distance`.  On square grids this is the "Chebyshev"
+    /// distance, matching the moves of a chess king.
+    Vertex,
+    /// The straight line distance between the centers of the coordinates,
+    /// as given by `Coord::euclidean_distance`.
+    Euclidean,
+}
+
+impl Metric {
+    /// All the available metrics.
+    pub const ALL: [Metric; 3] = [Metric::Face, Metric::Vertex, Metric::Euclidean];
+
+    /// Compute the distance between the two coordinates using this metric.
+    pub fn distance<C: Coord>(self, from: &C, to: &C) -> f32 {
+        use Metric::*;
+        match self {
+            Face => from.distance(to) as f32,
+            Vertex => from.vertex_distance(to) as f32,
+            Euclidean => from.euclidean_distance(to),
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Metric::*;
+        match self {
+            Face => write!(f, "Face"),
+            Vertex => write!(f, "Vertex"),
+            Euclidean => write!(f, "Euclidean"),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// Color assignment values for grid coordinates.  The four color theorem
 /// proves that for any loopless planar graph no more colors are needed to
 /// color adjacent nodes so that no two adjacent nodes have the same color.  
@@ -109,6 +156,16 @@ pub trait Coord: PartialEq + Eq + Clone + Hash + Debug + Display + Sync + Send {
     /// traversing along the face directions of the grid.
     fn distance(&self, other: &Self) -> usize;
 
+    /// Compute the distance between the two coordinates when traversing
+    /// along either the face or vertex directions of the grid.  On square
+    /// grids this is the "Chebyshev" distance.
+    fn vertex_distance(&self, other: &Self) -> usize;
+
+    /// Compute the straight line distance between the centers of the two
+    /// coordinates, measured in units of the distance between the centers
+    /// of coordinates neighbouring across a face.
+    fn euclidean_distance(&self, other: &Self) -> f32;
+
     /// Convert an angle in radians to a `Direction` on this grid for
     /// this coordinate.  One use case would be for snapping controller
     /// input angles into the nearest `Direction`.
@@ -164,6 +221,31 @@ pub trait Coord: PartialEq + Eq + Clone + Hash + Debug + Display + Sync + Send {
     /// vertex directions.
     fn path_iterator(&self, other: &Self) -> impl Iterator<Item=Self>;
 
+    /// Produce an iterator that will step through coordinates along a
+    /// "line drawing algorithm" between this `Coord` and the `other`
+    /// `Coord`, in the style of Bresenham's algorithm.  The line produced by
+    /// the iterator will be inclusive and contain both the `self` and `other`
+    /// coordinates.
+    ///
+    /// Unlike `path_iterator`, each step may be along either a face or a
+    /// vertex direction, so the length is guaranteed to be at most one more
+    /// than the `distance` between the two coordinates.  On hexagonal grids
+    /// vertex neighbours do not touch, so this is the same as `path_iterator`.
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self>;
+
+    /// Produce an iterator that will step through every coordinate touched
+    /// by the segment between the centers of this `Coord` and the `other`
+    /// `Coord`, in order along the segment.  This is a "supercover" line:
+    /// where the segment passes exactly through a vertex, or along an edge,
+    /// all the coordinates meeting there are included.  The line produced by
+    /// the iterator will be inclusive and contain both the `self` and `other`
+    /// coordinates.
+    ///
+    /// The length is guaranteed to be at least one more than the `distance`
+    /// between the two coordinates, and the coordinates produced are the
+    /// same, up to order, if `self` and `other` are swapped.
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self>;
+
     /// Produce an iterator that will step through coordinates along the
     /// given axis, either in the positive or negative direction.
     /// The provided `RangeBounds` can be used to constrain the end
@@ -264,6 +346,21 @@ where
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// A trait for those coordinate systems that can be reconstructed from the
+/// array offsets produced by `Coord::grid_to_array_offset`.  This allows
+/// for storing values associated with coordinates in dense arrays.
+///
+/// Unlike the other coordinate systems, `dynamic::Coord` cannot satisfy
+/// this trait, as an array offset alone does not determine which kind of
+/// coordinate to produce.
+pub trait ArrayOffsetCoord: Coord {
+    /// Construct a coordinate from a pair of array offsets.  This should
+    /// be the inverse of `Coord::grid_to_array_offset`.
+    fn array_offset_to_grid(array_offset: (isize, isize)) -> Self;
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 /// `AllowedCoordIterRange` is a helper trait to constrain the type of
 /// `RangeBounds` we want to allow for `CoordIter`.
 pub trait AllowedCoordIterRange: RangeBounds<usize> {
@@ -321,6 +418,32 @@ pub trait SizedGrid {
     /// Convert a point in screen space to a `Coord`.
     fn screen_to_grid(&self, point: Point) -> Self::Coord;
 
+    /// Convert an angle in radians in screen space to a `Direction` on this
+    /// grid for the given `Coord`.  This is the same as
+    /// `Coord::angle_to_direction`, unless the grid is laid out in a
+    /// different orientation than the coordinates assume.
+    fn angle_to_direction(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        angle: f32,
+    ) -> Direction {
+        coord.angle_to_direction(dir_type, angle)
+    }
+
+    /// Convert a `Direction` to an angle in radians in screen space for the
+    /// given `Coord`.  This is the same as `Coord::direction_angle`, unless
+    /// the grid is laid out in a different orientation than the coordinates
+    /// assume.
+    fn direction_angle(
+        &self,
+        coord: &Self::Coord,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<f32> {
+        coord.direction_angle(dir_type, dir)
+    }
+
     /// Given a rectangle defined by two points in screen space,
     /// produce an iterator over the coordinates that intersect
     /// with that rectangle.
@@ -342,6 +465,34 @@ pub trait SizedGrid {
     fn coord_intersects_rect(&self, coord: &Self::Coord, min: Point, max: Point) -> bool {
         utils::convex_poly_intersects_rect(&self.vertices(coord), min, max)
     }
+
+    /// Obtain the end points of a `parts::Edge` in screen space.
+    fn edge_to_screen(&self, edge: &parts::Edge<Self::Coord>) -> (Point, Point)
+    where
+        Self::Coord: parts::PartCoord,
+    {
+        self.edges(&edge.coord())[&edge.direction()]
+    }
+
+    /// Obtain the position of a `parts::Vertex` in screen space.
+    fn vertex_to_screen(&self, vertex: &parts::Vertex<Self::Coord>) -> Point
+    where
+        Self::Coord: parts::PartCoord,
+    {
+        let coord = vertex.coord();
+        let boundary = parts::PartCoord::boundary(&coord);
+        let index = boundary
+            .iter()
+            .position(|(corner, _)| *corner == vertex.direction())
+            .expect("Vertices should only be at the corners of a cell");
+        // The vertex is where the edges before and after the corner meet.
+        let edges = self.edges(&coord);
+        let before = boundary[(index + boundary.len() - 1) % boundary.len()].1;
+        let (a, b) = edges[&before];
+        let (c, d) = edges[&boundary[index].1];
+        let gap = |p: Point| p.distance(c).min(p.distance(d));
+        if gap(a) < gap(b) { a } else { b }
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
@@ -380,12 +531,50 @@ where
     /// Are there no coordinates in this shape?
     fn is_empty(&self) -> bool;
 
+    /// The number of coordinates in this shape.
+    fn len(&self) -> usize;
+
+    /// Adds a coordinate to the shape.  Returns whether the coordinate was
+    /// newly added.
+    fn insert(&mut self, coord: C) -> bool;
+
+    /// Removes a coordinate from the shape.  Returns whether the coordinate
+    /// was present.
+    fn remove(&mut self, coord: &C) -> bool;
+
     /// Create a shape by combining the coordinates of this shape with
     /// those of the other shape.
     fn union<'a>(&'a self, other: &'a Self) -> Self
     where
         C: 'a;
 
+    /// Create a shape from the coordinates common to this shape and the
+    /// other shape.
+    fn intersection<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        self - &(self - other)
+    }
+
+    /// Create a shape from the coordinates of this shape that are not in
+    /// the other shape.  Equivalent to `self - other`.
+    fn difference<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        self - other
+    }
+
+    /// Create a shape from the coordinates that are in exactly one of this
+    /// shape and the other shape.
+    fn symmetric_difference<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        (self - other).union(&(other - self))
+    }
+
     /// Obtain an iterator over coordinates in the `Shape`.
     fn iter<'a>(&'a self) -> Self::Iterator<'a>
     where
@@ -450,12 +639,56 @@ where
     /// with the coordinate, if it exists.
     fn insert(&mut self, coord: C, value: V) -> Option<V>;
 
+    /// Removes the value associated with the given coordinate, returning
+    /// it if it was present.
+    fn remove(&mut self, coord: &C) -> Option<V>;
+
     /// Are there no coordinates in this shape?
     fn is_empty(&self) -> bool;
 
+    /// The number of coordinates with an associated value.
+    fn len(&self) -> usize;
+
     /// Strip the contents and obtain the cooresponding `Shape`.
     fn as_shape(&self) -> Self::Shape;
 
+    /// Create a container holding only those coordinates and values of this
+    /// container whose coordinates lie within the given `Shape`.
+    fn restrict(&self, shape: &Self::Shape) -> Self {
+        let mut result = self.clone();
+        let outside: Vec<C> = self
+            .iter()
+            .map(|(coord, _)| coord)
+            .filter(|coord| !shape.contains(coord))
+            .cloned()
+            .collect();
+        for coord in outside {
+            result.remove(&coord);
+        }
+        result
+    }
+
+    /// Create a container holding the coordinates and values of both this
+    /// container and the other.  Where both containers hold a value for the
+    /// same coordinate, `resolve` is called with the coordinate, the value
+    /// from this container, and the value from the other container, to
+    /// determine the merged value.
+    fn merge<F>(&self, other: &Self, mut resolve: F) -> Self
+    where
+        F: FnMut(&C, &V, &V) -> V,
+    {
+        let mut result = self.clone();
+        for (coord, value) in other.iter() {
+            match result.get_mut(coord) {
+                Some(existing) => *existing = resolve(coord, existing, value),
+                None => {
+                    result.insert(coord.clone(), value.clone());
+                }
+            }
+        }
+        result
+    }
+
     /// Obtain an iterator over coordinates and values in the `ShapeContainer`.
     fn iter<'a>(&'a self) -> Self::Iterator<'a>
     where
@@ -488,9 +721,26 @@ where
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+pub mod board;
+pub mod canonical;
+pub mod connectivity;
+pub mod cubic;
 pub mod dynamic;
 pub mod hex;
+pub mod layered;
+pub mod notation;
+pub mod outline;
+pub mod parts;
+pub mod pathfinding;
+pub mod polyform;
+pub mod prism;
 pub mod shape;
 pub mod square;
+pub mod svg;
+pub mod symmetry;
+pub mod text;
+pub mod tiling;
+pub mod topology;
 pub mod triangle;
+pub mod visibility;
 mod utils;
@@ -0,0 +1,146 @@
+//! Parsing and formatting of coordinates as text.
+//!
+//! Every coordinate type implements `FromStr` as the inverse of its
+//! `Display` implementation, so for example `"(1,-2)".parse()` produces the
+//! square or hexagonal coordinate with components one and minus two, and
+//! `"(1,-2,▲)".parse()` produces an upward facing triangle.  Whitespace
+//! around the components is ignored.  As the syntax of square and hexagonal
+//! coordinates coincides, `dynamic::Coord` instead parses coordinates
+//! tagged with their `Kind`, as in `Hex(1,-2)`, which are produced by
+//! `dynamic::Coord::tagged`.
+//!
+//! Game-style labels are also provided, combining a lettered column with a
+//! numbered row, as in `b3`.  Columns are lettered `a` through `z`, then
+//! `aa`, `ab` and so on, while rows are numbered from one.  See
+//! `square::Coord::to_label` for chess-like labels and
+//! `hex::Coord::to_label` for labelling the columns and rows of an offset
+//! scheme.
+
+use std::fmt::Display;
+use std::num::ParseIntError;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The ways in which parsing a coordinate, or one of its parts, can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCoordError {
+    /// The text did not have the expected form, which is described by
+    /// `expected`.
+    Syntax { text: String, expected: &'static str },
+    /// A component of the coordinate was not a valid integer.
+    Integer { text: String, error: ParseIntError },
+    /// The text did not name a `triangle::TrianglePoint`.
+    Point(String),
+    /// The text did not name a `dynamic::Kind`.
+    Kind(String),
+    /// The text was not a lettered column followed by a row numbered from
+    /// one, or did not fit within the range of coordinates.
+    Label(String),
+}
+
+impl Display for ParseCoordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseCoordError::*;
+        match self {
+            Syntax { text, expected } => write!(f, "Expected {expected}, got {text:?}"),
+            Integer { text, error } => write!(f, "Invalid coordinate component {text:?}: {error}"),
+            Point(text) => write!(f, "Expected a triangle point such as ▲ or ▼, got {text:?}"),
+            Kind(text) => write!(f, "Expected Square, Hex or Triangle, got {text:?}"),
+            Label(text) => write!(f, "Expected a label such as a1, got {text:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseCoordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseCoordError::Integer { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Produce the letters for the column with the given zero based index,
+/// such that `0` is `a`, `25` is `z`, `26` is `aa` and so on.
+pub fn column_letters(index: u32) -> String {
+    let mut letters = Vec::new();
+    let mut remaining = index as u64 + 1;
+    while remaining > 0 {
+        remaining -= 1;
+        letters.push((b'a' + (remaining % 26) as u8) as char);
+        remaining /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Parse the letters of a column, in either case, to its zero based index.
+/// This is the inverse of `column_letters`.
+pub fn parse_column_letters(text: &str) -> Result<u32, ParseCoordError> {
+    let error = || ParseCoordError::Label(text.to_string());
+    if text.is_empty() {
+        return Err(error());
+    }
+    let mut value: u64 = 0;
+    for c in text.chars() {
+        if !c.is_ascii_alphabetic() {
+            return Err(error());
+        }
+        let digit = (c.to_ascii_lowercase() as u8 - b'a') as u64 + 1;
+        value = value.checked_mul(26).and_then(|v| v.checked_add(digit)).ok_or_else(error)?;
+    }
+    u32::try_from(value - 1).map_err(|_| error())
+}
+
+/// Produce a label from zero based column and row indices, or `None` if
+/// either is negative.
+pub(crate) fn format_label(column: i32, row: i32) -> Option<String> {
+    let column = u32::try_from(column).ok()?;
+    let row = u32::try_from(row).ok()?;
+    Some(format!("{}{}", column_letters(column), row as u64 + 1))
+}
+
+/// Parse a label into zero based column and row indices.  This is the
+/// inverse of `format_label`.
+pub(crate) fn parse_label(text: &str) -> Result<(i32, i32), ParseCoordError> {
+    let error = || ParseCoordError::Label(text.to_string());
+    let trimmed = text.trim();
+    let split = trimmed.find(|c: char| !c.is_ascii_alphabetic()).ok_or_else(error)?;
+    let (letters, digits) = trimmed.split_at(split);
+    let column = parse_column_letters(letters).map_err(|_| error())?;
+    if !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(error());
+    }
+    let row: u32 = digits.parse().map_err(|_| error())?;
+    let column = i32::try_from(column).map_err(|_| error())?;
+    let row = row.checked_sub(1).and_then(|row| i32::try_from(row).ok()).ok_or_else(error)?;
+    Ok((column, row))
+}
+
+/// Split a parenthesized, comma separated, tuple into exactly `N` trimmed
+/// components.  The `expected` form is used for reporting errors.
+pub(crate) fn parse_tuple<'a, const N: usize>(
+    text: &'a str,
+    expected: &'static str,
+) -> Result<[&'a str; N], ParseCoordError> {
+    let error = || ParseCoordError::Syntax {
+        text: text.to_string(),
+        expected,
+    };
+    let inner = text
+        .trim()
+        .strip_prefix('(')
+        .and_then(|rest| rest.strip_suffix(')'))
+        .ok_or_else(error)?;
+    let components: Vec<&str> = inner.split(',').map(str::trim).collect();
+    components.try_into().map_err(|_| error())
+}
+
+/// Parse a single integer component of a coordinate.
+pub(crate) fn parse_component(text: &str) -> Result<i32, ParseCoordError> {
+    text.parse().map_err(|error| ParseCoordError::Integer {
+        text: text.to_string(),
+        error,
+    })
+}
@@ -0,0 +1,235 @@
+//! Boundaries and outlines of shapes.
+//!
+//! The boundary of a `Shape` can be described in terms of coordinates,
+//! either as the face edges separating the shape from its surroundings, or
+//! as the coordinates on either side of them.  For rendering, the boundary
+//! can also be traced in screen space using the edges provided by a
+//! `SizedGrid`, producing closed polygons, each possibly with holes.
+
+use crate::shape::HashShape;
+use crate::{Coord, DirectionType, Shape, SizedGrid};
+use endgame_direction::Direction;
+use glam::Vec2;
+use std::collections::{HashMap, HashSet};
+
+/// How close, relative to the edge length, two points in screen space must
+/// be to be considered the same vertex.
+const VERTEX_TOLERANCE: f32 = 1e-3;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A closed outline of a region of a `Shape` in screen space.  The
+/// `exterior` is wound in the same direction as `SizedGrid::vertices`,
+/// while each of the `holes` is wound in the opposite direction.  Each
+/// polygon has one point per grid vertex along the boundary, without
+/// repeating the first point at the end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outline {
+    pub exterior: Vec<Vec2>,
+    pub holes: Vec<Vec<Vec2>>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Find the face edges of a `Shape` whose neighbouring coordinate lies
+/// outside the shape, as pairs of the coordinate within the shape and the
+/// `Direction` of the edge.
+pub fn boundary_edges<C: Coord, S>(shape: &S) -> HashSet<(C, Direction)>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    shape
+        .iter()
+        .flat_map(|coord| {
+            coord
+                .allowed_directions(DirectionType::Face)
+                .iter()
+                .filter(|dir| {
+                    coord
+                        .move_in_direction(DirectionType::Face, *dir)
+                        .is_none_or(|next| !shape.contains(&next))
+                })
+                .map(|dir| (coord.clone(), dir))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Find the coordinates of a `Shape` that neighbour a coordinate outside
+/// the shape, in directions of the given `DirectionType`.
+pub fn inner_boundary<C: Coord, S>(shape: &S, dir_type: DirectionType) -> HashShape<C>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    shape
+        .iter()
+        .filter(|coord| neighbours(*coord, dir_type).iter().any(|next| !shape.contains(next)))
+        .cloned()
+        .collect()
+}
+
+/// Find the coordinates outside a `Shape` that neighbour a coordinate of
+/// the shape, in directions of the given `DirectionType`.
+pub fn outer_boundary<C: Coord, S>(shape: &S, dir_type: DirectionType) -> HashShape<C>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    shape
+        .iter()
+        .flat_map(|coord| neighbours(coord, dir_type))
+        .filter(|next| !shape.contains(next))
+        .collect()
+}
+
+/// Trace the boundary of a `Shape` on the given grid, producing one
+/// `Outline` for each face connected region of the shape.  Regions that
+/// only meet at a vertex are given separate outlines, as are the holes
+/// that only meet the exterior, or each other, at a vertex.  The outlines
+/// are produced in no particular order.
+pub fn outlines<SG: SizedGrid, S>(grid: &SG, shape: &S) -> Vec<Outline>
+where
+    S: Shape<SG::Coord>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let Some(first) = shape.iter().next() else {
+        return Vec::new();
+    };
+    // The winding of the vertices of coordinates, which is uniform across
+    // the grid.
+    let winding = signed_area(&grid.vertices(first)).signum();
+    let tolerance = grid.edge_length() * VERTEX_TOLERANCE;
+    let key = |point: Vec2| {
+        let scaled = (point / tolerance).round();
+        (scaled.x as i64, scaled.y as i64)
+    };
+
+    // Orient each boundary edge such that the shape lies on the same side
+    // as the interior of a coordinate traversing its vertices.
+    let mut edges: Vec<(Vec2, Vec2)> = Vec::new();
+    let mut outgoing: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+    for (coord, dir) in boundary_edges(shape) {
+        let (mut a, mut b) = grid.edges(&coord)[&dir];
+        let center = grid.grid_to_screen(&coord);
+        if (b - a).perp_dot(center - a) * winding < 0.0 {
+            std::mem::swap(&mut a, &mut b);
+        }
+        outgoing.entry(key(a)).or_default().push(edges.len());
+        edges.push((a, b));
+    }
+
+    // Follow the edges around each loop.  Where several loops meet at a
+    // vertex, take the sharpest turn towards the shape, so that the loops
+    // remain separate.
+    let mut used = vec![false; edges.len()];
+    let mut exteriors: Vec<Vec<Vec2>> = Vec::new();
+    let mut holes: Vec<Vec<Vec2>> = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut polygon = Vec::new();
+        let mut current = start;
+        while !used[current] {
+            used[current] = true;
+            let (a, b) = edges[current];
+            polygon.push(a);
+            let direction = b - a;
+            // Numerical error may place the same vertex either side of a
+            // rounding boundary, so also consider the adjacent keys.
+            let (x, y) = key(b);
+            current = (x - 1..=x + 1)
+                .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+                .filter_map(|k| outgoing.get(&k))
+                .flatten()
+                .copied()
+                .max_by(|i, j| {
+                    let turn = |index: &usize| {
+                        let next = edges[*index].1 - edges[*index].0;
+                        winding * direction.perp_dot(next).atan2(direction.dot(next))
+                    };
+                    turn(i).total_cmp(&turn(j))
+                })
+                .expect("Every boundary vertex should have an outgoing edge");
+        }
+        if signed_area(&polygon) * winding > 0.0 {
+            exteriors.push(polygon);
+        } else {
+            holes.push(polygon);
+        }
+    }
+
+    let mut result: Vec<Outline> = exteriors
+        .into_iter()
+        .map(|exterior| Outline {
+            exterior,
+            holes: Vec::new(),
+        })
+        .collect();
+    for hole in holes {
+        // Find a point just within the shape alongside the hole, and assign
+        // the hole to the smallest exterior containing that point.
+        let (a, b) = (hole[0], hole[1 % hole.len()]);
+        let inward = (b - a).perp().normalize_or_zero() * winding;
+        let point = a.midpoint(b) + inward * tolerance * 10.0;
+        if let Some(outline) = result
+            .iter_mut()
+            .filter(|outline| polygon_contains(&outline.exterior, point))
+            .min_by(|x, y| {
+                signed_area(&x.exterior)
+                    .abs()
+                    .total_cmp(&signed_area(&y.exterior).abs())
+            })
+        {
+            outline.holes.push(hole);
+        }
+    }
+    result
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to find the neighbours of a coordinate in
+/// directions of the given `DirectionType`.
+fn neighbours<C: Coord>(coord: &C, dir_type: DirectionType) -> Vec<C> {
+    coord
+        .allowed_directions(dir_type)
+        .iter()
+        .filter_map(|dir| coord.move_in_direction(dir_type, dir))
+        .collect()
+}
+
+/// Internal helper to compute the signed area of a polygon.
+fn signed_area(polygon: &[Vec2]) -> f32 {
+    crate::utils::vertices_to_edges(polygon)
+        .map(|(a, b)| a.perp_dot(b))
+        .sum::<f32>()
+        / 2.0
+}
+
+/// Internal helper to determine whether a point lies within a polygon, by
+/// counting the crossings of a ray cast from the point.
+fn polygon_contains(polygon: &[Vec2], point: Vec2) -> bool {
+    crate::utils::vertices_to_edges(polygon)
+        .filter(|(a, b)| {
+            (a.y > point.y) != (b.y > point.y)
+                && point.x < a.x + (point.y - a.y) / (b.y - a.y) * (b.x - a.x)
+        })
+        .count()
+        % 2
+        == 1
+}
@@ -0,0 +1,255 @@
+//! Edges and vertices of grid cells.
+//!
+//! Games such as Dots and Boxes, or those with road building or wall
+//! placement, need to address the edges and corners between cells rather
+//! than only the cells themselves.  An `Edge` is shared by the two cells on
+//! either side of it, and a `Vertex` by every cell meeting at a corner:
+//! four on square grids, three on hexagonal grids and six on triangular
+//! grids.
+//!
+//! Each edge or vertex could be described from any of the cells that share
+//! it, as the face or vertex `Direction` leading to it from that cell.  So
+//! that each has a single identity, they are always represented from the
+//! cell with the least array offset, breaking ties by the least
+//! `Direction`.
+
+use crate::{Coord, DirectionType};
+use endgame_direction::Direction;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Coordinates whose cells have edges and vertices that can be addressed
+/// with `Edge` and `Vertex`.
+pub trait PartCoord: Coord + Copy {
+    /// The corners and edges of the cell in clockwise order in screen
+    /// space, as pairs of the vertex `Direction` of a corner and the face
+    /// `Direction` of the edge that follows it.
+    fn boundary(&self) -> Vec<(Direction, Direction)>;
+
+    /// The edges of the cell, in clockwise order.
+    fn edges(&self) -> Vec<Edge<Self>> {
+        self.boundary()
+            .into_iter()
+            .map(|(_, face)| Edge::canonical(*self, face))
+            .collect()
+    }
+
+    /// The vertices of the cell, in clockwise order.
+    fn vertices(&self) -> Vec<Vertex<Self>> {
+        self.boundary()
+            .into_iter()
+            .map(|(corner, _)| Vertex::canonical(*self, corner))
+            .collect()
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An edge between two neighbouring cells of a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Edge<C: Coord> {
+    coord: C,
+    dir: Direction,
+}
+
+impl<C: PartCoord> Edge<C> {
+    /// Construct the edge of the given cell in the given face
+    /// `Direction`, or `None` if it is not a face direction of the cell.
+    pub fn from_face(coord: C, dir: Direction) -> Option<Self> {
+        let allowed = coord.allowed_directions(DirectionType::Face).contains(dir);
+        allowed.then(|| Edge::canonical(coord, dir))
+    }
+
+    /// The cell from which the edge is represented.
+    pub fn coord(&self) -> C {
+        self.coord
+    }
+
+    /// The face `Direction` of the edge from `coord`.
+    pub fn direction(&self) -> Direction {
+        self.dir
+    }
+
+    /// The two cells on either side of the edge, starting with `coord`.
+    pub fn faces(&self) -> [C; 2] {
+        [self.coord, neighbour(&self.coord, self.dir)]
+    }
+
+    /// The two vertices at the ends of the edge, in clockwise order around
+    /// `coord`.
+    pub fn vertices(&self) -> [Vertex<C>; 2] {
+        let boundary = self.coord.boundary();
+        let index = face_index(&boundary, self.dir);
+        let next = (index + 1) % boundary.len();
+        [
+            Vertex::canonical(self.coord, boundary[index].0),
+            Vertex::canonical(self.coord, boundary[next].0),
+        ]
+    }
+
+    /// The other edges sharing a vertex with this edge.
+    pub fn neighbours(&self) -> Vec<Edge<C>> {
+        let mut result = Vec::new();
+        for vertex in self.vertices() {
+            for edge in vertex.edges() {
+                if edge != *self && !result.contains(&edge) {
+                    result.push(edge);
+                }
+            }
+        }
+        result
+    }
+
+    /// Internal helper to construct the canonical representation of an
+    /// edge, which must be in a face direction of the cell.
+    fn canonical(coord: C, dir: Direction) -> Self {
+        let other = Edge {
+            coord: neighbour(&coord, dir),
+            dir: !dir,
+        };
+        let edge = Edge { coord, dir };
+        if other.key() < edge.key() { other } else { edge }
+    }
+
+    /// Internal helper to produce the key ordering representations.
+    fn key(&self) -> ((isize, isize), Direction) {
+        (self.coord.grid_to_array_offset(), self.dir)
+    }
+}
+
+impl<C: Coord> Display for Edge<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Edge({} {})", self.coord, self.dir)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A vertex where the corners of several cells of a grid meet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Vertex<C: Coord> {
+    coord: C,
+    dir: Direction,
+}
+
+impl<C: PartCoord> Vertex<C> {
+    /// Construct the vertex at the corner of the given cell in the given
+    /// vertex `Direction`, or `None` if no corner of the cell lies in that
+    /// direction.
+    pub fn from_corner(coord: C, dir: Direction) -> Option<Self> {
+        let allowed = coord.boundary().iter().any(|(corner, _)| *corner == dir);
+        allowed.then(|| Vertex::canonical(coord, dir))
+    }
+
+    /// The cell from which the vertex is represented.
+    pub fn coord(&self) -> C {
+        self.coord
+    }
+
+    /// The vertex `Direction` of the corner from `coord`.
+    pub fn direction(&self) -> Direction {
+        self.dir
+    }
+
+    /// The cells meeting at the vertex, in clockwise order around it,
+    /// starting with `coord`.
+    pub fn faces(&self) -> Vec<C> {
+        corners_around(self.coord, self.dir)
+            .into_iter()
+            .map(|(coord, _)| coord)
+            .collect()
+    }
+
+    /// The edges meeting at the vertex, in clockwise order around it.
+    pub fn edges(&self) -> Vec<Edge<C>> {
+        corners_around(self.coord, self.dir)
+            .into_iter()
+            .map(|(coord, corner)| {
+                let boundary = coord.boundary();
+                let index = corner_index(&boundary, corner);
+                Edge::canonical(coord, boundary[index].1)
+            })
+            .collect()
+    }
+
+    /// The vertices at the other ends of the edges meeting at the vertex.
+    pub fn neighbours(&self) -> Vec<Vertex<C>> {
+        self.edges()
+            .into_iter()
+            .map(|edge| {
+                let [first, second] = edge.vertices();
+                if first == *self { second } else { first }
+            })
+            .collect()
+    }
+
+    /// Internal helper to construct the canonical representation of a
+    /// vertex, which must be at a corner of the cell.
+    fn canonical(coord: C, dir: Direction) -> Self {
+        let (coord, dir) = corners_around(coord, dir)
+            .into_iter()
+            .min_by_key(|(coord, dir)| (coord.grid_to_array_offset(), *dir))
+            .expect("There is always at least one cell at a vertex");
+        Vertex { coord, dir }
+    }
+}
+
+impl<C: Coord> Display for Vertex<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Vertex({} {})", self.coord, self.dir)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to find the neighbouring cell in a face direction.
+fn neighbour<C: PartCoord>(coord: &C, dir: Direction) -> C {
+    coord
+        .move_in_direction(DirectionType::Face, dir)
+        .expect("Edges should only be in face directions")
+}
+
+/// Internal helper to find the index of a face direction in the boundary
+/// of a cell.
+fn face_index(boundary: &[(Direction, Direction)], dir: Direction) -> usize {
+    boundary
+        .iter()
+        .position(|(_, face)| *face == dir)
+        .expect("Edges should only be in face directions")
+}
+
+/// Internal helper to find the index of a vertex direction in the boundary
+/// of a cell.
+fn corner_index(boundary: &[(Direction, Direction)], dir: Direction) -> usize {
+    boundary
+        .iter()
+        .position(|(corner, _)| *corner == dir)
+        .expect("Vertices should only be at the corners of a cell")
+}
+
+/// Internal helper to find every cell meeting at a vertex, paired with the
+/// direction of the vertex from that cell, in clockwise order around the
+/// vertex starting from the given cell.
+fn corners_around<C: PartCoord>(coord: C, dir: Direction) -> Vec<(C, Direction)> {
+    let mut result = vec![(coord, dir)];
+    let (mut current, mut corner) = (coord, dir);
+    loop {
+        // Cross the edge following the corner.  As the neighbour lists its
+        // boundary in the same rotational order, the shared edge runs in
+        // the opposite direction, so the corner is at its end.
+        let boundary = current.boundary();
+        let face = boundary[corner_index(&boundary, corner)].1;
+        current = neighbour(&current, face);
+        let boundary = current.boundary();
+        let index = face_index(&boundary, !face);
+        corner = boundary[(index + 1) % boundary.len()].0;
+        if (current, corner) == (coord, dir) {
+            return result;
+        }
+        result.push((current, corner));
+    }
+}
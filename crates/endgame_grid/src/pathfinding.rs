@@ -0,0 +1,289 @@
+//! Shortest path search between coordinates of a grid.
+//!
+//! Searches are parameterized by a cost closure, which given a coordinate
+//! and one of its neighbours returns the cost of stepping between them, or
+//! `None` if the step is impassable.  As grids are infinite, the cost
+//! closure is also responsible for bounding the search: should the goal be
+//! unreachable, a search over an unbounded region will not terminate.
+//! Typically this is done by returning `None` for any coordinate outside
+//! the board, which `entry_cost` does for the coordinates of a
+//! `ShapeContainer`.
+//!
+//! When many agents head towards the same destinations, it is cheaper to
+//! compute a single `distance_field` or `flow_field` over the board than to
+//! search for a path for each agent.
+
+use crate::shape::HashShapeContainer;
+use crate::{Coord, DirectionType, Shape, ShapeContainer};
+use endgame_direction::Direction;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Find a lowest cost path from `start` to `goal`, stepping between
+/// neighbouring coordinates in directions of the given `DirectionType`.
+/// Returns the path, inclusive of both `start` and `goal`, along with its
+/// total cost, or `None` if the goal is unreachable.
+///
+/// The search is guided by `Coord::distance` to the goal, scaled by the
+/// number of face steps a single step covers.  This assumes that every
+/// step costs at least one.  Should there be cheaper steps the result may
+/// not be optimal, in which case `dijkstra` should be used instead.
+pub fn astar<C: Coord, F>(
+    start: &C,
+    goal: &C,
+    dir_type: DirectionType,
+    cost: F,
+) -> Option<(Vec<C>, u32)>
+where
+    F: FnMut(&C, &C) -> Option<u32>,
+{
+    // How far, measured in face steps, a single step may cover.  This is
+    // uniform across each grid, so it suffices to examine the start.
+    let max_step = start
+        .allowed_directions(dir_type)
+        .iter()
+        .filter_map(|dir| start.move_in_direction(dir_type, dir))
+        .map(|neighbour| start.distance(&neighbour))
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    search(start, goal, dir_type, cost, |coord| {
+        coord.distance(goal).div_ceil(max_step) as u32
+    })
+}
+
+/// Find a lowest cost path from `start` to `goal`, stepping between
+/// neighbouring coordinates in directions of the given `DirectionType`.
+/// Returns the path, inclusive of both `start` and `goal`, along with its
+/// total cost, or `None` if the goal is unreachable.
+///
+/// Unlike `astar`, the search is unguided, so the result is optimal even
+/// when steps may cost zero.
+pub fn dijkstra<C: Coord, F>(
+    start: &C,
+    goal: &C,
+    dir_type: DirectionType,
+    cost: F,
+) -> Option<(Vec<C>, u32)>
+where
+    F: FnMut(&C, &C) -> Option<u32>,
+{
+    search(start, goal, dir_type, cost, |_| 0)
+}
+
+/// Produce a cost closure from a `ShapeContainer` of costs, where the cost
+/// of a step is the cost associated with the coordinate being entered.
+/// Coordinates not within the container are impassable.
+pub fn entry_cost<C: Coord, SC>(costs: &SC) -> impl FnMut(&C, &C) -> Option<u32> + '_
+where
+    SC: ShapeContainer<C, u32>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    |_, to| costs.get(to).copied()
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Compute the lowest cost of reaching the nearest of the given `sources`
+/// from every coordinate of `bounds` from which one is reachable, stepping
+/// between neighbouring coordinates in directions of the given
+/// `DirectionType` without leaving `bounds`.  Sources outside of `bounds`
+/// are ignored.
+///
+/// As with the path searches, `cost` gives the cost of stepping from its
+/// first argument to its second, so the result is the cost for an agent
+/// to travel towards the sources.
+pub fn distance_field<C: Coord, S, I, F>(
+    sources: I,
+    bounds: &S,
+    dir_type: DirectionType,
+    cost: F,
+) -> HashShapeContainer<C, u32>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    I: IntoIterator<Item=C>,
+    F: FnMut(&C, &C) -> Option<u32>,
+{
+    field_search(sources, bounds, dir_type, cost)
+        .into_iter()
+        .map(|(coord, (so_far, _))| (coord, so_far))
+        .collect()
+}
+
+/// Compute, for every coordinate of `bounds` from which one of the given
+/// `sources` is reachable, the `Direction` of the first step along a
+/// lowest cost path to the nearest source.  Following the directions from
+/// any coordinate will lead to a source, with each step the cost of the
+/// remaining path as given by `distance_field` decreasing by the cost of
+/// the step.  The sources themselves have no direction, and so are not
+/// contained in the result.
+pub fn flow_field<C: Coord, S, I, F>(
+    sources: I,
+    bounds: &S,
+    dir_type: DirectionType,
+    cost: F,
+) -> HashShapeContainer<C, Direction>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    I: IntoIterator<Item=C>,
+    F: FnMut(&C, &C) -> Option<u32>,
+{
+    field_search(sources, bounds, dir_type, cost)
+        .into_iter()
+        .filter_map(|(coord, (_, dir))| dir.map(|dir| (coord, dir)))
+        .collect()
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper implementing a multiple source search outward from the
+/// `sources`.  Produces the lowest cost of reaching a source from each
+/// coordinate reached, along with the `Direction` of the first step
+/// towards it, if any.
+fn field_search<C: Coord, S, I, F>(
+    sources: I,
+    bounds: &S,
+    dir_type: DirectionType,
+    mut cost: F,
+) -> HashMap<C, (u32, Option<Direction>)>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    I: IntoIterator<Item=C>,
+    F: FnMut(&C, &C) -> Option<u32>,
+{
+    let mut field: HashMap<C, (u32, Option<Direction>)> = HashMap::new();
+    // As with `search`, coordinates are referred to by index in the
+    // frontier, so as to avoid requiring that they be ordered.
+    let mut nodes: Vec<C> = Vec::new();
+    let mut frontier = BinaryHeap::new();
+    for source in sources {
+        if bounds.contains(&source) && !field.contains_key(&source) {
+            field.insert(source.clone(), (0, None));
+            frontier.push(Reverse((0, nodes.len())));
+            nodes.push(source);
+        }
+    }
+
+    while let Some(Reverse((so_far, index))) = frontier.pop() {
+        let coord = nodes[index].clone();
+        // Skip entries that have since been superseded by a cheaper path.
+        if field[&coord].0 < so_far {
+            continue;
+        }
+        for dir in coord.allowed_directions(dir_type).iter() {
+            let Some(next) = coord.move_in_direction(dir_type, dir) else {
+                continue;
+            };
+            if !bounds.contains(&next) {
+                continue;
+            }
+            // The search proceeds away from the sources, so find the
+            // direction leading back.
+            let Some(back) = next
+                .allowed_directions(dir_type)
+                .iter()
+                .find(|back| next.move_in_direction(dir_type, *back).as_ref() == Some(&coord))
+            else {
+                continue;
+            };
+            let Some(step) = cost(&next, &coord) else {
+                continue;
+            };
+            let next_so_far = so_far.saturating_add(step);
+            if field
+                .get(&next)
+                .is_some_and(|(existing, _)| *existing <= next_so_far)
+            {
+                continue;
+            }
+            field.insert(next.clone(), (next_so_far, Some(back)));
+            frontier.push(Reverse((next_so_far, nodes.len())));
+            nodes.push(next);
+        }
+    }
+    field
+}
+
+/// Internal helper implementing a best first search, ordered by the cost
+/// so far plus the `heuristic` estimate of the remaining cost.  So long as
+/// the heuristic never overestimates the remaining cost, the resulting path
+/// will be optimal.
+fn search<C: Coord, F, H>(
+    start: &C,
+    goal: &C,
+    dir_type: DirectionType,
+    mut cost: F,
+    heuristic: H,
+) -> Option<(Vec<C>, u32)>
+where
+    F: FnMut(&C, &C) -> Option<u32>,
+    H: Fn(&C) -> u32,
+{
+    // Each coordinate reached, along with the index of its predecessor.
+    // Coordinates are referred to by their index, so as to avoid requiring
+    // that they be ordered.
+    let mut nodes: Vec<(C, Option<usize>)> = vec![(start.clone(), None)];
+    // The index of the best node and its cost for each coordinate reached.
+    let mut best: HashMap<C, (usize, u32)> = HashMap::from([(start.clone(), (0, 0))]);
+    let mut frontier = BinaryHeap::from([Reverse((heuristic(start), 0, 0))]);
+
+    while let Some(Reverse((_, so_far, index))) = frontier.pop() {
+        let coord = nodes[index].0.clone();
+        // Skip entries that have since been superseded by a cheaper path.
+        if best[&coord].0 != index {
+            continue;
+        }
+        if coord == *goal {
+            return Some((reconstruct(&nodes, index), so_far));
+        }
+        for dir in coord.allowed_directions(dir_type).iter() {
+            let Some(next) = coord.move_in_direction(dir_type, dir) else {
+                continue;
+            };
+            let Some(step) = cost(&coord, &next) else {
+                continue;
+            };
+            let next_so_far = so_far.saturating_add(step);
+            if best
+                .get(&next)
+                .is_some_and(|(_, existing)| *existing <= next_so_far)
+            {
+                continue;
+            }
+            let next_index = nodes.len();
+            let estimate = next_so_far.saturating_add(heuristic(&next));
+            nodes.push((next.clone(), Some(index)));
+            best.insert(next, (next_index, next_so_far));
+            frontier.push(Reverse((estimate, next_so_far, next_index)));
+        }
+    }
+    None
+}
+
+/// Internal helper to recover the path ending at the given node.
+fn reconstruct<C: Coord>(nodes: &[(C, Option<usize>)], mut index: usize) -> Vec<C> {
+    let mut path = vec![nodes[index].0.clone()];
+    while let Some(previous) = nodes[index].1 {
+        path.push(nodes[previous].0.clone());
+        index = previous;
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,73 @@
+//! Enumeration of polyforms.
+//!
+//! A polyform is a shape made of coordinates connected across their faces:
+//! polyominoes on square grids, polyhexes on hexagonal grids, and
+//! polyiamonds on triangular grids.  Polyforms of each size are enumerated
+//! by extending each polyform one size smaller by a single neighbouring
+//! coordinate in every possible way, and keeping the distinct canonical
+//! forms under the chosen `Equivalence`.
+
+use crate::canonical::{canonicalize_with, CanonicalCoord, Equivalence};
+use crate::shape::HashShape;
+use crate::{DirectionType, Shape};
+use std::collections::HashSet;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Enumerate the polyforms made of the given number of coordinates, which
+/// are distinct under the given `Equivalence`.  Each polyform is in the
+/// canonical form produced by `canonicalize_with`, so its least coordinate,
+/// in the order of array offsets, lies at the origin, or for triangular
+/// grids possibly the downward facing triangle alongside it.
+///
+/// The polyforms are produced in order of their sorted array offsets, so
+/// the result is deterministic.  The only polyform of size zero is the
+/// empty shape.
+pub fn polyforms<C: CanonicalCoord>(size: usize, equivalence: Equivalence) -> Vec<HashShape<C>> {
+    let origin = C::array_offset_to_grid((0, 0));
+    let mut current: HashSet<HashShape<C>> = HashSet::from([HashShape::new()]);
+    for _ in 0..size {
+        let mut next = HashSet::new();
+        for shape in current.iter() {
+            // Starting from the empty shape, also consider the neighbours
+            // of the origin, as on triangular grids the `Fixed` forms of
+            // a single triangle need not include the origin.
+            let frontier: HashSet<C> = if shape.is_empty() {
+                neighbours(&origin).into_iter().chain([origin]).collect()
+            } else {
+                shape
+                    .iter()
+                    .flat_map(neighbours)
+                    .filter(|next| !shape.contains(next))
+                    .collect()
+            };
+            for coord in frontier {
+                let mut extended = shape.clone();
+                extended.insert(coord);
+                next.insert(canonicalize_with(&extended, equivalence).form);
+            }
+        }
+        current = next;
+    }
+
+    let mut result: Vec<HashShape<C>> = current.into_iter().collect();
+    result.sort_by_cached_key(|shape| {
+        let mut offsets: Vec<(isize, isize)> =
+            shape.iter().map(|coord| coord.grid_to_array_offset()).collect();
+        offsets.sort();
+        offsets
+    });
+    result
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to find the neighbours of a coordinate across its
+/// faces.
+fn neighbours<C: CanonicalCoord>(coord: &C) -> Vec<C> {
+    coord
+        .allowed_directions(DirectionType::Face)
+        .iter()
+        .filter_map(|dir| coord.move_in_direction(DirectionType::Face, dir))
+        .collect()
+}
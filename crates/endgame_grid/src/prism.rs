@@ -0,0 +1,399 @@
+//! A lattice of hexagonal prisms, stacking hexagonal grids into three
+//! dimensions.
+//!
+//! Unlike `layered::Coord`, every step of a prism lattice is a translation,
+//! so `Coord` is a `ModuleCoord`.  As with `layered::Coord`, steps remain
+//! within a level, so both `Coord::distance` and `Coord::vertex_distance`
+//! are the sum of the respective distance within a level and the number of
+//! levels.
+//!
+//! `Direction`s only describe movement within a level, with
+//! `LayeredCoord::move_vertically` moving between levels.  Rotations turn
+//! around the vertical axis.
+
+use crate::layered::{interleave, LayeredCoord};
+use crate::notation::{parse_component, parse_tuple, ParseCoordError};
+use crate::shape::HashShape;
+use crate::hex;
+use crate::utils::{Cover, ModuleCoordIter};
+use crate::{AllowedCoordIterRange, Color, DirectionType, ModuleCoord, Shape};
+use endgame_direction::{Direction, DirectionSet};
+use glam::{ivec3, IVec3};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::str::FromStr;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Axes {
+    Q,
+    R,
+    S,
+    Z,
+}
+
+impl Axes {
+    /// The axis of the hexagonal grid within a level, if any.
+    fn planar(self) -> Option<hex::Axes> {
+        use Axes::*;
+        match self {
+            Q => Some(hex::Axes::Q),
+            R => Some(hex::Axes::R),
+            S => Some(hex::Axes::S),
+            Z => None,
+        }
+    }
+}
+
+impl Display for Axes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Axes::*;
+        let c = match self {
+            Q => 'Q',
+            R => 'R',
+            S => 'S',
+            Z => 'Z',
+        };
+        write!(f, "{}", c)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Coord(IVec3);
+
+impl Coord {
+    /// The axes of a prism lattice.
+    pub const AXES: [Axes; 4] = [Axes::Q, Axes::R, Axes::S, Axes::Z];
+
+    /// Construct a new `Coord` from the axial x and y coordinates of a
+    /// `hex::Coord`, along with a z coordinate for the level.
+    pub const fn new(x: i32, y: i32, z: i32) -> Self {
+        Coord(ivec3(x, y, z))
+    }
+
+    /// Construct a new `Coord` from an `IVec3`.
+    pub const fn from_ivec3(coord: IVec3) -> Self {
+        Coord(coord)
+    }
+
+    /// Convert the coordinate to an `IVec3`.
+    pub const fn to_ivec3(&self) -> IVec3 {
+        self.0
+    }
+
+    /// Produce the surface of the hexagonal prism of coordinates, as given
+    /// by `range`, at the given `radius` from the origin.
+    pub fn ring(radius: usize) -> HashShape<Coord> {
+        let origin = hex::Coord::default();
+        Coord::range(radius)
+            .iter()
+            .filter(|coord| {
+                let planar = crate::Coord::distance(&coord.planar(), &origin);
+                planar.max(coord.0.z.unsigned_abs() as usize) == radius
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Produce the hexagonal prism of coordinates within the given `radius`
+    /// of the origin within each level, across the levels within `radius`
+    /// of the origin.
+    pub fn range(radius: usize) -> HashShape<Coord> {
+        let iradius = radius as i32;
+        let mut coords = Vec::new();
+        let layer = hex::Coord::range(radius);
+        for z in -iradius..=iradius {
+            coords.extend(layer.iter().map(|planar| Coord::from_planar(*planar, z)));
+        }
+        HashShape::from_iter(coords)
+    }
+}
+
+impl Default for Coord {
+    fn default() -> Self {
+        Coord(ivec3(0, 0, 0))
+    }
+}
+
+impl Display for Coord {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({},{},{})", self.0.x, self.0.y, self.0.z)
+    }
+}
+
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    /// Parse a coordinate of the form `(x,y,z)`, as produced by `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, z] = parse_tuple(s, "a prism coordinate of the form (x,y,z)")?;
+        Ok(Coord::new(parse_component(x)?, parse_component(y)?, parse_component(z)?))
+    }
+}
+
+impl std::ops::Neg for Coord {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Coord(-self.0)
+    }
+}
+
+impl std::ops::Add for Coord {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Coord(self.0 + other.0)
+    }
+}
+
+impl std::ops::Add<&Coord> for Coord {
+    type Output = Self;
+
+    fn add(self, other: &Self) -> Self {
+        Coord(self.0 + other.0)
+    }
+}
+
+impl std::ops::Add<&Coord> for &Coord {
+    type Output = Coord;
+
+    fn add(self, other: &Coord) -> Self::Output {
+        Coord(self.0 + other.0)
+    }
+}
+
+impl std::ops::Sub for Coord {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Coord(self.0 - other.0)
+    }
+}
+
+impl std::ops::Sub<&Coord> for Coord {
+    type Output = Self;
+
+    fn sub(self, other: &Self) -> Self {
+        Coord(self.0 - other.0)
+    }
+}
+
+impl std::ops::Sub<&Coord> for &Coord {
+    type Output = Coord;
+
+    fn sub(self, other: &Coord) -> Self::Output {
+        Coord(self.0 - other.0)
+    }
+}
+
+impl std::ops::AddAssign for Coord {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl std::ops::AddAssign<&Coord> for Coord {
+    fn add_assign(&mut self, other: &Self) {
+        self.0 += other.0;
+    }
+}
+
+impl std::ops::SubAssign for Coord {
+    fn sub_assign(&mut self, other: Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl std::ops::SubAssign<&Coord> for Coord {
+    fn sub_assign(&mut self, other: &Self) {
+        self.0 -= other.0;
+    }
+}
+
+impl std::ops::Mul<isize> for Coord {
+    type Output = Self;
+
+    fn mul(self, other: isize) -> Self {
+        Coord(self.0 * (other as i32))
+    }
+}
+
+impl std::ops::MulAssign<isize> for Coord {
+    fn mul_assign(&mut self, other: isize) {
+        *self = *self * other;
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl crate::Coord for Coord {
+    type Axes = Axes;
+
+    fn is_origin(&self) -> bool {
+        self.0 == IVec3::ZERO
+    }
+
+    fn distance(&self, other: &Self) -> usize {
+        self.planar().distance(&other.planar()) + self.0.z.abs_diff(other.0.z) as usize
+    }
+
+    fn vertex_distance(&self, other: &Self) -> usize {
+        // Diagonal steps remain within a level, so do not shorten the
+        // climb between levels.
+        self.planar().vertex_distance(&other.planar()) + self.0.z.abs_diff(other.0.z) as usize
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        let planar = self.planar().euclidean_distance(&other.planar());
+        planar.hypot((other.0.z - self.0.z) as f32)
+    }
+
+    fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
+        self.planar().angle_to_direction(dir_type, angle)
+    }
+
+    fn direction_angle(&self, dir_type: DirectionType, dir: Direction) -> Option<f32> {
+        self.planar().direction_angle(dir_type, dir)
+    }
+
+    fn move_in_direction(&self, dir_type: DirectionType, dir: Direction) -> Option<Self> {
+        let offset = <Self as ModuleCoord>::offset_in_direction(self, dir_type, dir)?;
+        Some(*self + offset)
+    }
+
+    fn move_on_axis(&self, axis: Self::Axes, positive: bool) -> Self {
+        let offset = <Self as ModuleCoord>::offset_on_axis(self, axis, positive);
+        *self + offset
+    }
+
+    fn direction_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        dir_type: DirectionType,
+        dir: Direction,
+        range: RB,
+    ) -> impl Iterator<Item=Self> {
+        ModuleCoordIter {
+            opt_offset: self.offset_in_direction(dir_type, dir),
+            index: 0,
+            coord: *self,
+            range,
+        }
+    }
+
+    fn path_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        let planar: Vec<hex::Coord> = self.planar().path_iterator(&other.planar()).collect();
+        interleave(planar, self.0.z, other.0.z)
+            .into_iter()
+            .map(|(planar, z)| Coord::from_planar(planar, z))
+    }
+
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        let planar: Vec<hex::Coord> = self.planar().line_iterator(&other.planar()).collect();
+        interleave(planar, self.0.z, other.0.z)
+            .into_iter()
+            .map(|(planar, z)| Coord::from_planar(planar, z))
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        // Walk the lanes of the finer triangular lattice within a level,
+        // scaled so that each level spans two units with its center in the
+        // middle.
+        let lanes = |coord: &Self| {
+            let [u, v, w] = coord.planar().fine_lanes();
+            [2 * u, 2 * v, 2 * w, 2 * coord.0.z as i64 - 1]
+        };
+        let valid = |[u, v, w, _]: &[i64; 4]| hex::Coord::is_fine_triangle(&[*u, *v, *w]);
+        crate::utils::lane_walk(lanes(self), lanes(other), 2, valid, Cover::Super)
+            .into_iter()
+            .map(|[u, v, w, z]| {
+                Coord::from_planar(hex::Coord::from_fine_triangle([u, v, w]), z as i32)
+            })
+            .unique()
+    }
+
+    fn axis_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        axis: Self::Axes,
+        positive: bool,
+        range: RB,
+    ) -> impl Iterator<Item=Self> {
+        ModuleCoordIter {
+            opt_offset: Some(self.offset_on_axis(axis, positive)),
+            index: 0,
+            coord: *self,
+            range,
+        }
+    }
+
+    fn allowed_direction(&self, dir_type: DirectionType, dir: Direction) -> bool {
+        self.planar().allowed_direction(dir_type, dir)
+    }
+
+    fn allowed_directions(&self, dir_type: DirectionType) -> DirectionSet {
+        self.planar().allowed_directions(dir_type)
+    }
+
+    fn grid_to_array_offset(&self) -> (isize, isize) {
+        crate::layered::Coord::new(self.planar(), self.0.z).grid_to_array_offset()
+    }
+
+    fn to_color(&self) -> Color {
+        crate::layered::Coord::new(self.planar(), self.0.z).to_color()
+    }
+
+    fn rotate_clockwise(&self) -> Self {
+        Coord::from_planar(self.planar().rotate_clockwise(), self.0.z)
+    }
+
+    fn rotate_counterclockwise(&self) -> Self {
+        Coord::from_planar(self.planar().rotate_counterclockwise(), self.0.z)
+    }
+
+    fn reflect(&self, axis: Self::Axes) -> Self {
+        match axis.planar() {
+            Some(axis) => Coord::from_planar(self.planar().reflect(axis), self.0.z),
+            None => Coord(self.0 * ivec3(1, 1, -1)),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl ModuleCoord for Coord {
+    fn offset_in_direction(&self, dir_type: DirectionType, dir: Direction) -> Option<Self> {
+        let offset = self.planar().offset_in_direction(dir_type, dir)?;
+        Some(Coord::from_planar(offset, 0))
+    }
+
+    fn offset_on_axis(&self, axis: Self::Axes, positive: bool) -> Self {
+        match axis.planar() {
+            Some(axis) => Coord::from_planar(self.planar().offset_on_axis(axis, positive), 0),
+            None => Coord::new(0, 0, if positive { 1 } else { -1 }),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl LayeredCoord for Coord {
+    type Planar = hex::Coord;
+
+    fn planar(&self) -> hex::Coord {
+        hex::Coord::from_ivec2(self.0.truncate())
+    }
+
+    fn level(&self) -> i32 {
+        self.0.z
+    }
+
+    fn from_planar(planar: hex::Coord, level: i32) -> Self {
+        Coord(planar.to_ivec2().extend(level))
+    }
+}
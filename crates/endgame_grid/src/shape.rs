@@ -1,9 +1,50 @@
-use crate::{Coord, ModuleCoord};
+use crate::{ArrayOffsetCoord, Coord, ModuleCoord};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// Implements the given binary operator for every combination of owned and
+/// borrowed operands of a `Shape` type, by delegating to the given `Shape`
+/// method.
+macro_rules! shape_binary_op {
+    ($shape:ident, $bound:ident, $op:ident, $op_method:ident, $method:ident) => {
+        impl<C: $bound> std::ops::$op for $shape<C> {
+            type Output = Self;
+
+            fn $op_method(self, rhs: Self) -> Self::Output {
+                crate::Shape::$method(&self, &rhs)
+            }
+        }
+
+        impl<C: $bound> std::ops::$op<&$shape<C>> for $shape<C> {
+            type Output = Self;
+
+            fn $op_method(self, rhs: &Self) -> Self::Output {
+                crate::Shape::$method(&self, rhs)
+            }
+        }
+
+        impl<C: $bound> std::ops::$op<$shape<C>> for &$shape<C> {
+            type Output = $shape<C>;
+
+            fn $op_method(self, rhs: $shape<C>) -> Self::Output {
+                crate::Shape::$method(self, &rhs)
+            }
+        }
+
+        impl<C: $bound> std::ops::$op<&$shape<C>> for &$shape<C> {
+            type Output = $shape<C>;
+
+            fn $op_method(self, rhs: &$shape<C>) -> Self::Output {
+                crate::Shape::$method(self, rhs)
+            }
+        }
+    };
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -103,6 +144,16 @@ impl<'a, 'b, C: Coord> std::ops::Sub<&'b HashShape<C>> for &'a HashShape<C> {
     }
 }
 
+impl<C: Coord> Extend<C> for HashShape<C> {
+    fn extend<I: IntoIterator<Item=C>>(&mut self, iter: I) {
+        self.set.extend(iter)
+    }
+}
+
+shape_binary_op!(HashShape, Coord, BitOr, bitor, union);
+shape_binary_op!(HashShape, Coord, BitAnd, bitand, intersection);
+shape_binary_op!(HashShape, Coord, BitXor, bitxor, symmetric_difference);
+
 impl<C: Coord> crate::Shape<C> for HashShape<C> {
     type Iterator<'a>
     = HashShapeIterator<'a, C>
@@ -136,6 +187,18 @@ impl<C: Coord> crate::Shape<C> for HashShape<C> {
         self.set.is_empty()
     }
 
+    fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    fn insert(&mut self, coord: C) -> bool {
+        self.set.insert(coord)
+    }
+
+    fn remove(&mut self, coord: &C) -> bool {
+        self.set.remove(coord)
+    }
+
     fn union<'a>(&'a self, other: &'a Self) -> Self
     where
         C: 'a,
@@ -145,6 +208,24 @@ impl<C: Coord> crate::Shape<C> for HashShape<C> {
         }
     }
 
+    fn intersection<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        HashShape {
+            set: self.set.intersection(&other.set).cloned().collect(),
+        }
+    }
+
+    fn symmetric_difference<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        HashShape {
+            set: self.set.symmetric_difference(&other.set).cloned().collect(),
+        }
+    }
+
     fn iter<'a>(&'a self) -> Self::Iterator<'a>
     where
         C: 'a,
@@ -325,10 +406,29 @@ where
         self.map.insert(coord, value)
     }
 
+    fn remove(&mut self, coord: &C) -> Option<V> {
+        self.map.remove(coord)
+    }
+
     fn is_empty(&self) -> bool {
         self.map.is_empty()
     }
 
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn restrict(&self, shape: &Self::Shape) -> Self {
+        HashShapeContainer {
+            map: self
+                .map
+                .iter()
+                .filter(|(coord, _)| shape.set.contains(coord))
+                .map(|(coord, value)| (coord.clone(), value.clone()))
+                .collect(),
+        }
+    }
+
     fn as_shape(&self) -> Self::Shape {
         HashShape {
             set: self.map.keys().cloned().collect(),
@@ -401,4 +501,1512 @@ where
             .collect::<HashMap<_, _>>();
         HashShapeContainer { map: new_map }
     }
-}
\ No newline at end of file
+}
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The rectangular region of array offsets spanned by an array-backed
+/// `Shape` or `ShapeContainer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ArrayBounds {
+    min: (isize, isize),
+    width: usize,
+    height: usize,
+}
+
+impl ArrayBounds {
+    /// Compute the smallest bounds that include all the given array offsets.
+    fn from_offsets<I: IntoIterator<Item=(isize, isize)>>(offsets: I) -> Self {
+        offsets
+            .into_iter()
+            .fold(ArrayBounds::default(), |bounds, offset| bounds.expand(offset))
+    }
+
+    /// Is this region empty?
+    fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    /// The number of array offsets within the region.
+    fn len(&self) -> usize {
+        self.width * self.height
+    }
+
+    /// The array offset of the maximum corner of the region.  Only
+    /// meaningful if the region is not empty.
+    fn max(&self) -> (isize, isize) {
+        (
+            self.min.0 + self.width as isize - 1,
+            self.min.1 + self.height as isize - 1,
+        )
+    }
+
+    /// Compute the index into a row-major array for the given array offset,
+    /// or `None` if it lies outside the region.
+    fn index(&self, offset: (isize, isize)) -> Option<usize> {
+        let x = usize::try_from(offset.0 - self.min.0).ok()?;
+        let y = usize::try_from(offset.1 - self.min.1).ok()?;
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Compute the array offset for an index into a row-major array.
+    fn offset(&self, index: usize) -> (isize, isize) {
+        (
+            self.min.0 + (index % self.width) as isize,
+            self.min.1 + (index / self.width) as isize,
+        )
+    }
+
+    /// Iterate over the array offsets of the region in row-major order.
+    fn offsets(&self) -> impl Iterator<Item=(isize, isize)> + use<> {
+        let (min_x, min_y) = self.min;
+        let width = self.width as isize;
+        (min_y..min_y + self.height as isize)
+            .flat_map(move |y| (min_x..min_x + width).map(move |x| (x, y)))
+    }
+
+    /// Produce the smallest region containing this one and the given offset.
+    fn expand(&self, offset: (isize, isize)) -> Self {
+        if self.is_empty() {
+            return ArrayBounds {
+                min: offset,
+                width: 1,
+                height: 1,
+            };
+        }
+        let (min_x, min_y) = (self.min.0.min(offset.0), self.min.1.min(offset.1));
+        let (max_x, max_y) = self.max();
+        let (max_x, max_y) = (max_x.max(offset.0), max_y.max(offset.1));
+        ArrayBounds {
+            min: (min_x, min_y),
+            width: (max_x - min_x + 1) as usize,
+            height: (max_y - min_y + 1) as usize,
+        }
+    }
+
+    /// Produce the smallest region containing both this region and the other.
+    fn union(&self, other: &Self) -> Self {
+        if other.is_empty() {
+            *self
+        } else {
+            self.expand(other.min).expand(other.max())
+        }
+    }
+
+    /// Produce a region containing this one and the given offset, growing
+    /// geometrically along each axis that must be extended.  This ensures
+    /// that building a container by repeated insertion outside its bounds
+    /// takes amortized constant time per insertion.
+    fn grow(&self, offset: (isize, isize)) -> Self {
+        if self.is_empty() {
+            return self.expand(offset);
+        }
+        let (width, height) = (self.width as isize, self.height as isize);
+        let (mut min_x, mut min_y) = self.min;
+        let (mut max_x, mut max_y) = self.max();
+        if offset.0 < min_x {
+            min_x = offset.0.min(min_x - width);
+        } else if offset.0 > max_x {
+            max_x = offset.0.max(max_x + width);
+        }
+        if offset.1 < min_y {
+            min_y = offset.1.min(min_y - height);
+        } else if offset.1 > max_y {
+            max_y = offset.1.max(max_y + height);
+        }
+        ArrayBounds {
+            min: (min_x, min_y),
+            width: (max_x - min_x + 1) as usize,
+            height: (max_y - min_y + 1) as usize,
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The coordinates for every array offset within an `ArrayBounds`, in
+/// row-major order.  As the lattice is immutable, it is shared between
+/// clones of the array-backed shapes and containers, and between a
+/// container and the shapes derived from it.
+#[derive(Clone)]
+struct ArrayLattice<C: ArrayOffsetCoord> {
+    bounds: ArrayBounds,
+    coords: Arc<[C]>,
+}
+
+impl<C: ArrayOffsetCoord> ArrayLattice<C> {
+    fn new(bounds: ArrayBounds) -> Self {
+        Self {
+            bounds,
+            coords: bounds.offsets().map(C::array_offset_to_grid).collect(),
+        }
+    }
+
+    /// The index of the coordinate in the lattice, or `None` if it lies
+    /// outside the bounds.
+    fn index(&self, coord: &C) -> Option<usize> {
+        self.bounds.index(coord.grid_to_array_offset())
+    }
+}
+
+impl<C: ArrayOffsetCoord> Default for ArrayLattice<C> {
+    fn default() -> Self {
+        Self::new(ArrayBounds::default())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The number of bits in each word of a `BitShape`.
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A `Shape` represented as a bitset over a bounded region of the grid.
+/// Coordinates are indexed by their `Coord::grid_to_array_offset`, and
+/// the region is the smallest rectangle of array offsets spanning the
+/// coordinates the shape was constructed from.
+///
+/// When two shapes share the same region, union, intersection,
+/// difference, and the subshape and disjointness tests operate on 64
+/// coordinates at a time.  Shapes with different regions are first
+/// realigned, which takes time proportional to the number of coordinates
+/// they contain.  `complement` is relative to the region of the shape, so
+/// it is best to construct masks from a common region, for example via
+/// `BitShape::empty_like`.
+///
+/// Memory usage is proportional to the size of the region, so sparse
+/// shapes are better represented with a `HashShape`.
+#[derive(Clone)]
+pub struct BitShape<C: ArrayOffsetCoord> {
+    lattice: ArrayLattice<C>,
+    /// One bit per coordinate in the lattice.  Bits beyond the end of the
+    /// lattice are always zero.
+    bits: Vec<u64>,
+}
+
+impl<C: ArrayOffsetCoord> BitShape<C> {
+    /// Internal helper to create an empty shape over the given lattice.
+    fn empty_over(lattice: ArrayLattice<C>) -> Self {
+        let words = lattice.bounds.len().div_ceil(WORD_BITS);
+        Self {
+            lattice,
+            bits: vec![0; words],
+        }
+    }
+
+    /// Create an empty `BitShape` with the same region as this one.
+    pub fn empty_like(&self) -> Self {
+        Self::empty_over(self.lattice.clone())
+    }
+
+    /// Create a `BitShape` containing every coordinate in the region of
+    /// this one.
+    pub fn full_like(&self) -> Self {
+        self.empty_like().complement()
+    }
+
+    /// Create a shape containing the coordinates within the region of this
+    /// shape that are not in this shape.
+    pub fn complement(&self) -> Self {
+        let mut result = self.with_bits(self.bits.iter().map(|w| !w));
+        result.clear_padding();
+        result
+    }
+
+    /// Internal helper to construct a shape over the same lattice with the
+    /// given words.
+    fn with_bits<I: Iterator<Item=u64>>(&self, bits: I) -> Self {
+        Self {
+            lattice: self.lattice.clone(),
+            bits: bits.collect(),
+        }
+    }
+
+    /// Internal helper to combine the words of two shapes, aligning them
+    /// to a common region if necessary.
+    fn combine<F: Fn(u64, u64) -> u64>(&self, other: &Self, op: F) -> Self {
+        let lattice = if self.lattice.bounds == other.lattice.bounds {
+            self.lattice.clone()
+        } else if other.lattice.bounds == self.lattice.bounds.union(&other.lattice.bounds) {
+            other.lattice.clone()
+        } else {
+            ArrayLattice::new(self.lattice.bounds.union(&other.lattice.bounds))
+        };
+        let lhs = self.aligned_bits(&lattice.bounds);
+        let rhs = other.aligned_bits(&lattice.bounds);
+        Self {
+            lattice,
+            bits: lhs.iter().zip(rhs.iter()).map(|(a, b)| op(*a, *b)).collect(),
+        }
+    }
+
+    /// Internal helper to obtain the words of this shape realigned to the
+    /// given bounds.  Coordinates outside the bounds are dropped.
+    fn aligned_bits(&self, bounds: &ArrayBounds) -> std::borrow::Cow<'_, [u64]> {
+        if self.lattice.bounds == *bounds {
+            return std::borrow::Cow::Borrowed(&self.bits);
+        }
+        let mut bits = vec![0; bounds.len().div_ceil(WORD_BITS)];
+        for index in self.indices() {
+            if let Some(new_index) = bounds.index(self.lattice.bounds.offset(index)) {
+                bits[new_index / WORD_BITS] |= 1 << (new_index % WORD_BITS);
+            }
+        }
+        std::borrow::Cow::Owned(bits)
+    }
+
+    /// Internal helper to iterate over the lattice indices of the
+    /// coordinates in the shape.
+    fn indices(&self) -> impl Iterator<Item=usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(word_index, word)| {
+            BitIter(*word).map(move |bit| word_index * WORD_BITS + bit)
+        })
+    }
+
+    /// Internal helper to zero the bits in the final word that lie beyond
+    /// the end of the lattice.
+    fn clear_padding(&mut self) {
+        let remainder = self.lattice.bounds.len() % WORD_BITS;
+        if remainder != 0
+            && let Some(last) = self.bits.last_mut()
+        {
+            *last &= (1 << remainder) - 1;
+        }
+    }
+}
+
+/// An iterator over the positions of the set bits in a word, from least to
+/// most significant.
+struct BitIter(u64);
+
+impl Iterator for BitIter {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0 == 0 {
+            return None;
+        }
+        let bit = self.0.trailing_zeros() as usize;
+        // Clear the lowest set bit.
+        self.0 &= self.0 - 1;
+        Some(bit)
+    }
+}
+
+impl<C: ArrayOffsetCoord> Debug for BitShape<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(crate::Shape::iter(self)).finish()
+    }
+}
+
+impl<C: ArrayOffsetCoord> PartialEq for BitShape<C> {
+    fn eq(&self, other: &Self) -> bool {
+        // Shapes with different regions may still hold the same coordinates.
+        crate::Shape::len(self) == crate::Shape::len(other)
+            && crate::Shape::is_subshape(self, other)
+    }
+}
+
+impl<C: ArrayOffsetCoord> Eq for BitShape<C> {}
+
+impl<C: ArrayOffsetCoord> Hash for BitShape<C> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Iteration is in row-major order of array offsets, regardless
+        // of the region, so it is consistent with equality.
+        for coord in crate::Shape::iter(self) {
+            coord.hash(state);
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord> FromIterator<C> for BitShape<C> {
+    fn from_iter<I: IntoIterator<Item=C>>(iter: I) -> Self {
+        let coords: Vec<C> = iter.into_iter().collect();
+        let mut shape = Self::empty_over(ArrayLattice::new(ArrayBounds::from_offsets(
+            coords.iter().map(|c| c.grid_to_array_offset()),
+        )));
+        for coord in coords {
+            let index = shape
+                .lattice
+                .index(&coord)
+                .expect("Bounds should contain all coordinates");
+            shape.bits[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+        }
+        shape
+    }
+}
+
+impl<C: ArrayOffsetCoord> From<&[C]> for BitShape<C> {
+    fn from(slice: &[C]) -> Self {
+        slice.iter().cloned().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord, const N: usize> From<[C; N]> for BitShape<C> {
+    fn from(array: [C; N]) -> Self {
+        array.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord> From<&HashShape<C>> for BitShape<C> {
+    fn from(shape: &HashShape<C>) -> Self {
+        shape.set.iter().cloned().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord> From<HashShape<C>> for BitShape<C> {
+    fn from(shape: HashShape<C>) -> Self {
+        shape.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord> From<&BitShape<C>> for HashShape<C> {
+    fn from(shape: &BitShape<C>) -> Self {
+        crate::Shape::iter(shape).cloned().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord> From<BitShape<C>> for HashShape<C> {
+    fn from(shape: BitShape<C>) -> Self {
+        shape.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord> IntoIterator for BitShape<C> {
+    type Item = C;
+    type IntoIter = BitShapeIntoIterator<C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BitShapeIntoIterator {
+            coords: self.lattice.coords,
+            bits: self.bits.into_iter(),
+            word_index: 0,
+            word: BitIter(0),
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord> std::ops::Sub for BitShape<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        crate::Shape::difference(&self, &rhs)
+    }
+}
+
+impl<C: ArrayOffsetCoord> std::ops::Sub<&BitShape<C>> for BitShape<C> {
+    type Output = Self;
+
+    fn sub(self, rhs: &Self) -> Self::Output {
+        crate::Shape::difference(&self, rhs)
+    }
+}
+
+impl<C: ArrayOffsetCoord> std::ops::Sub<BitShape<C>> for &BitShape<C> {
+    type Output = BitShape<C>;
+
+    fn sub(self, rhs: BitShape<C>) -> Self::Output {
+        crate::Shape::difference(self, &rhs)
+    }
+}
+
+impl<C: ArrayOffsetCoord> std::ops::Sub<&BitShape<C>> for &BitShape<C> {
+    type Output = BitShape<C>;
+
+    fn sub(self, rhs: &BitShape<C>) -> Self::Output {
+        crate::Shape::difference(self, rhs)
+    }
+}
+
+shape_binary_op!(BitShape, ArrayOffsetCoord, BitOr, bitor, union);
+shape_binary_op!(BitShape, ArrayOffsetCoord, BitAnd, bitand, intersection);
+shape_binary_op!(BitShape, ArrayOffsetCoord, BitXor, bitxor, symmetric_difference);
+
+impl<C: ArrayOffsetCoord> std::ops::Not for BitShape<C> {
+    type Output = Self;
+
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
+}
+
+impl<C: ArrayOffsetCoord> std::ops::Not for &BitShape<C> {
+    type Output = BitShape<C>;
+
+    fn not(self) -> Self::Output {
+        self.complement()
+    }
+}
+
+impl<C: ArrayOffsetCoord> Extend<C> for BitShape<C> {
+    fn extend<I: IntoIterator<Item=C>>(&mut self, iter: I) {
+        for coord in iter {
+            crate::Shape::insert(self, coord);
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord> crate::Shape<C> for BitShape<C> {
+    type Iterator<'a>
+    = BitShapeIterator<'a, C>
+    where
+        Self: 'a,
+        C: 'a;
+
+    fn new() -> Self {
+        Self::empty_over(ArrayLattice::default())
+    }
+
+    fn contains(&self, coord: &C) -> bool {
+        self.lattice
+            .index(coord)
+            .is_some_and(|index| self.bits[index / WORD_BITS] & (1 << (index % WORD_BITS)) != 0)
+    }
+
+    fn is_subshape(&self, other: &Self) -> bool {
+        let aligned = other.aligned_bits(&self.lattice.bounds);
+        self.bits
+            .iter()
+            .zip(aligned.iter())
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    fn is_supershape(&self, other: &Self) -> bool {
+        other.is_subshape(self)
+    }
+
+    fn is_disjoint(&self, other: &Self) -> bool {
+        let aligned = other.aligned_bits(&self.lattice.bounds);
+        self.bits
+            .iter()
+            .zip(aligned.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bits.iter().all(|w| *w == 0)
+    }
+
+    fn len(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    fn insert(&mut self, coord: C) -> bool {
+        let index = match self.lattice.index(&coord) {
+            Some(index) => index,
+            None => {
+                let lattice =
+                    ArrayLattice::new(self.lattice.bounds.grow(coord.grid_to_array_offset()));
+                self.bits = self.aligned_bits(&lattice.bounds).into_owned();
+                self.lattice = lattice;
+                self.lattice
+                    .index(&coord)
+                    .expect("Grown bounds should contain the coordinate")
+            }
+        };
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.bits[index / WORD_BITS];
+        let added = *word & mask == 0;
+        *word |= mask;
+        added
+    }
+
+    fn remove(&mut self, coord: &C) -> bool {
+        let Some(index) = self.lattice.index(coord) else {
+            return false;
+        };
+        let mask = 1 << (index % WORD_BITS);
+        let word = &mut self.bits[index / WORD_BITS];
+        let removed = *word & mask != 0;
+        *word &= !mask;
+        removed
+    }
+
+    /// The result spans the regions of both shapes.
+    fn union<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// The result has the same region as this shape.
+    fn intersection<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        let aligned = other.aligned_bits(&self.lattice.bounds);
+        self.with_bits(self.bits.iter().zip(aligned.iter()).map(|(a, b)| a & b))
+    }
+
+    /// The result has the same region as this shape.
+    fn difference<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        let aligned = other.aligned_bits(&self.lattice.bounds);
+        self.with_bits(self.bits.iter().zip(aligned.iter()).map(|(a, b)| a & !b))
+    }
+
+    /// The result spans the regions of both shapes.
+    fn symmetric_difference<'a>(&'a self, other: &'a Self) -> Self
+    where
+        C: 'a,
+    {
+        self.combine(other, |a, b| a ^ b)
+    }
+
+    fn iter<'a>(&'a self) -> Self::Iterator<'a>
+    where
+        C: 'a,
+    {
+        BitShapeIterator {
+            coords: &self.lattice.coords,
+            bits: self.bits.iter().enumerate(),
+            word_index: 0,
+            word: BitIter(0),
+        }
+    }
+}
+
+impl<MC: ModuleCoord + ArrayOffsetCoord> crate::ModuleShape<MC> for BitShape<MC>
+where
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    fn translate(&self, offset: &MC) -> Self {
+        // Translation does not necessarily correspond to a uniform shift of
+        // array offsets (e.g. for hexagonal grids), so rebuild the shape.
+        <Self as crate::Shape<MC>>::iter(self)
+            .map(|coord| coord + offset)
+            .collect()
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct BitShapeIterator<'a, C: Coord + 'a> {
+    coords: &'a [C],
+    bits: std::iter::Enumerate<std::slice::Iter<'a, u64>>,
+    word_index: usize,
+    word: BitIter,
+}
+
+impl<'a, C: Coord + 'a> Iterator for BitShapeIterator<'a, C> {
+    type Item = &'a C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bit) = self.word.next() {
+                return Some(&self.coords[self.word_index * WORD_BITS + bit]);
+            }
+            let (word_index, word) = self.bits.next()?;
+            self.word_index = word_index;
+            self.word = BitIter(*word);
+        }
+    }
+}
+
+impl<'a, C: Coord + 'a> crate::ShapeIterator<'a, C> for BitShapeIterator<'a, C> {}
+
+/// An iterator that moves coordinates out of a `BitShape`.
+pub struct BitShapeIntoIterator<C: Coord> {
+    coords: Arc<[C]>,
+    bits: std::vec::IntoIter<u64>,
+    word_index: usize,
+    word: BitIter,
+}
+
+impl<C: Coord> Iterator for BitShapeIntoIterator<C> {
+    type Item = C;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(bit) = self.word.next() {
+                // The word index is advanced when loading the word.
+                return Some(self.coords[(self.word_index - 1) * WORD_BITS + bit].clone());
+            }
+            self.word = BitIter(self.bits.next()?);
+            self.word_index += 1;
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A `ShapeContainer` backed by a dense, contiguous array of values.  Values
+/// are indexed by the `Coord::grid_to_array_offset` of their coordinate,
+/// so `get`, `get_mut`, and `insert` within the current bounds of the
+/// container take constant time and involve no hashing.
+///
+/// The coordinates spanned by the bounds are computed once using
+/// `ArrayOffsetCoord::array_offset_to_grid` and shared between clones, so
+/// cloning a container only copies its values.  This makes it well suited
+/// for game boards that are copied from turn to turn.  For the same reason,
+/// `as_shape` produces a `BitShape` over the same region without
+/// recomputing any coordinates.
+///
+/// Inserting a coordinate outside the current bounds will grow the
+/// container, which takes time proportional to the size of the new bounds.
+/// Memory usage is proportional to the size of the bounding rectangle in
+/// array offsets, rather than the number of coordinates, so sparse shapes
+/// are better represented with a `HashShapeContainer`.
+#[derive(Clone)]
+pub struct ArrayShapeContainer<C: ArrayOffsetCoord, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    lattice: ArrayLattice<C>,
+    /// The value, if any, associated with each coordinate in the lattice.
+    values: Vec<Option<V>>,
+    /// The number of coordinates with an associated value.
+    len: usize,
+}
+
+impl<C: ArrayOffsetCoord, V> ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    /// Create an empty `ArrayShapeContainer`.
+    pub fn new() -> Self {
+        Self::over(ArrayLattice::default())
+    }
+
+    /// Internal helper to create an empty container over the given lattice.
+    fn over(lattice: ArrayLattice<C>) -> Self {
+        Self {
+            values: vec![None; lattice.bounds.len()],
+            lattice,
+            len: 0,
+        }
+    }
+
+    /// Create an `ArrayShapeContainer` associating every coordinate of the
+    /// given `Shape` with the value `v`.
+    pub fn from_shape_value<S>(shape: S, v: V) -> Self
+    where
+        S: crate::Shape<C> + std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        Self::from_iter_value(shape.iter().cloned(), v)
+    }
+
+    /// Create an `ArrayShapeContainer` associating every coordinate
+    /// produced by the iterator with the value `v`.
+    pub fn from_iter_value<I: IntoIterator<Item=C>>(iter: I, v: V) -> Self {
+        iter.into_iter().zip(std::iter::repeat(v)).collect()
+    }
+
+    /// Internal helper to grow the bounds of the container to include the
+    /// given array offset, relocating the existing values.
+    fn grow(&mut self, offset: (isize, isize)) {
+        let bounds = self.lattice.bounds;
+        let mut grown = Self::over(ArrayLattice::new(bounds.grow(offset)));
+        for (old_offset, value) in bounds.offsets().zip(self.values.drain(..)) {
+            if value.is_some() {
+                let index = grown
+                    .lattice
+                    .bounds
+                    .index(old_offset)
+                    .expect("Grown bounds should contain the existing bounds");
+                grown.values[index] = value;
+            }
+        }
+        grown.len = self.len;
+        *self = grown;
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Default for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Debug for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(crate::ShapeContainer::iter(self)).finish()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> PartialEq for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        // Containers with different bounds may still hold the same contents.
+        use crate::ShapeContainer;
+        self.len == other.len && self.iter().all(|(c, v)| other.get(c) == Some(v))
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Eq for ArrayShapeContainer<C, V> where
+    V: Debug + Clone + PartialEq + Eq + Hash
+{}
+
+impl<C: ArrayOffsetCoord, V> Hash for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Iteration is in row-major order of array offsets, regardless
+        // of the bounds, so it is consistent with equality.
+        for (coord, value) in crate::ShapeContainer::iter(self) {
+            coord.hash(state);
+            value.hash(state);
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> FromIterator<(C, V)> for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item=(C, V)>>(iter: I) -> Self {
+        let pairs: Vec<(C, V)> = iter.into_iter().collect();
+        let mut container = Self::over(ArrayLattice::new(ArrayBounds::from_offsets(
+            pairs.iter().map(|(c, _)| c.grid_to_array_offset()),
+        )));
+        for (coord, value) in pairs {
+            crate::ShapeContainer::insert(&mut container, coord, value);
+        }
+        container
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> From<HashShapeContainer<C, V>> for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from(container: HashShapeContainer<C, V>) -> Self {
+        container.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> From<ArrayShapeContainer<C, V>> for HashShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from(container: ArrayShapeContainer<C, V>) -> Self {
+        container.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> IntoIterator for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    type Item = (C, V);
+    type IntoIter = ArrayShapeContainerIntoIterator<C, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        ArrayShapeContainerIntoIterator {
+            coords: self.lattice.coords,
+            index: 0,
+            inner: self.values.into_iter(),
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> crate::ShapeContainer<C, V> for ArrayShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    type Iterator<'a>
+    = ArrayShapeContainerIterator<'a, C, V>
+    where
+        Self: 'a,
+        C: 'a,
+        V: 'a;
+
+    type Shape = BitShape<C>;
+
+    fn contains(&self, coord: &C) -> bool {
+        self.get(coord).is_some()
+    }
+
+    fn get(&self, coord: &C) -> Option<&V> {
+        let index = self.lattice.index(coord)?;
+        self.values[index].as_ref()
+    }
+
+    fn get_mut(&mut self, coord: &C) -> Option<&mut V> {
+        let index = self.lattice.index(coord)?;
+        self.values[index].as_mut()
+    }
+
+    fn insert(&mut self, coord: C, value: V) -> Option<V> {
+        let index = match self.lattice.index(&coord) {
+            Some(index) => index,
+            None => {
+                self.grow(coord.grid_to_array_offset());
+                self.lattice
+                    .index(&coord)
+                    .expect("Grown bounds should contain the coordinate")
+            }
+        };
+        let result = self.values[index].replace(value);
+        if result.is_none() {
+            self.len += 1;
+        }
+        result
+    }
+
+    fn remove(&mut self, coord: &C) -> Option<V> {
+        let index = self.lattice.index(coord)?;
+        let result = self.values[index].take();
+        if result.is_some() {
+            self.len -= 1;
+        }
+        result
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn as_shape(&self) -> Self::Shape {
+        let mut shape = BitShape::empty_over(self.lattice.clone());
+        for (index, value) in self.values.iter().enumerate() {
+            if value.is_some() {
+                shape.bits[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+            }
+        }
+        shape
+    }
+
+    fn iter<'a>(&'a self) -> Self::Iterator<'a>
+    where
+        C: 'a,
+        V: 'a,
+    {
+        ArrayShapeContainerIterator {
+            inner: self.lattice.coords.iter().zip(self.values.iter()),
+        }
+    }
+}
+
+impl<MC: ModuleCoord + ArrayOffsetCoord, V> crate::ModuleShapeContainer<MC, V>
+for ArrayShapeContainer<MC, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    fn translate(&self, offset: &MC) -> Self {
+        // Translation does not necessarily correspond to a uniform shift of
+        // array offsets (e.g. for hexagonal grids), so rebuild the container.
+        <Self as crate::ShapeContainer<MC, V>>::iter(self)
+            .map(|(coord, value)| (coord + offset, value.clone()))
+            .collect()
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct ArrayShapeContainerIterator<'a, C: Coord + 'a, V: 'a> {
+    inner: std::iter::Zip<std::slice::Iter<'a, C>, std::slice::Iter<'a, Option<V>>>,
+}
+
+impl<'a, C: Coord + 'a, V: 'a> Iterator for ArrayShapeContainerIterator<'a, C, V> {
+    type Item = (&'a C, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Skip over any coordinates without an associated value.
+        self.inner
+            .by_ref()
+            .find_map(|(coord, opt_value)| opt_value.as_ref().map(|value| (coord, value)))
+    }
+}
+
+impl<'a, C: Coord + 'a, V: 'a> crate::ShapeContainerIterator<'a, C, V>
+for ArrayShapeContainerIterator<'a, C, V>
+{}
+
+/// An iterator that moves coordinates and values out of an
+/// `ArrayShapeContainer`.
+pub struct ArrayShapeContainerIntoIterator<C: Coord, V> {
+    coords: Arc<[C]>,
+    index: usize,
+    inner: std::vec::IntoIter<Option<V>>,
+}
+
+impl<C: Coord, V> Iterator for ArrayShapeContainerIntoIterator<C, V> {
+    type Item = (C, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for opt_value in self.inner.by_ref() {
+            let index = self.index;
+            self.index += 1;
+            if let Some(value) = opt_value {
+                return Some((self.coords[index].clone(), value));
+            }
+        }
+        None
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The chunk size used by `ChunkedShapeContainer::new`.
+pub const DEFAULT_CHUNK_SIZE: usize = 32;
+
+/// A square tile of array offsets within a `ChunkedShapeContainer`, backed
+/// by a dense array indexed in the same manner as an `ArrayShapeContainer`.
+/// Rather than a lattice of every coordinate in the chunk, only those with
+/// an associated value are stored, alongside the value.
+///
+/// Chunks are identified by their key, which is the array offset of the
+/// chunk's minimum corner divided by the chunk size.
+#[derive(Clone)]
+pub struct Chunk<C: ArrayOffsetCoord, V> {
+    key: (isize, isize),
+    bounds: ArrayBounds,
+    /// The coordinate and value, if any, for each array offset in the
+    /// bounds.
+    entries: Vec<Option<(C, V)>>,
+    /// The number of coordinates with an associated value.
+    len: usize,
+}
+
+impl<C: ArrayOffsetCoord, V: Clone> Chunk<C, V> {
+    /// Internal helper to create an empty chunk with the given key.
+    fn new(key: (isize, isize), size: usize) -> Self {
+        let isize = size as isize;
+        let bounds = ArrayBounds {
+            min: (key.0 * isize, key.1 * isize),
+            width: size,
+            height: size,
+        };
+        Self {
+            key,
+            bounds,
+            entries: vec![None; bounds.len()],
+            len: 0,
+        }
+    }
+
+    /// The key identifying this chunk.
+    pub fn key(&self) -> (isize, isize) {
+        self.key
+    }
+
+    /// Are there no values in this chunk?
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of coordinates in this chunk with an associated value.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Iterate over every coordinate spanned by this chunk, whether or not
+    /// it has an associated value, in row-major order of array offsets.
+    pub fn coords(&self) -> impl Iterator<Item=C> + use<C, V> {
+        self.bounds.offsets().map(C::array_offset_to_grid)
+    }
+
+    /// Checks whether the given coordinate has an associated value in this
+    /// chunk.
+    pub fn contains(&self, coord: &C) -> bool {
+        self.get(coord).is_some()
+    }
+
+    /// Retrieves the value associated with the given coordinate, or `None`
+    /// if it has no value or lies outside the chunk.
+    pub fn get(&self, coord: &C) -> Option<&V> {
+        let index = self.bounds.index(coord.grid_to_array_offset())?;
+        self.entries[index].as_ref().map(|(_, value)| value)
+    }
+
+    /// Retrieves a mutable reference to the value associated with the given
+    /// coordinate, or `None` if it has no value or lies outside the chunk.
+    pub fn get_mut(&mut self, coord: &C) -> Option<&mut V> {
+        let index = self.bounds.index(coord.grid_to_array_offset())?;
+        self.entries[index].as_mut().map(|(_, value)| value)
+    }
+
+    /// Associates a value with the given coordinate, returning the previous
+    /// value, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the coordinate lies outside the chunk.
+    pub fn insert(&mut self, coord: C, value: V) -> Option<V> {
+        let index = self
+            .bounds
+            .index(coord.grid_to_array_offset())
+            .unwrap_or_else(|| panic!("Coordinate {} lies outside the chunk", coord));
+        let result = self.entries[index].replace((coord, value)).map(|(_, value)| value);
+        if result.is_none() {
+            self.len += 1;
+        }
+        result
+    }
+
+    /// Removes the value associated with the given coordinate, returning it
+    /// if it was present.
+    pub fn remove(&mut self, coord: &C) -> Option<V> {
+        let index = self.bounds.index(coord.grid_to_array_offset())?;
+        let result = self.entries[index].take().map(|(_, value)| value);
+        if result.is_some() {
+            self.len -= 1;
+        }
+        result
+    }
+
+    /// Iterate over the coordinates and values in this chunk, in row-major
+    /// order of array offsets.
+    pub fn iter(&self) -> ChunkIterator<'_, C, V> {
+        ChunkIterator {
+            inner: self.entries.iter().flatten(),
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V: Clone + Debug> Debug for Chunk<C, V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Chunk")
+            .field("key", &self.key)
+            .field("values", &self.iter().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<'a, C: ArrayOffsetCoord, V: Clone> IntoIterator for &'a Chunk<C, V> {
+    type Item = (&'a C, &'a V);
+    type IntoIter = ChunkIterator<'a, C, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> IntoIterator for Chunk<C, V> {
+    type Item = (C, V);
+    type IntoIter = std::iter::Flatten<std::vec::IntoIter<Option<(C, V)>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter().flatten()
+    }
+}
+
+/// An iterator over the coordinates and values of a `Chunk`.
+pub struct ChunkIterator<'a, C: Coord + 'a, V: 'a> {
+    inner: std::iter::Flatten<std::slice::Iter<'a, Option<(C, V)>>>,
+}
+
+impl<'a, C: Coord + 'a, V: 'a> Iterator for ChunkIterator<'a, C, V> {
+    type Item = (&'a C, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(coord, value)| (coord, value))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A hook called when a `ChunkedShapeContainer` loads a chunk.
+type LoadHook<C, V> = Arc<dyn Fn(&mut Chunk<C, V>) + Send + Sync>;
+
+/// A hook called when a `ChunkedShapeContainer` unloads a chunk.
+type UnloadHook<C, V> = Arc<dyn Fn(&Chunk<C, V>) + Send + Sync>;
+
+/// A sparse `ShapeContainer` for very large or unbounded grids.  The array
+/// offsets of the grid are partitioned into square `Chunk`s of a fixed
+/// size, each backed by a dense array, and chunks are only allocated once
+/// a value is inserted into them.  So memory usage is proportional to the
+/// number of chunks in use, and `get`, `get_mut`, and `insert` involve a
+/// single hash lookup per chunk rather than per coordinate.
+///
+/// Chunks may be loaded and unloaded explicitly, for example as a player
+/// moves around a procedurally generated world.  A hook registered with
+/// `on_load` is called whenever a chunk is allocated, and may populate it,
+/// while a hook registered with `on_unload` is called with the contents
+/// of a chunk before it is discarded.  Removing every value from a chunk
+/// does not unload it.
+///
+/// Iteration proceeds chunk by chunk, in no particular order of chunks.
+/// Equality and hashing only consider the coordinates and values held, so
+/// containers with different chunk sizes or hooks may be equal.
+#[derive(Clone)]
+pub struct ChunkedShapeContainer<C: ArrayOffsetCoord, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    chunk_size: usize,
+    chunks: HashMap<(isize, isize), Chunk<C, V>>,
+    /// The number of coordinates with an associated value.
+    len: usize,
+    on_load: Option<LoadHook<C, V>>,
+    on_unload: Option<UnloadHook<C, V>>,
+}
+
+impl<C: ArrayOffsetCoord, V> ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    /// Create an empty `ChunkedShapeContainer` with chunks of
+    /// `DEFAULT_CHUNK_SIZE` array offsets square.
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Create an empty `ChunkedShapeContainer` with chunks of the given
+    /// number of array offsets square.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the chunk size is zero.
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "Chunk size must be positive");
+        Self {
+            chunk_size,
+            chunks: HashMap::new(),
+            len: 0,
+            on_load: None,
+            on_unload: None,
+        }
+    }
+
+    /// Create a `ChunkedShapeContainer` associating every coordinate of the
+    /// given `Shape` with the value `v`.
+    pub fn from_shape_value<S>(shape: S, v: V) -> Self
+    where
+        S: crate::Shape<C> + std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        Self::from_iter_value(shape.iter().cloned(), v)
+    }
+
+    /// Create a `ChunkedShapeContainer` associating every coordinate
+    /// produced by the iterator with the value `v`.
+    pub fn from_iter_value<I: IntoIterator<Item=C>>(iter: I, v: V) -> Self {
+        iter.into_iter().zip(std::iter::repeat(v)).collect()
+    }
+
+    /// Register a hook to be called with each newly allocated chunk,
+    /// replacing any existing hook.  Values the hook inserts into the chunk
+    /// become part of the container.
+    pub fn on_load<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut Chunk<C, V>) + Send + Sync + 'static,
+    {
+        self.on_load = Some(Arc::new(hook));
+        self
+    }
+
+    /// Register a hook to be called with each chunk before it is unloaded,
+    /// replacing any existing hook.
+    pub fn on_unload<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Chunk<C, V>) + Send + Sync + 'static,
+    {
+        self.on_unload = Some(Arc::new(hook));
+        self
+    }
+
+    /// The number of array offsets along each side of a chunk.
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// The key of the chunk spanning the given coordinate.
+    pub fn chunk_key(&self, coord: &C) -> (isize, isize) {
+        let (x, y) = coord.grid_to_array_offset();
+        let size = self.chunk_size as isize;
+        (x.div_euclid(size), y.div_euclid(size))
+    }
+
+    /// Is the chunk with the given key currently loaded?
+    pub fn is_loaded(&self, key: (isize, isize)) -> bool {
+        self.chunks.contains_key(&key)
+    }
+
+    /// Retrieve the loaded chunk with the given key, if any.
+    pub fn chunk(&self, key: (isize, isize)) -> Option<&Chunk<C, V>> {
+        self.chunks.get(&key)
+    }
+
+    /// Iterate over the loaded chunks, in no particular order.
+    pub fn chunks(&self) -> impl Iterator<Item=&Chunk<C, V>> {
+        self.chunks.values()
+    }
+
+    /// Load the chunk with the given key, allocating it and calling the
+    /// load hook if it is not already loaded.
+    pub fn load_chunk(&mut self, key: (isize, isize)) -> &Chunk<C, V> {
+        self.loaded_chunk_mut(key)
+    }
+
+    /// Unload the chunk with the given key, calling the unload hook and
+    /// returning the chunk, if it was loaded.
+    pub fn unload_chunk(&mut self, key: (isize, isize)) -> Option<Chunk<C, V>> {
+        let chunk = self.chunks.remove(&key)?;
+        if let Some(hook) = &self.on_unload {
+            hook(&chunk);
+        }
+        self.len -= chunk.len;
+        Some(chunk)
+    }
+
+    /// Unload every chunk for which the predicate does not hold, calling
+    /// the unload hook for each.
+    pub fn retain_chunks<F: FnMut(&Chunk<C, V>) -> bool>(&mut self, mut keep: F) {
+        let keys: Vec<(isize, isize)> = self
+            .chunks
+            .values()
+            .filter(|chunk| !keep(chunk))
+            .map(|chunk| chunk.key)
+            .collect();
+        for key in keys {
+            self.unload_chunk(key);
+        }
+    }
+
+    /// Internal helper to obtain the chunk with the given key, loading it
+    /// if needed.
+    fn loaded_chunk_mut(&mut self, key: (isize, isize)) -> &mut Chunk<C, V> {
+        match self.chunks.entry(key) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                let chunk = entry.insert(Chunk::new(key, self.chunk_size));
+                if let Some(hook) = &self.on_load {
+                    hook(chunk);
+                }
+                self.len += chunk.len;
+                chunk
+            }
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Default for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Debug for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map().entries(crate::ShapeContainer::iter(self)).finish()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> PartialEq for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn eq(&self, other: &Self) -> bool {
+        use crate::ShapeContainer;
+        self.len == other.len && self.iter().all(|(c, v)| other.get(c) == Some(v))
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Eq for ChunkedShapeContainer<C, V> where
+    V: Debug + Clone + PartialEq + Eq + Hash
+{}
+
+impl<C: ArrayOffsetCoord, V> Hash for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // Order-independent hashing, as the order of iteration depends upon
+        // the chunk size and the order chunks were loaded.
+        let mut hashes: Vec<u64> = crate::ShapeContainer::iter(self)
+            .map(|(key, value)| {
+                let mut hasher = std::hash::DefaultHasher::new();
+                key.hash(&mut hasher);
+                value.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        hashes.sort_unstable();
+        for h in hashes {
+            h.hash(state);
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> FromIterator<(C, V)> for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from_iter<I: IntoIterator<Item=(C, V)>>(iter: I) -> Self {
+        let mut container = Self::new();
+        container.extend(iter);
+        container
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> Extend<(C, V)> for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn extend<I: IntoIterator<Item=(C, V)>>(&mut self, iter: I) {
+        for (coord, value) in iter {
+            crate::ShapeContainer::insert(self, coord, value);
+        }
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> From<HashShapeContainer<C, V>> for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from(container: HashShapeContainer<C, V>) -> Self {
+        container.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> From<ChunkedShapeContainer<C, V>> for HashShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    fn from(container: ChunkedShapeContainer<C, V>) -> Self {
+        container.into_iter().collect()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> IntoIterator for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    type Item = (C, V);
+    type IntoIter =
+    std::iter::Flatten<std::collections::hash_map::IntoValues<(isize, isize), Chunk<C, V>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.chunks.into_values().flatten()
+    }
+}
+
+impl<C: ArrayOffsetCoord, V> crate::ShapeContainer<C, V> for ChunkedShapeContainer<C, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+{
+    type Iterator<'a>
+    = ChunkedShapeContainerIterator<'a, C, V>
+    where
+        Self: 'a,
+        C: 'a,
+        V: 'a;
+
+    type Shape = HashShape<C>;
+
+    fn contains(&self, coord: &C) -> bool {
+        self.get(coord).is_some()
+    }
+
+    fn get(&self, coord: &C) -> Option<&V> {
+        self.chunks.get(&self.chunk_key(coord))?.get(coord)
+    }
+
+    fn get_mut(&mut self, coord: &C) -> Option<&mut V> {
+        let key = self.chunk_key(coord);
+        self.chunks.get_mut(&key)?.get_mut(coord)
+    }
+
+    fn insert(&mut self, coord: C, value: V) -> Option<V> {
+        let key = self.chunk_key(&coord);
+        let result = self.loaded_chunk_mut(key).insert(coord, value);
+        if result.is_none() {
+            self.len += 1;
+        }
+        result
+    }
+
+    fn remove(&mut self, coord: &C) -> Option<V> {
+        let key = self.chunk_key(coord);
+        let result = self.chunks.get_mut(&key)?.remove(coord);
+        if result.is_some() {
+            self.len -= 1;
+        }
+        result
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn as_shape(&self) -> Self::Shape {
+        self.iter().map(|(coord, _)| coord.clone()).collect()
+    }
+
+    fn iter<'a>(&'a self) -> Self::Iterator<'a>
+    where
+        C: 'a,
+        V: 'a,
+    {
+        ChunkedShapeContainerIterator {
+            inner: self.chunks.values().flatten(),
+        }
+    }
+}
+
+impl<MC: ModuleCoord + ArrayOffsetCoord, V> crate::ModuleShapeContainer<MC, V>
+for ChunkedShapeContainer<MC, V>
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    /// Translate the container, producing a container with the same chunk
+    /// size but without any hooks, so that no chunks are populated.
+    fn translate(&self, offset: &MC) -> Self {
+        let mut result = Self::with_chunk_size(self.chunk_size);
+        result.extend(
+            <Self as crate::ShapeContainer<MC, V>>::iter(self)
+                .map(|(coord, value)| (coord + offset, value.clone())),
+        );
+        result
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+pub struct ChunkedShapeContainerIterator<'a, C: ArrayOffsetCoord + 'a, V: Clone + 'a> {
+    inner: std::iter::Flatten<std::collections::hash_map::Values<'a, (isize, isize), Chunk<C, V>>>,
+}
+
+impl<'a, C: ArrayOffsetCoord + 'a, V: Clone + 'a> Iterator
+for ChunkedShapeContainerIterator<'a, C, V>
+{
+    type Item = (&'a C, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+}
+
+impl<'a, C: ArrayOffsetCoord + 'a, V: Clone + 'a> crate::ShapeContainerIterator<'a, C, V>
+for ChunkedShapeContainerIterator<'a, C, V>
+{}
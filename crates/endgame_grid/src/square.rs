@@ -1,12 +1,15 @@
 use crate::shape::HashShape;
-use crate::utils::{vertices_to_edges, ModuleCoordIter};
-use crate::{AllowedCoordIterRange, Color, DirectionType, ModuleCoord, Point};
+use crate::notation::{format_label, parse_component, parse_label, parse_tuple, ParseCoordError};
+use crate::utils::{vertices_to_edges, Cover, ModuleCoordIter};
+use crate::visibility::SightCell;
+use crate::{AllowedCoordIterRange, Color, DirectionType, Metric, ModuleCoord, Point};
 use endgame_direction::{Direction, DirectionSet};
 use glam::{ivec2, IVec2, Mat2, Vec2};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::fmt::Display;
+use std::str::FromStr;
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -64,6 +67,21 @@ impl Coord {
         Coord(ivec2(array_offset.0 as i32, array_offset.1 as i32))
     }
 
+    /// Produce a chess-like label for the coordinate, with the x component
+    /// as a lettered column and the y component as a row numbered from one.
+    /// So the origin is `a1` and `Coord::new(4, 3)` is `e4`.  Coordinates
+    /// with negative components have no label.
+    pub fn to_label(&self) -> Option<String> {
+        format_label(self.0.x, self.0.y)
+    }
+
+    /// Parse a chess-like label, as produced by `to_label`.  Columns may be
+    /// lettered in either case.
+    pub fn from_label(label: &str) -> Result<Self, ParseCoordError> {
+        let (x, y) = parse_label(label)?;
+        Ok(Coord::new(x, y))
+    }
+
     /// Construct a new `SquareGridCoord` from an `IVec2`.
     pub const fn from_ivec2(coord: IVec2) -> Self {
         Coord(coord)
@@ -98,6 +116,67 @@ impl Coord {
         }
         HashShape::from_iter(coords.into_iter())
     }
+
+    /// Produce a rectangle of the given `width` and `height`, extending from
+    /// the origin in the positive direction along each axis.
+    pub fn rectangle(width: usize, height: usize) -> HashShape<Coord> {
+        Coord::parallelogram(Axes::X, width, Axes::Y, height)
+    }
+
+    /// Produce a parallelogram extending from the origin in the positive
+    /// direction along each of the two given axes, with `first_len`
+    /// coordinates along the `first` axis and `second_len` along the
+    /// `second`.  On square grids this is always a rectangle.
+    pub fn parallelogram(
+        first: Axes,
+        first_len: usize,
+        second: Axes,
+        second_len: usize,
+    ) -> HashShape<Coord> {
+        crate::utils::parallelogram(Coord::default(), first, first_len, second, second_len)
+    }
+
+    /// Produce a right triangle with legs of `size` coordinates, extending
+    /// from the origin in the positive direction along each axis.  This has
+    /// the same number of coordinates as a triangle of the same `size` on a
+    /// hexagonal grid.
+    pub fn triangle(size: usize) -> HashShape<Coord> {
+        let isize = size as i32;
+        let mut coords = Vec::new();
+        for x in 0..isize {
+            for y in 0..isize - x {
+                coords.push(Coord::new(x, y));
+            }
+        }
+        HashShape::from_iter(coords)
+    }
+
+    /// Produce a hexagon of the given `radius` centered on the origin, by
+    /// additionally cutting the corners of the `range` along one diagonal.
+    /// This has the same number of coordinates as a hexagon of the same
+    /// `radius` on a hexagonal grid.
+    pub fn hexagon(radius: usize) -> HashShape<Coord> {
+        let iradius = radius as i32;
+        Coord::range(radius)
+            .into_iter()
+            .filter(|coord| (coord.0.x + coord.0.y).abs() <= iradius)
+            .collect()
+    }
+
+    /// Produce the coordinates at the given `radius` from the origin under
+    /// the given `Metric`.  With `Metric::Vertex` this is the same as
+    /// `ring`.
+    pub fn metric_ring(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_ring(Coord::default(), metric, radius)
+    }
+
+    /// Produce the coordinates within the given `radius` of the origin under
+    /// the given `Metric`.  These form a diamond with `Metric::Face`, a
+    /// square the same as `range` with `Metric::Vertex`, and a disc with
+    /// `Metric::Euclidean`.
+    pub fn metric_range(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_range(Coord::default(), metric, radius)
+    }
 }
 impl Default for Coord {
     fn default() -> Self {
@@ -111,6 +190,16 @@ impl Display for Coord {
     }
 }
 
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    /// Parse a coordinate of the form `(x,y)`, as produced by `Display`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y] = parse_tuple(s, "a square coordinate of the form (x,y)")?;
+        Ok(Coord::new(parse_component(x)?, parse_component(y)?))
+    }
+}
+
 impl std::ops::Neg for Coord {
     type Output = Self;
 
@@ -219,6 +308,16 @@ impl crate::Coord for Coord {
         (other.0 - self.0).abs().element_sum() as usize
     }
 
+    fn vertex_distance(&self, other: &Self) -> usize {
+        // Diagonal steps cover both axes at once, giving the Chebyshev
+        // distance.
+        (other.0 - self.0).abs().max_element() as usize
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        (other.0 - self.0).as_vec2().length()
+    }
+
     fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
         use Direction::*;
         use DirectionType::*;
@@ -287,6 +386,19 @@ impl crate::Coord for Coord {
         SquarePathIter::new(self, other)
     }
 
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        SquareLineIter::new(self, other)
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        // Each square spans two units along each axis, with its center in
+        // the middle.
+        let lanes = |coord: &Self| [2 * coord.0.x as i64 - 1, 2 * coord.0.y as i64 - 1];
+        crate::utils::lane_walk(lanes(self), lanes(other), 2, |_| true, Cover::Super)
+            .into_iter()
+            .map(|[x, y]| Coord::new(x as i32, y as i32))
+    }
+
     fn axis_iterator<RB: AllowedCoordIterRange>(
         &self,
         axis: Self::Axes,
@@ -391,6 +503,97 @@ impl ModuleCoord for Coord {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+impl crate::topology::WrappingCoord for Coord {}
+
+impl crate::canonical::CanonicalCoord for Coord {
+    fn translate_from(&self, anchor: &Self) -> Self {
+        *self - *anchor
+    }
+}
+
+impl crate::symmetry::SymmetricCoord for Coord {
+    const ROTATIONS: usize = 4;
+    const REFLECTION_AXIS: Axes = Axes::X;
+
+    fn reflection_steps(axis: Axes) -> usize {
+        use Axes::*;
+        match axis {
+            X => 0,
+            Y => 2,
+        }
+    }
+}
+
+impl crate::text::TextCoord for Coord {
+    fn to_text_offset(&self) -> (i32, i32) {
+        (self.0.x, self.0.y)
+    }
+
+    fn from_text_offset(offset: (i32, i32)) -> Self {
+        Coord::new(offset.0, offset.1)
+    }
+}
+
+impl crate::parts::PartCoord for Coord {
+    fn boundary(&self) -> Vec<(Direction, Direction)> {
+        use Direction::*;
+        vec![(NorthWest, North), (NorthEast, East), (SouthEast, South), (SouthWest, West)]
+    }
+}
+
+impl crate::visibility::SightCoord for Coord {
+    fn sight_lines(&self, other: &Self) -> [Vec<Self>; 2] {
+        // Each square spans two units along each axis, with its center in
+        // the middle, and the segment is displaced perpendicular to itself.
+        let lanes = |coord: &Self| [2 * coord.0.x as i64 - 1, 2 * coord.0.y as i64 - 1];
+        let (start, end) = (lanes(self), lanes(other));
+        let shift = [start[1] - end[1], end[0] - start[0]];
+        [shift, shift.map(|s| -s)].map(|shift| {
+            crate::utils::lane_walk(start, end, 2, |_| true, Cover::Displaced(shift))
+                .into_iter()
+                .map(|[x, y]| Coord::new(x as i32, y as i32))
+                .collect()
+        })
+    }
+
+    fn sight_sectors(&self) -> Vec<[[i64; 2]; 2]> {
+        vec![[[1, 0], [1, 1]]; 8]
+    }
+
+    fn sight_row(&self, sector: usize, depth: usize) -> Vec<SightCell<Self>> {
+        // Each octant is scanned as if it lay between the positive x axis
+        // and the diagonal, and then mapped into place by swapping the axes
+        // and turning.  The rows are then columns moving away from the
+        // origin, scanned upwards.
+        let i = depth as i32 + 1;
+        (0..=i)
+            .map(|j| {
+                let local = if sector.is_multiple_of(2) { ivec2(i, j) } else { ivec2(j, i) };
+                let offset = (0..sector / 2).fold(local, |offset, _| offset.perp());
+                let (i, j) = (i as i64, j as i64);
+                SightCell {
+                    coord: Coord(self.0 + offset),
+                    center: [2 * i, 2 * j],
+                    vertices: vec![
+                        [2 * i - 1, 2 * j - 1],
+                        [2 * i + 1, 2 * j - 1],
+                        [2 * i + 1, 2 * j + 1],
+                        [2 * i - 1, 2 * j + 1],
+                    ],
+                }
+            })
+            .collect()
+    }
+}
+
+impl crate::ArrayOffsetCoord for Coord {
+    fn array_offset_to_grid(array_offset: (isize, isize)) -> Self {
+        Coord::array_offset_to_grid(array_offset)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 #[derive(Debug, Clone)]
 pub struct SquarePathIter {
     current: IVec2,
@@ -466,6 +669,65 @@ impl Iterator for SquarePathIter {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+/// SquareLineIter is an iterator that traverses a line between two
+/// coordinates in a square grid using Bresenham's line algorithm.  Each
+/// step moves along the major axis, and along the minor axis whenever the
+/// accumulated error warrants it, so diagonal steps are taken along
+/// vertex directions.
+#[derive(Debug, Clone)]
+pub struct SquareLineIter {
+    current: IVec2,
+    end: IVec2,
+    delta: IVec2,
+    step: IVec2,
+    error: i32,
+    done: bool,
+}
+
+impl SquareLineIter {
+    /// Create a new `SquareLineIter` that will traverse the line between
+    /// `start` and `end`.
+    pub fn new(start: &Coord, end: &Coord) -> Self {
+        let diff = end.0 - start.0;
+        let delta = IVec2::new(diff.x.abs(), -diff.y.abs());
+        SquareLineIter {
+            current: start.0,
+            end: end.0,
+            delta,
+            step: diff.signum(),
+            error: delta.x + delta.y,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for SquareLineIter {
+    type Item = Coord;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let c = self.current;
+        if c == self.end {
+            self.done = true;
+            return Some(Coord::from_ivec2(c));
+        }
+        let doubled = 2 * self.error;
+        if doubled >= self.delta.y {
+            self.error += self.delta.y;
+            self.current.x += self.step.x;
+        }
+        if doubled <= self.delta.x {
+            self.error += self.delta.x;
+            self.current.y += self.step.y;
+        }
+        Some(Coord::from_ivec2(c))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 // Regular square grids.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub struct SizedGrid {
@@ -0,0 +1,668 @@
+//! Export of grids as SVG images.
+//!
+//! An `SvgWriter` draws cells, shapes, containers and arrows for any
+//! `SizedGrid`, using `SizedGrid::vertices` and `SizedGrid::edges`, and
+//! produces a standalone SVG document.  This allows boards to be drawn for
+//! rulebooks and bug reports without running a user interface.  The styles
+//! mirror those of `endgame_egui`, such as `CellStyle` and `Theme`, but do
+//! not depend upon it.
+//!
+//! Screen space has the y-axis pointing upwards, while SVG has it pointing
+//! downwards, so the y-axis is flipped on output.  Numbers are written with
+//! at most two decimal places and elements are written in the order they
+//! were drawn, so the output is deterministic and suitable for snapshot
+//! tests.
+
+use crate::Color::{Four, One, Three, Two};
+use crate::{outline, Coord, DirectionType, Shape, ShapeContainer, SizedGrid};
+use endgame_direction::Direction;
+use glam::Vec2;
+use std::collections::{HashMap, HashSet};
+use std::f32::consts::FRAC_PI_6;
+use std::fmt::{Debug, Display, Write};
+use std::hash::Hash;
+
+/// The offset, in screen space, of the shadow drawn behind a label.
+const SHADOW_OFFSET: Vec2 = Vec2::new(1.0, -1.0);
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A color with red, green, blue and alpha components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgba {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+impl Rgba {
+    pub const TRANSPARENT: Rgba = Rgba::new(0, 0, 0, 0);
+    pub const BLACK: Rgba = Rgba::rgb(0, 0, 0);
+    pub const WHITE: Rgba = Rgba::rgb(255, 255, 255);
+    pub const GRAY: Rgba = Rgba::rgb(160, 160, 160);
+    pub const RED: Rgba = Rgba::rgb(255, 0, 0);
+
+    /// Construct a color from all of its components.
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Rgba { r, g, b, a }
+    }
+
+    /// Construct an opaque color.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self {
+        Rgba::new(r, g, b, 255)
+    }
+
+    /// Lighten the color by adding the amount to each of the red, green and
+    /// blue components, saturating at the maximum.
+    pub const fn lighten(self, amount: u8) -> Self {
+        Rgba::new(
+            self.r.saturating_add(amount),
+            self.g.saturating_add(amount),
+            self.b.saturating_add(amount),
+            self.a,
+        )
+    }
+
+    /// Darken the color by subtracting the amount from each of the red,
+    /// green and blue components, saturating at zero.
+    pub const fn darken(self, amount: u8) -> Self {
+        Rgba::new(
+            self.r.saturating_sub(amount),
+            self.g.saturating_sub(amount),
+            self.b.saturating_sub(amount),
+            self.a,
+        )
+    }
+}
+
+impl Display for Rgba {
+    /// Display the color in hexadecimal notation, ignoring the alpha
+    /// component.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// `LabelStyle` provides styling information for rendering text labels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelStyle {
+    pub color: Rgba,
+    pub font_size: f32,
+    pub add_shadow: Option<Rgba>,
+}
+
+/// `ArrowStyle` provides styling information for rendering arrows between
+/// coordinates.  If no heads are specified it is the degenerate case of a
+/// line segment with the possibility of a label.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrowStyle {
+    pub color: Rgba,
+    pub width: f32,
+    pub to_head: bool,
+    pub from_head: bool,
+    pub label: Option<LabelStyle>,
+}
+
+/// `CellPrimitiveBorderStyle` provides styling information for rendering
+/// the border of a grid cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellPrimitiveBorderStyle {
+    /// Draw no border.
+    None,
+    /// Draw a border of uniform thickness and color.
+    Uniform(f32, Rgba),
+}
+
+impl CellPrimitiveBorderStyle {
+    /// Get the color of the border style.
+    pub fn color(&self) -> Rgba {
+        match self {
+            CellPrimitiveBorderStyle::None => Rgba::TRANSPARENT,
+            CellPrimitiveBorderStyle::Uniform(_, c) => *c,
+        }
+    }
+
+    /// Get the width of the border style.
+    pub fn width(&self) -> f32 {
+        match self {
+            CellPrimitiveBorderStyle::None => 0.0,
+            CellPrimitiveBorderStyle::Uniform(w, _) => *w,
+        }
+    }
+}
+
+/// `CellBorderStyle` provides styling information for rendering the border
+/// of a grid cell.  It can either be a primitive style applied to the entire
+/// border, or a different style for each edge of the cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CellBorderStyle {
+    /// Just use a primitive style.
+    Primitive(CellPrimitiveBorderStyle),
+    /// Use a different style for each edge.  This map must be a subset of
+    /// the directions that correspond to faces of the cell.
+    PerEdge(HashMap<Direction, CellPrimitiveBorderStyle>),
+}
+
+impl CellBorderStyle {
+    /// Helper to create a `CellBorderStyle` with no border.
+    pub fn none() -> Self {
+        CellBorderStyle::Primitive(CellPrimitiveBorderStyle::None)
+    }
+
+    /// Helper to create a `CellBorderStyle` with a uniform border.
+    pub fn uniform(width: f32, color: Rgba) -> Self {
+        CellBorderStyle::Primitive(CellPrimitiveBorderStyle::Uniform(width, color))
+    }
+}
+
+/// `CellStyle` provides styling information for rendering a grid cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellStyle {
+    pub fill_color: Option<Rgba>,
+    pub border: CellBorderStyle,
+    pub label: Option<LabelStyle>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A `Theme` provides some predefined styling for grid cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Theme {
+    /// A theme reminiscent of a map where no adjacent cells have the same
+    /// color.
+    Map,
+    /// A theme reminiscent of classic graph paper with a light background and
+    /// blue grid lines.
+    GraphPaper,
+}
+
+impl Theme {
+    /// For the given theme, coordinate, and dark mode setting, produce a
+    /// `CellStyle`.
+    pub fn cell_style<C: Coord>(self, coord: &C, dark_mode: bool) -> CellStyle {
+        let coord_color = coord.to_color();
+        match self {
+            Theme::Map => {
+                let fill_color = match coord_color {
+                    One => Rgba::rgb(64, 128, 64),
+                    Two => Rgba::rgb(232, 232, 216),
+                    Three => Rgba::rgb(128, 64, 64),
+                    Four => Rgba::rgb(64, 64, 128),
+                };
+                let (text_color, shadow_color) = match coord_color {
+                    Two => (Rgba::BLACK, Rgba::GRAY),
+                    One | Three | Four => (Rgba::WHITE, Rgba::BLACK),
+                };
+                let border = if coord.is_origin() {
+                    let color = if dark_mode {
+                        fill_color.lighten(64)
+                    } else {
+                        fill_color.darken(64)
+                    };
+                    CellBorderStyle::uniform(4.0, color)
+                } else {
+                    CellBorderStyle::none()
+                };
+                CellStyle {
+                    fill_color: Some(fill_color),
+                    border,
+                    label: Some(LabelStyle {
+                        color: text_color,
+                        font_size: 8.0,
+                        add_shadow: Some(shadow_color),
+                    }),
+                }
+            }
+            Theme::GraphPaper => {
+                let color = Rgba::rgb(98, 213, 250);
+                let border = if coord.is_origin() {
+                    CellBorderStyle::uniform(4.0, color)
+                } else {
+                    CellBorderStyle::uniform(2.0, color)
+                };
+                CellStyle {
+                    fill_color: Some(Rgba::rgb(255, 255, 250)),
+                    border,
+                    label: Some(LabelStyle {
+                        color,
+                        font_size: 8.0,
+                        add_shadow: Some(Rgba::GRAY),
+                    }),
+                }
+            }
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A writer that accumulates drawing operations on a grid and produces a
+/// standalone SVG document.  The document is sized to fit everything that
+/// has been drawn, plus a margin.
+#[derive(Debug, Clone)]
+pub struct SvgWriter<SZ: SizedGrid> {
+    sized_grid: SZ,
+    margin: f32,
+    background: Option<Rgba>,
+    elements: Vec<String>,
+    bounds: Option<(Vec2, Vec2)>,
+}
+
+impl<SZ: SizedGrid> SvgWriter<SZ> {
+    /// Construct a writer for the given `SizedGrid`, with a margin of the
+    /// inradius of a cell and no background.
+    pub fn new(sized_grid: SZ) -> Self {
+        Self {
+            margin: sized_grid.inradius(),
+            sized_grid,
+            background: None,
+            elements: Vec::new(),
+            bounds: None,
+        }
+    }
+
+    /// Set the margin around everything that has been drawn.
+    pub fn with_margin(mut self, margin: f32) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Fill the background of the document with the given color.
+    pub fn with_background(mut self, color: Rgba) -> Self {
+        self.background = Some(color);
+        self
+    }
+
+    /// Draw a single cell with the given style and optional label.  For
+    /// per edge border styles the edges are drawn after the cell.
+    pub fn cell(&mut self, coord: &SZ::Coord, style: &CellStyle, label: Option<&str>) {
+        let vertices = self.sized_grid.vertices(coord);
+        let primitive = match &style.border {
+            CellBorderStyle::Primitive(primitive) => primitive,
+            CellBorderStyle::PerEdge(_) => &CellPrimitiveBorderStyle::None,
+        };
+        let element = format!(
+            "<polygon points=\"{}\"{}{}/>",
+            self.points(&vertices),
+            paint("fill", style.fill_color.unwrap_or(Rgba::TRANSPARENT)),
+            stroke(primitive),
+        );
+        self.elements.push(element);
+
+        if let CellBorderStyle::PerEdge(edge_styles) = &style.border {
+            let edges = self.sized_grid.edges(coord);
+            assert!(
+                edge_styles
+                    .keys()
+                    .collect::<HashSet<_>>()
+                    .is_subset(&edges.keys().collect::<HashSet<_>>()),
+                "The edge styles must be a subset of the grid cell edges."
+            );
+            // Draw the edges in order of direction, so that the output is
+            // deterministic.
+            let mut edges: Vec<(Direction, (Vec2, Vec2))> = edges.into_iter().collect();
+            edges.sort_by_key(|(dir, _)| *dir);
+            for (dir, (start, end)) in edges {
+                let edge_style = edge_styles.get(&dir).unwrap_or(&CellPrimitiveBorderStyle::None);
+                if let CellPrimitiveBorderStyle::None = edge_style {
+                    continue;
+                }
+                let points = self.points(&[start, end]);
+                let element =
+                    format!("<polyline points=\"{points}\" fill=\"none\"{}/>", stroke(edge_style));
+                self.elements.push(element);
+            }
+        }
+
+        if let Some((label_style, label)) = style.label.as_ref().zip(label) {
+            let center = self.sized_grid.grid_to_screen(coord);
+            self.label(center, label_style, label);
+        }
+    }
+
+    /// Draw the coordinates of a `Shape` as a single region, with the given
+    /// style used for the edges on its boundary, and optionally a style for
+    /// the edges between its coordinates.  Only primitive border styles are
+    /// supported, so nothing is drawn for a per edge border style.
+    pub fn shape<S>(
+        &mut self,
+        shape: &S,
+        style: &CellStyle,
+        inner_border_style: Option<CellPrimitiveBorderStyle>,
+    ) where
+        S: Shape<SZ::Coord>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        let CellBorderStyle::Primitive(primitive) = &style.border else {
+            return;
+        };
+        let inner = inner_border_style.unwrap_or(CellPrimitiveBorderStyle::None);
+        for coord in sorted(shape.iter()) {
+            let edge_styles = coord
+                .allowed_directions(DirectionType::Face)
+                .iter()
+                .map(|dir| {
+                    let neighbour = coord
+                        .move_in_direction(DirectionType::Face, dir)
+                        .expect("Direction should be valid");
+                    let edge_style = if shape.contains(&neighbour) {
+                        inner.clone()
+                    } else {
+                        primitive.clone()
+                    };
+                    (dir, edge_style)
+                })
+                .collect();
+            let style = CellStyle {
+                border: CellBorderStyle::PerEdge(edge_styles),
+                ..style.clone()
+            };
+            self.cell(&coord, &style, None);
+        }
+    }
+
+    /// Draw only the outline of the given shape, including the outlines of
+    /// any holes, as closed paths with the given border style.
+    pub fn shape_outline<S>(&mut self, shape: &S, style: &CellPrimitiveBorderStyle)
+    where
+        S: Shape<SZ::Coord>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        if let CellPrimitiveBorderStyle::None = style {
+            return;
+        }
+        for outline in outline::outlines(&self.sized_grid, shape) {
+            for polygon in std::iter::once(&outline.exterior).chain(outline.holes.iter()) {
+                let element = format!(
+                    "<polygon points=\"{}\" fill=\"none\"{}/>",
+                    self.points(polygon),
+                    stroke(style)
+                );
+                self.elements.push(element);
+            }
+        }
+    }
+
+    /// Draw each coordinate of a `ShapeContainer` with the style and label
+    /// produced from its value.
+    pub fn shape_container<V, SC>(
+        &mut self,
+        container: &SC,
+        style_for_value: impl Fn(&SZ::Coord, &V) -> CellStyle,
+        label_for_value: impl Fn(&SZ::Coord, &V) -> Option<String>,
+    ) where
+        V: Debug + Clone + PartialEq + Eq + Hash,
+        SC: ShapeContainer<SZ::Coord, V>,
+        SC::Shape: std::ops::Sub<Output=SC::Shape>,
+        for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+        for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+        for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    {
+        for coord in sorted(container.as_shape().iter()) {
+            let value = container.get(&coord).expect("Coordinate should be in the container");
+            let label = label_for_value(&coord, value);
+            self.cell(&coord, &style_for_value(&coord, value), label.as_deref());
+        }
+    }
+
+    /// Draw every coordinate of a `Shape` with the style and label produced
+    /// for the coordinate, such as with `Theme::cell_style`.  The origin is
+    /// drawn last, as depending on the styling it could be obscured by other
+    /// cells.
+    pub fn grid<S>(
+        &mut self,
+        shape: &S,
+        style_for_coord: impl Fn(&SZ::Coord) -> CellStyle,
+        label_for_coord: impl Fn(&SZ::Coord) -> Option<String>,
+    ) where
+        S: Shape<SZ::Coord>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        let mut coords = sorted(shape.iter());
+        coords.sort_by_key(|coord| coord.is_origin());
+        for coord in coords {
+            let label = label_for_coord(&coord);
+            self.cell(&coord, &style_for_coord(&coord), label.as_deref());
+        }
+    }
+
+    /// Draw an arrow between the centers of two coordinates, shortened so
+    /// that it does not obscure their centers.  An arrow from a coordinate
+    /// to itself is drawn as a loop around its center.
+    pub fn arrow(
+        &mut self,
+        from: &SZ::Coord,
+        to: &SZ::Coord,
+        style: &ArrowStyle,
+        label: Option<&str>,
+    ) {
+        let from_pos = self.sized_grid.grid_to_screen(from);
+        let to_pos = self.sized_grid.grid_to_screen(to);
+        let gap = self.sized_grid.inradius() * 0.33;
+        let stroke = stroke(&CellPrimitiveBorderStyle::Uniform(style.width, style.color));
+
+        if from == to {
+            let (center, radius) = (self.point(from_pos), gap);
+            self.include(from_pos - Vec2::splat(radius));
+            self.include(from_pos + Vec2::splat(radius));
+            self.elements.push(format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"none\"{stroke}/>",
+                number(center.x),
+                number(center.y),
+                number(radius),
+            ));
+            if let Some((label_style, label)) = style.label.as_ref().zip(label) {
+                let above = from_pos + Vec2::Y * (radius + label_style.font_size * 2.0);
+                self.label(above, label_style, label);
+            }
+            return;
+        }
+
+        let dir = (to_pos - from_pos).normalize();
+        let (start, end) = (from_pos + dir * gap, to_pos - dir * gap);
+        let points = self.points(&[start, end]);
+        self.elements.push(format!("<polyline points=\"{points}\" fill=\"none\"{stroke}/>"));
+        let angle = (start - end).to_angle();
+        if style.to_head {
+            self.arrow_head(end, angle, style.color);
+        }
+        if style.from_head {
+            self.arrow_head(start, angle + std::f32::consts::PI, style.color);
+        }
+
+        if let Some((label_style, label)) = style.label.as_ref().zip(label) {
+            let center = (start + end) / 2.0;
+            let offset = Vec2::from_angle(angle + std::f32::consts::FRAC_PI_2)
+                * label_style.font_size
+                * 2.0;
+            self.label(center + offset, label_style, label);
+        }
+    }
+
+    /// Produce the SVG document for everything drawn so far.
+    pub fn to_svg(&self) -> String {
+        let (min, max) = self.bounds.unwrap_or((Vec2::ZERO, Vec2::ZERO));
+        let (min, max) = (min - Vec2::splat(self.margin), max + Vec2::splat(self.margin));
+        let size = max - min;
+        let mut result = String::new();
+        writeln!(
+            result,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" \
+             width=\"{}\" height=\"{}\">",
+            number(min.x),
+            number(min.y),
+            number(size.x),
+            number(size.y),
+            number(size.x),
+            number(size.y),
+        )
+        .expect("Writing to a String should not fail");
+        if let Some(background) = self.background {
+            writeln!(
+                result,
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>",
+                number(min.x),
+                number(min.y),
+                number(size.x),
+                number(size.y),
+                paint("fill", background),
+            )
+            .expect("Writing to a String should not fail");
+        }
+        for element in self.elements.iter() {
+            result.push_str(element);
+            result.push('\n');
+        }
+        result.push_str("</svg>\n");
+        result
+    }
+
+    /// Internal helper to draw a label centered on the given point in
+    /// screen space, with its shadow if any.
+    fn label(&mut self, pos: Vec2, style: &LabelStyle, label: &str) {
+        let point = self.point(pos);
+        // Text is not measured, so only the anchor point, along with that
+        // of its shadow, is included in the bounds.
+        self.include(pos);
+        if style.add_shadow.is_some() {
+            self.include(pos + SHADOW_OFFSET);
+        }
+        let text = escape(label);
+        let text_element = |pos: Vec2, color: Rgba| {
+            format!(
+                "<text x=\"{}\" y=\"{}\" font-family=\"monospace\" font-size=\"{}\" \
+                 text-anchor=\"middle\" dominant-baseline=\"central\"{}>{text}</text>",
+                number(pos.x),
+                number(pos.y),
+                number(style.font_size),
+                paint("fill", color),
+            )
+        };
+        if let Some(shadow) = style.add_shadow {
+            let element = text_element(self.point(pos + SHADOW_OFFSET), shadow);
+            self.elements.push(element);
+        }
+        let element = text_element(point, style.color);
+        self.elements.push(element);
+    }
+
+    /// Internal helper to draw a solid arrow head with its tip at the given
+    /// point in screen space, pointing away from the given angle.
+    fn arrow_head(&mut self, tip: Vec2, angle: f32, color: Rgba) {
+        let right = tip + Vec2::from_angle(angle + FRAC_PI_6) * 6.0;
+        let left = tip + Vec2::from_angle(angle - FRAC_PI_6) * 6.0;
+        let points = self.points(&[tip, right, left]);
+        let element = format!("<polygon points=\"{points}\"{}/>", paint("fill", color));
+        self.elements.push(element);
+    }
+
+    /// Internal helper to convert a point in screen space to SVG space,
+    /// which has the y-axis flipped.
+    fn point(&self, pos: Vec2) -> Vec2 {
+        Vec2::new(pos.x, -pos.y)
+    }
+
+    /// Internal helper to extend the bounds of the document to include
+    /// the given point in screen space.
+    fn include(&mut self, pos: Vec2) {
+        let point = self.point(pos);
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (min.min(point), max.max(point)),
+        });
+    }
+
+    /// Internal helper to format points in screen space for a `points`
+    /// attribute, including them in the bounds of the document.
+    fn points(&mut self, points: &[Vec2]) -> String {
+        let mut result = Vec::new();
+        for pos in points {
+            self.include(*pos);
+            let point = self.point(*pos);
+            result.push(format!("{},{}", number(point.x), number(point.y)));
+        }
+        result.join(" ")
+    }
+}
+
+impl<SZ: SizedGrid> Display for SvgWriter<SZ> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_svg())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to sort coordinates by their `Display` representation,
+/// so that drawing them is deterministic.
+fn sorted<'a, C: Coord + 'a>(coords: impl Iterator<Item=&'a C>) -> Vec<C> {
+    let mut result: Vec<C> = coords.cloned().collect();
+    result.sort_by_cached_key(|coord| coord.to_string());
+    result
+}
+
+/// Internal helper to format a number with at most two decimal places,
+/// without trailing zeros.
+fn number(value: f32) -> String {
+    let text = format!("{value:.2}");
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Internal helper to produce the attributes for painting with a color,
+/// such as `fill` or `stroke`.
+fn paint(attribute: &str, color: Rgba) -> String {
+    match color.a {
+        0 => format!(" {attribute}=\"none\""),
+        255 => format!(" {attribute}=\"{color}\""),
+        a => {
+            let opacity = number(a as f32 / 255.0);
+            format!(" {attribute}=\"{color}\" {attribute}-opacity=\"{opacity}\"")
+        }
+    }
+}
+
+/// Internal helper to produce the attributes for stroking with a border
+/// style.
+fn stroke(style: &CellPrimitiveBorderStyle) -> String {
+    match style {
+        CellPrimitiveBorderStyle::None => paint("stroke", Rgba::TRANSPARENT),
+        CellPrimitiveBorderStyle::Uniform(width, color) => format!(
+            "{} stroke-width=\"{}\" stroke-linejoin=\"round\" stroke-linecap=\"round\"",
+            paint("stroke", *color),
+            number(*width)
+        ),
+    }
+}
+
+/// Internal helper to escape text for inclusion in XML.
+fn escape(text: &str) -> String {
+    let mut result = String::new();
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
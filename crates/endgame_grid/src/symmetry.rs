@@ -0,0 +1,384 @@
+//! Symmetry transforms of grids.
+//!
+//! The rotations and reflections of a grid about the origin form a
+//! dihedral group: D4 for square grids, D6 for hexagonal grids, and D3 for
+//! triangular grids, whose rotations and reflections are about the center
+//! of the origin triangle.  A `Transform` is an element of this group,
+//! which can be composed, inverted, and applied to coordinates,
+//! `Direction`s, `Shape`s and `ShapeContainer`s.
+//!
+//! For those coordinate systems satisfying `ModuleCoord`, an `Isometry`
+//! combines a `Transform` with a translation.  This also allows for
+//! rotating and reflecting about an arbitrary pivot coordinate.
+
+use crate::{Coord, DirectionType, ModuleCoord, Shape, ShapeContainer};
+use endgame_direction::Direction;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Coordinates whose rotations and reflections about the origin form a
+/// dihedral group.  Every element of the group can be written as an
+/// optional reflection across `REFLECTION_AXIS`, followed by some number of
+/// steps of `Coord::rotate_clockwise`.
+pub trait SymmetricCoord: Coord + Copy {
+    /// The number of steps of `Coord::rotate_clockwise` that return a
+    /// coordinate to itself.
+    const ROTATIONS: usize;
+
+    /// The axis across which every reflection of a `Transform` is made,
+    /// before rotating.
+    const REFLECTION_AXIS: Self::Axes;
+
+    /// The number of steps of `Coord::rotate_clockwise` which, following a
+    /// reflection across `REFLECTION_AXIS`, produce a reflection across the
+    /// given axis.
+    fn reflection_steps(axis: Self::Axes) -> usize;
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A rotation or reflection of a grid about the origin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Transform<C: SymmetricCoord> {
+    rotation: usize,
+    reflected: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _marker: PhantomData<C>,
+}
+
+impl<C: SymmetricCoord> Default for Transform<C> {
+    fn default() -> Self {
+        Transform::identity()
+    }
+}
+
+impl<C: SymmetricCoord> Transform<C> {
+    /// Internal helper to construct a `Transform`, normalising the
+    /// rotation.
+    fn new(rotation: isize, reflected: bool) -> Self {
+        Transform {
+            rotation: rotation.rem_euclid(C::ROTATIONS as isize) as usize,
+            reflected,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The `Transform` that leaves every coordinate in place.
+    pub fn identity() -> Self {
+        Transform::new(0, false)
+    }
+
+    /// The `Transform` that rotates around the origin by the given number
+    /// of steps, as with `Coord::rotate`.
+    pub fn rotation(steps: isize) -> Self {
+        Transform::new(steps, false)
+    }
+
+    /// The `Transform` that reflects across the given axis, as with
+    /// `Coord::reflect`.
+    pub fn reflection(axis: C::Axes) -> Self {
+        Transform::new(C::reflection_steps(axis) as isize, true)
+    }
+
+    /// Enumerate the rotations of the grid, starting with the identity.
+    pub fn rotations() -> Vec<Self> {
+        (0..C::ROTATIONS as isize).map(Transform::rotation).collect()
+    }
+
+    /// Enumerate all the elements of the group, the rotations followed by
+    /// the reflections.  No two elements act the same on every coordinate.
+    pub fn all() -> Vec<Self> {
+        [false, true]
+            .into_iter()
+            .flat_map(|reflected| {
+                (0..C::ROTATIONS as isize).map(move |rotation| Transform::new(rotation, reflected))
+            })
+            .collect()
+    }
+
+    /// The number of steps of `Coord::rotate_clockwise` made by this
+    /// `Transform`, after any reflection.
+    pub fn rotation_steps(&self) -> usize {
+        self.rotation
+    }
+
+    /// Does this `Transform` reverse the winding of the grid?
+    pub fn is_reflection(&self) -> bool {
+        self.reflected
+    }
+
+    /// Produce the `Transform` that applies `other` and then this
+    /// `Transform`.
+    pub fn compose(&self, other: &Self) -> Self {
+        // A reflection reverses the sense of any rotation preceding it.
+        let rotation = if self.reflected {
+            self.rotation as isize - other.rotation as isize
+        } else {
+            self.rotation as isize + other.rotation as isize
+        };
+        Transform::new(rotation, self.reflected != other.reflected)
+    }
+
+    /// Produce the `Transform` that undoes this one.
+    pub fn inverse(&self) -> Self {
+        if self.reflected {
+            // Reflections are their own inverse.
+            *self
+        } else {
+            Transform::new(-(self.rotation as isize), false)
+        }
+    }
+
+    /// Apply the `Transform` to a coordinate.
+    pub fn apply(&self, coord: &C) -> C {
+        let coord = if self.reflected {
+            coord.reflect(C::REFLECTION_AXIS)
+        } else {
+            *coord
+        };
+        coord.rotate(self.rotation as isize)
+    }
+
+    /// Apply the `Transform` to a `Direction` of the given coordinate,
+    /// producing the `Direction` from the transformed coordinate to the
+    /// transformed neighbour.  Returns `None` if the `Direction` is not
+    /// allowed for the coordinate.
+    pub fn apply_direction(
+        &self,
+        coord: &C,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<Direction> {
+        map_direction(coord, dir_type, dir, |c| self.apply(c))
+    }
+
+    /// Apply the `Transform` to every coordinate of a `Shape`.
+    pub fn apply_shape<S>(&self, shape: &S) -> S
+    where
+        S: Shape<C>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        map_shape(shape, |c| self.apply(c))
+    }
+
+    /// Apply the `Transform` to every coordinate of a `ShapeContainer`,
+    /// keeping the associated values.
+    pub fn apply_container<V, SC>(&self, container: &SC) -> SC
+    where
+        V: Debug + Clone + PartialEq + Eq + Hash,
+        SC: ShapeContainer<C, V>,
+        SC::Shape: std::ops::Sub<Output=SC::Shape>,
+        for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+        for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+        for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    {
+        map_container(container, |c| self.apply(c))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A `Transform` followed by a translation, for those coordinate systems
+/// satisfying `ModuleCoord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Isometry<MC: SymmetricCoord + ModuleCoord>
+where
+        for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+        for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    transform: Transform<MC>,
+    offset: MC,
+}
+
+impl<MC: SymmetricCoord + ModuleCoord> Default for Isometry<MC>
+where
+        for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+        for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    fn default() -> Self {
+        Isometry::identity()
+    }
+}
+
+impl<MC: SymmetricCoord + ModuleCoord> From<Transform<MC>> for Isometry<MC>
+where
+        for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+        for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    fn from(transform: Transform<MC>) -> Self {
+        Isometry::new(transform, MC::default())
+    }
+}
+
+impl<MC: SymmetricCoord + ModuleCoord> Isometry<MC>
+where
+        for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+        for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    /// Construct an `Isometry` that applies the `Transform` and then
+    /// translates by the given offset.
+    pub fn new(transform: Transform<MC>, offset: MC) -> Self {
+        Isometry { transform, offset }
+    }
+
+    /// The `Isometry` that leaves every coordinate in place.
+    pub fn identity() -> Self {
+        Isometry::new(Transform::identity(), MC::default())
+    }
+
+    /// The `Isometry` that translates by the given offset.
+    pub fn translation(offset: MC) -> Self {
+        Isometry::new(Transform::identity(), offset)
+    }
+
+    /// The `Isometry` that applies the `Transform` about the given pivot
+    /// coordinate, rather than the origin.  The pivot is left in place.
+    pub fn about(transform: Transform<MC>, pivot: &MC) -> Self {
+        Isometry::new(transform, *pivot - transform.apply(pivot))
+    }
+
+    /// The rotation or reflection made by this `Isometry`.
+    pub fn transform(&self) -> Transform<MC> {
+        self.transform
+    }
+
+    /// The translation made by this `Isometry`, after its `Transform`.
+    pub fn offset(&self) -> MC {
+        self.offset
+    }
+
+    /// Produce the `Isometry` that applies `other` and then this
+    /// `Isometry`.
+    pub fn compose(&self, other: &Self) -> Self {
+        Isometry::new(
+            self.transform.compose(&other.transform),
+            self.transform.apply(&other.offset) + self.offset,
+        )
+    }
+
+    /// Produce the `Isometry` that undoes this one.
+    pub fn inverse(&self) -> Self {
+        let inverse = self.transform.inverse();
+        Isometry::new(inverse, -inverse.apply(&self.offset))
+    }
+
+    /// Apply the `Isometry` to a coordinate.
+    pub fn apply(&self, coord: &MC) -> MC {
+        self.transform.apply(coord) + self.offset
+    }
+
+    /// Apply the `Isometry` to a `Direction` of the given coordinate,
+    /// producing the `Direction` from the transformed coordinate to the
+    /// transformed neighbour.  Returns `None` if the `Direction` is not
+    /// allowed for the coordinate.
+    pub fn apply_direction(
+        &self,
+        coord: &MC,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<Direction> {
+        map_direction(coord, dir_type, dir, |c| self.apply(c))
+    }
+
+    /// Apply the `Isometry` to every coordinate of a `Shape`.
+    pub fn apply_shape<S>(&self, shape: &S) -> S
+    where
+        S: Shape<MC>,
+        S: std::ops::Sub<Output=S>,
+        for<'a> S: std::ops::Sub<&'a S, Output=S>,
+        for<'b> S: std::ops::Sub<&'b S, Output=S>,
+        for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    {
+        map_shape(shape, |c| self.apply(c))
+    }
+
+    /// Apply the `Isometry` to every coordinate of a `ShapeContainer`,
+    /// keeping the associated values.
+    pub fn apply_container<V, SC>(&self, container: &SC) -> SC
+    where
+        V: Debug + Clone + PartialEq + Eq + Hash,
+        SC: ShapeContainer<MC, V>,
+        SC::Shape: std::ops::Sub<Output=SC::Shape>,
+        for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+        for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+        for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    {
+        map_container(container, |c| self.apply(c))
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to map a `Direction` of a coordinate through a mapping
+/// of coordinates that preserves neighbours.
+fn map_direction<C: Coord, F>(
+    coord: &C,
+    dir_type: DirectionType,
+    dir: Direction,
+    map: F,
+) -> Option<Direction>
+where
+    F: Fn(&C) -> C,
+{
+    let next = map(&coord.move_in_direction(dir_type, dir)?);
+    let mapped = map(coord);
+    let result = mapped
+        .allowed_directions(dir_type)
+        .iter()
+        .find(|d| mapped.move_in_direction(dir_type, *d).as_ref() == Some(&next))
+        .expect("Symmetries should map neighbours to neighbours");
+    Some(result)
+}
+
+/// Internal helper to map every coordinate of a `Shape`.
+pub(crate) fn map_shape<C: Coord, S, F>(shape: &S, map: F) -> S
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+    F: Fn(&C) -> C,
+{
+    let mut result = S::new();
+    for coord in shape.iter() {
+        result.insert(map(coord));
+    }
+    result
+}
+
+/// Internal helper to map every coordinate of a `ShapeContainer`.
+pub(crate) fn map_container<C: Coord, V, SC, F>(container: &SC, map: F) -> SC
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    SC: ShapeContainer<C, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    F: Fn(&C) -> C,
+{
+    // There is no way to construct an empty container generically, so
+    // empty a copy of the original.
+    let mut result = container.clone();
+    let entries: Vec<(C, V)> = container
+        .iter()
+        .map(|(coord, value)| (coord.clone(), value.clone()))
+        .collect();
+    for (coord, _) in entries.iter() {
+        result.remove(coord);
+    }
+    for (coord, value) in entries {
+        result.insert(map(&coord), value);
+    }
+    result
+}
@@ -0,0 +1,198 @@
+//! Rendering and parsing `ShapeContainer`s as text.
+//!
+//! Each coordinate is drawn as a cell two characters wide, laid out in the
+//! rows and columns of a `TextCoord::to_text_offset`, with rows increasing
+//! upwards so that the art matches the orientation of a `SizedGrid`.  So a
+//! square grid might be drawn as
+//!
+//! ```text
+//! X . O
+//! . X .
+//! O . X
+//! ```
+//!
+//! with the value of each cell followed by a space.  Hexagonal grids are
+//! drawn as pointy-topped hexagons, so odd rows are staggered by a single
+//! character.  Triangular grids prefix the value of each cell with the
+//! `TrianglePoint` glyph of the triangle, as in `▲X▼.▲O`.  Coordinates that
+//! are not in the container are drawn as whitespace, and so values should
+//! not be drawn as whitespace.
+//!
+//! As the art does not record where the origin lies, rendering and parsing
+//! are both relative to the coordinate drawn at the bottom left corner of
+//! the art.  Trailing whitespace is insignificant, but leading whitespace
+//! and blank lines are not.
+
+use crate::{Coord, Shape, ShapeContainer};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Coordinates that can be laid out in the rows and columns of text.
+pub trait TextCoord: Coord + Copy {
+    /// The column and row at which the coordinate is drawn.  Rows increase
+    /// upwards and columns to the right.
+    fn to_text_offset(&self) -> (i32, i32);
+
+    /// Construct the coordinate drawn at the given column and row.  This
+    /// should be the inverse of `to_text_offset`.
+    fn from_text_offset(offset: (i32, i32)) -> Self;
+
+    /// The number of characters by which the given row is indented.
+    fn indent(_row: i32) -> usize {
+        0
+    }
+
+    /// A glyph drawn before the value of the cell, if any.
+    fn glyph(&self) -> Option<char> {
+        None
+    }
+}
+
+/// The ways in which parsing text art can fail.  Lines and columns are
+/// numbered from one, with columns counting characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseTextError {
+    /// The character does not represent any value.
+    UnknownValue { line: usize, column: usize, found: char },
+    /// The character does not fall where the layout allows, such as a
+    /// character in the space following the value of a cell, or a glyph
+    /// that does not match the cell.
+    Misplaced { line: usize, column: usize, found: char },
+}
+
+impl Display for ParseTextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use ParseTextError::*;
+        match self {
+            UnknownValue { line, column, found } => {
+                write!(f, "Unknown value {found:?} at line {line}, column {column}")
+            }
+            Misplaced { line, column, found } => {
+                write!(f, "Misplaced character {found:?} at line {line}, column {column}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseTextError {}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Find the coordinate at the bottom left corner of the least art that
+/// draws every coordinate of the given `Shape`.  The origin is used for an
+/// empty `Shape`.
+pub fn bottom_left<C: TextCoord, S>(shape: &S) -> C
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let offsets = shape.iter().map(|coord| coord.to_text_offset());
+    let corner = offsets.reduce(|(c1, r1), (c2, r2)| (c1.min(c2), r1.min(r2)));
+    corner.map_or_else(|| C::from_text_offset((0, 0)), C::from_text_offset)
+}
+
+/// Render a `ShapeContainer` as text, drawing each value as the character
+/// produced by `cell`.  The art extends up and to the right from the given
+/// `corner`, so any coordinates below or to the left of it are omitted.
+/// Every line, including the last, is terminated by a newline.
+pub fn render<C: TextCoord, V, SC>(container: &SC, corner: &C, cell: impl Fn(&V) -> char) -> String
+where
+    V: Debug + Clone + PartialEq + Eq + Hash,
+    SC: ShapeContainer<C, V>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    let (corner_col, corner_row) = corner.to_text_offset();
+    let cells: Vec<(i32, i32, [char; 2])> = container
+        .iter()
+        .filter_map(|(coord, value)| {
+            let (col, row) = coord.to_text_offset();
+            if col < corner_col || row < corner_row {
+                return None;
+            }
+            let chars = match coord.glyph() {
+                Some(glyph) => [glyph, cell(value)],
+                None => [cell(value), ' '],
+            };
+            Some((col - corner_col, row - corner_row, chars))
+        })
+        .collect();
+    let Some(height) = cells.iter().map(|(_, row, _)| row + 1).max() else {
+        return String::new();
+    };
+
+    let mut lines: Vec<Vec<char>> = vec![Vec::new(); height as usize];
+    for (col, row, chars) in cells {
+        let line = &mut lines[row as usize];
+        let start = C::indent(corner_row + row) + 2 * col as usize;
+        if line.len() < start + 2 {
+            line.resize(start + 2, ' ');
+        }
+        line[start..start + 2].copy_from_slice(&chars);
+    }
+
+    let mut result = String::new();
+    for line in lines.iter().rev() {
+        let line: String = line.iter().collect();
+        result.push_str(line.trim_end());
+        result.push('\n');
+    }
+    result
+}
+
+/// Parse text art, as produced by `render`, into a container.  The
+/// `corner` is the coordinate drawn at the bottom left of the art, and
+/// `cell` converts each character drawn for a value back into the value,
+/// or `None` if it does not represent one.
+pub fn parse<C: TextCoord, V, SC>(
+    text: &str,
+    corner: &C,
+    cell: impl Fn(char) -> Option<V>,
+) -> Result<SC, ParseTextError>
+where
+    SC: FromIterator<(C, V)>,
+{
+    let (corner_col, corner_row) = corner.to_text_offset();
+    let lines: Vec<&str> = text.lines().collect();
+    let mut entries = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let row = corner_row + (lines.len() - 1 - index) as i32;
+        let indent = C::indent(row);
+        let chars: Vec<char> = line.chars().collect();
+        for (position, found) in chars.iter().copied().enumerate() {
+            if found.is_whitespace() {
+                continue;
+            }
+            let (line, column) = (index + 1, position + 1);
+            let misplaced = ParseTextError::Misplaced { line, column, found };
+            let Some(offset) = position.checked_sub(indent) else {
+                return Err(misplaced);
+            };
+            let coord = C::from_text_offset((corner_col + (offset / 2) as i32, row));
+            // Each cell is either a glyph followed by the value, or the
+            // value followed by a space.
+            let before = position.checked_sub(1).map(|before| chars[before]);
+            let after = chars.get(position + 1).filter(|after| !after.is_whitespace());
+            match (coord.glyph(), offset % 2) {
+                (Some(glyph), 0) if found == glyph && after.is_some() => continue,
+                (Some(glyph), 1) if before == Some(glyph) => {}
+                (None, 0) => {}
+                _ => return Err(misplaced),
+            }
+            let value = cell(found).ok_or(ParseTextError::UnknownValue {
+                line,
+                column,
+                found,
+            })?;
+            entries.push((coord, value));
+        }
+    }
+    Ok(entries.into_iter().collect())
+}
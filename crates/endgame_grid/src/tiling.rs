@@ -0,0 +1,376 @@
+//! Tiling regions with pieces, as an exact cover problem.
+//!
+//! Asking whether a set of pieces can tile a region of a grid, as in
+//! pentomino puzzles, is an instance of the exact cover problem: choose
+//! placements of the pieces such that every coordinate of the region is
+//! covered exactly once.  This is solved with Knuth's Algorithm X, using
+//! the "dancing links" representation of the sparse matrix of placements
+//! and coordinates.
+//!
+//! Pieces with congruent shapes, under the symmetries they are allowed,
+//! are interchangeable.  Rather than finding each tiling once for every
+//! way of permuting them, they are grouped together and each tiling is
+//! found once, with the interchangeable pieces identified in the order of
+//! their placements.
+
+use crate::canonical::{canonicalize_with, orientations, CanonicalCoord, Equivalence};
+use crate::shape::HashShapeContainer;
+use crate::{Shape, ShapeContainer};
+use std::collections::{HashMap, HashSet};
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Produce an iterator over the ways of tiling the `target` with the given
+/// pieces.  Each piece is paired with the `Equivalence` describing which of
+/// its rotations and reflections may be placed.  A tiling covers every
+/// coordinate of the target exactly once, using each piece at most once,
+/// and maps each coordinate to the index of the piece covering it.  Should
+/// the sizes of the pieces sum to that of the target, every tiling uses
+/// every piece.
+///
+/// Tilings are found lazily, so the first can be found without searching
+/// for the rest.  Each tiling is produced once, regardless of the order of
+/// the pieces, or of the symmetries of the target.
+pub fn tilings<C: CanonicalCoord, S>(target: &S, pieces: &[(S, Equivalence)]) -> Tilings<C>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    // Group together pieces that are interchangeable.
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_orientations: Vec<Vec<S>> = Vec::new();
+    let mut group_keys: HashMap<(S, Equivalence), usize> = HashMap::new();
+    for (index, (shape, equivalence)) in pieces.iter().enumerate() {
+        let key = (canonicalize_with(shape, *equivalence).form, *equivalence);
+        let group = *group_keys.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            group_orientations.push(orientations(shape, *equivalence));
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+
+    // Each coordinate of the target is a column of the matrix.
+    let columns: HashMap<C, usize> =
+        target.iter().enumerate().map(|(index, coord)| (*coord, index)).collect();
+    let mut links = Links::new(columns.len());
+
+    // Each placement of a piece within the target is a row of the matrix.
+    // Every placement moves the least coordinate of an orientation, its
+    // anchor, to some coordinate of the target.
+    let mut rows: Vec<(usize, Vec<C>)> = Vec::new();
+    for (group, shapes) in group_orientations.iter().enumerate() {
+        let mut placed: HashSet<Vec<usize>> = HashSet::new();
+        for shape in shapes.iter() {
+            let Some(anchor) = shape.iter().min_by_key(|coord| coord.grid_to_array_offset()) else {
+                continue;
+            };
+            for coord in target.iter() {
+                let offset = anchor.translate_from(coord);
+                if anchor.translate_from(&offset) != *coord {
+                    // Only triangles of the same orientation can be
+                    // translated onto one another.
+                    continue;
+                }
+                let cells: Vec<C> = shape.iter().map(|c| c.translate_from(&offset)).collect();
+                let Some(mut indices) =
+                    cells.iter().map(|c| columns.get(c).copied()).collect::<Option<Vec<_>>>()
+                else {
+                    continue;
+                };
+                indices.sort();
+                if placed.insert(indices.clone()) {
+                    links.add_row(rows.len(), &indices);
+                    rows.push((group, cells));
+                }
+            }
+        }
+    }
+
+    Tilings {
+        links,
+        used: vec![0; groups.len()],
+        groups,
+        rows,
+        stack: Vec::new(),
+        resuming: false,
+        done: false,
+    }
+}
+
+/// Find a tiling of the `target` with the given pieces, if there is one.
+/// See `tilings`.
+pub fn first_tiling<C: CanonicalCoord, S>(
+    target: &S,
+    pieces: &[(S, Equivalence)],
+) -> Option<HashShapeContainer<C, usize>>
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    tilings(target, pieces).next()
+}
+
+/// Count the tilings of the `target` with the given pieces.  See
+/// `tilings`.
+pub fn count_tilings<C: CanonicalCoord, S>(target: &S, pieces: &[(S, Equivalence)]) -> usize
+where
+    S: Shape<C>,
+    S: std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let mut tilings = tilings(target, pieces);
+    let mut count = 0;
+    while tilings.advance() {
+        count += 1;
+    }
+    count
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator over the tilings of a region, produced by `tilings`.
+#[derive(Debug, Clone)]
+pub struct Tilings<C: CanonicalCoord> {
+    links: Links,
+    /// The indices of the pieces in each group of interchangeable pieces.
+    groups: Vec<Vec<usize>>,
+    /// How many pieces of each group are placed by the current partial
+    /// tiling.
+    used: Vec<usize>,
+    /// The group and coordinates of each placement.
+    rows: Vec<(usize, Vec<C>)>,
+    /// For each level of the search, either the header of the column being
+    /// covered, before any placement has been tried, or the node of the
+    /// placement being tried.
+    stack: Vec<usize>,
+    /// Whether a tiling was just found, so the search should resume by
+    /// trying the next placement.
+    resuming: bool,
+    done: bool,
+}
+
+impl<C: CanonicalCoord> Tilings<C> {
+    /// Internal helper to advance the search to the next tiling.  Returns
+    /// whether one was found.
+    fn advance(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        loop {
+            if !self.resuming {
+                let Some(column) = self.links.choose_column() else {
+                    self.resuming = true;
+                    return true;
+                };
+                self.links.cover(column);
+                self.stack.push(column);
+            }
+            self.resuming = false;
+            if !self.try_next_placement() {
+                self.done = true;
+                return false;
+            }
+        }
+    }
+
+    /// Internal helper to undo the placement at the top of the stack, and
+    /// try the next available placement, backtracking as necessary.
+    /// Returns false once the search is exhausted.
+    fn try_next_placement(&mut self) -> bool {
+        while let Some(&node) = self.stack.last() {
+            let column = self.links.column[node];
+            if node != column {
+                self.links.unselect(node);
+                self.used[self.rows[self.links.row[node]].0] -= 1;
+            }
+            let mut next = self.links.down[node];
+            while next != column {
+                let group = self.rows[self.links.row[next]].0;
+                if self.used[group] < self.groups[group].len() {
+                    break;
+                }
+                next = self.links.down[next];
+            }
+            if next == column {
+                self.links.uncover(column);
+                self.stack.pop();
+                continue;
+            }
+            self.used[self.rows[self.links.row[next]].0] += 1;
+            self.links.select(next);
+            *self.stack.last_mut().expect("The stack should not be empty") = next;
+            return true;
+        }
+        false
+    }
+
+    /// Internal helper to produce the tiling for the placements on the
+    /// stack.
+    fn tiling(&self) -> HashShapeContainer<C, usize> {
+        // Assign interchangeable pieces to placements in order.
+        let mut placements: Vec<&(usize, Vec<C>)> = self
+            .stack
+            .iter()
+            .map(|node| &self.rows[self.links.row[*node]])
+            .collect();
+        placements.sort_by_cached_key(|(group, cells)| {
+            let mut offsets: Vec<(isize, isize)> =
+                cells.iter().map(|coord| coord.grid_to_array_offset()).collect();
+            offsets.sort();
+            (*group, offsets)
+        });
+        let mut result = HashShapeContainer::new();
+        let mut next_piece = vec![0; self.groups.len()];
+        for (group, cells) in placements {
+            let piece = self.groups[*group][next_piece[*group]];
+            next_piece[*group] += 1;
+            for coord in cells {
+                result.insert(*coord, piece);
+            }
+        }
+        result
+    }
+}
+
+impl<C: CanonicalCoord> Iterator for Tilings<C> {
+    type Item = HashShapeContainer<C, usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance().then(|| self.tiling())
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The "dancing links" representation of a sparse matrix of zeros and ones.
+/// Node zero is the root, followed by a header node for each column, and
+/// then a node for each one in the matrix.  Each node is linked to its
+/// neighbours in the same row and column, circularly.
+#[derive(Debug, Clone)]
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The column header of each node.  Headers are their own column.
+    column: Vec<usize>,
+    /// The row of each node.  This is meaningless for headers.
+    row: Vec<usize>,
+    /// The number of nodes in each column, indexed by header.
+    size: Vec<usize>,
+}
+
+impl Links {
+    /// Construct a matrix with the given number of columns, and no rows.
+    fn new(columns: usize) -> Self {
+        let count = columns + 1;
+        Links {
+            left: (0..count).map(|i| (i + count - 1) % count).collect(),
+            right: (0..count).map(|i| (i + 1) % count).collect(),
+            up: (0..count).collect(),
+            down: (0..count).collect(),
+            column: (0..count).collect(),
+            row: vec![0; count],
+            size: vec![0; count],
+        }
+    }
+
+    /// Add a row with ones in the given columns, numbered from zero.
+    fn add_row(&mut self, row: usize, columns: &[usize]) {
+        let first = self.column.len();
+        for (index, column) in columns.iter().enumerate() {
+            let node = first + index;
+            let header = column + 1;
+            self.left.push(if index == 0 { first + columns.len() - 1 } else { node - 1 });
+            self.right.push(if index + 1 == columns.len() { first } else { node + 1 });
+            self.up.push(self.up[header]);
+            self.down.push(header);
+            let above = self.up[header];
+            self.down[above] = node;
+            self.up[header] = node;
+            self.column.push(header);
+            self.row.push(row);
+            self.size[header] += 1;
+        }
+    }
+
+    /// Choose the uncovered column with the fewest nodes, or `None` if every
+    /// column is covered.
+    fn choose_column(&self) -> Option<usize> {
+        let mut best = None;
+        let mut header = self.right[0];
+        while header != 0 {
+            if best.is_none_or(|best| self.size[header] < self.size[best]) {
+                best = Some(header);
+            }
+            header = self.right[header];
+        }
+        best
+    }
+
+    /// Remove a column, and every row with a one in it, from the matrix.
+    fn cover(&mut self, header: usize) {
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = right;
+        self.left[right] = left;
+        let mut row_node = self.down[header];
+        while row_node != header {
+            let mut node = self.right[row_node];
+            while node != row_node {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = down;
+                self.up[down] = up;
+                self.size[self.column[node]] -= 1;
+                node = self.right[node];
+            }
+            row_node = self.down[row_node];
+        }
+    }
+
+    /// Restore a column removed by `cover`.
+    fn uncover(&mut self, header: usize) {
+        let mut row_node = self.up[header];
+        while row_node != header {
+            let mut node = self.left[row_node];
+            while node != row_node {
+                let (up, down) = (self.up[node], self.down[node]);
+                self.down[up] = node;
+                self.up[down] = node;
+                self.size[self.column[node]] += 1;
+                node = self.left[node];
+            }
+            row_node = self.up[row_node];
+        }
+        let (left, right) = (self.left[header], self.right[header]);
+        self.right[left] = header;
+        self.left[right] = header;
+    }
+
+    /// Cover the other columns of the row of the given node, whose own
+    /// column is already covered.
+    fn select(&mut self, row_node: usize) {
+        let mut node = self.right[row_node];
+        while node != row_node {
+            self.cover(self.column[node]);
+            node = self.right[node];
+        }
+    }
+
+    /// Undo `select`.
+    fn unselect(&mut self, row_node: usize) {
+        let mut node = self.left[row_node];
+        while node != row_node {
+            self.uncover(self.column[node]);
+            node = self.left[node];
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Wrapping topologies over grids.
+//!
+//! Some boards wrap around their edges, so that leaving one side re-enters
+//! on the opposite side, as on a cylinder or a torus.  A `Topology` models
+//! this by identifying coordinates that differ by a whole number of
+//! repetitions of a rectangular board, described by its columns and rows of
+//! array offsets.  Each coordinate is normalised into a fundamental domain,
+//! the copy of the board whose array offsets are non-negative and less than
+//! the number of columns or rows along each wrapping axis.
+
+use crate::shape::HashShape;
+use crate::{AllowedCoordIterRange, ArrayOffsetCoord, DirectionType};
+use endgame_direction::Direction;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Which axes of the array offsets wrap around.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Wrap {
+    /// Columns wrap around, forming a vertical cylinder.
+    X,
+    /// Rows wrap around, forming a horizontal cylinder.
+    Y,
+    /// Both columns and rows wrap around, forming a torus.
+    Both,
+}
+
+impl Display for Wrap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Wrap::*;
+        match self {
+            X => write!(f, "X"),
+            Y => write!(f, "Y"),
+            Both => write!(f, "Both"),
+        }
+    }
+}
+
+/// Coordinates that can be wrapped around a board of columns and rows of
+/// array offsets.  This requires that shifting the array offsets by a whole
+/// board is a translation of the grid, which does not hold for triangular
+/// grids, as their array offsets do not form rectangles.
+pub trait WrappingCoord: ArrayOffsetCoord + Copy {
+    /// The number of columns of a board wrapping along the x-axis must be a
+    /// multiple of this value.  For hexagonal grids alternate columns are
+    /// offset, so there must be an even number of columns for the pattern
+    /// to continue across the seam.
+    const COLUMN_MULTIPLE: usize = 1;
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A wrapping topology over the coordinates of a grid.  Coordinates
+/// produced by a `Topology` are always normalised into its fundamental
+/// domain, while those provided to it may lie anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Topology<C: WrappingCoord> {
+    columns: Option<isize>,
+    rows: Option<isize>,
+    _marker: std::marker::PhantomData<C>,
+}
+
+impl<C: WrappingCoord> Topology<C> {
+    /// Construct a `Topology` that wraps around after the given number of
+    /// columns.
+    pub fn wrap_x(columns: usize) -> Self {
+        Topology::new(Some(columns), None)
+    }
+
+    /// Construct a `Topology` that wraps around after the given number of
+    /// rows.
+    pub fn wrap_y(rows: usize) -> Self {
+        Topology::new(None, Some(rows))
+    }
+
+    /// Construct a `Topology` that wraps around after the given number of
+    /// columns and rows.
+    pub fn wrap_both(columns: usize, rows: usize) -> Self {
+        Topology::new(Some(columns), Some(rows))
+    }
+
+    /// Internal helper to construct a `Topology`, checking that the number
+    /// of columns and rows is valid.
+    fn new(columns: Option<usize>, rows: Option<usize>) -> Self {
+        if let Some(columns) = columns {
+            assert!(
+                columns > 0 && columns % C::COLUMN_MULTIPLE == 0,
+                "The number of columns must be a positive multiple of {}, got {}",
+                C::COLUMN_MULTIPLE,
+                columns
+            );
+        }
+        if let Some(rows) = rows {
+            assert!(rows > 0, "The number of rows must be positive");
+        }
+        Topology {
+            columns: columns.map(|c| c as isize),
+            rows: rows.map(|r| r as isize),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Which axes wrap around.
+    pub fn wrap(&self) -> Wrap {
+        match (self.columns, self.rows) {
+            (Some(_), None) => Wrap::X,
+            (None, Some(_)) => Wrap::Y,
+            _ => Wrap::Both,
+        }
+    }
+
+    /// The number of columns after which the topology wraps around, if any.
+    pub fn columns(&self) -> Option<usize> {
+        self.columns.map(|c| c as usize)
+    }
+
+    /// The number of rows after which the topology wraps around, if any.
+    pub fn rows(&self) -> Option<usize> {
+        self.rows.map(|r| r as usize)
+    }
+
+    /// Produce the coordinate within the fundamental domain that is
+    /// identified with the given coordinate.
+    pub fn normalize(&self, coord: &C) -> C {
+        let (x, y) = coord.grid_to_array_offset();
+        C::array_offset_to_grid((
+            self.columns.map_or(x, |c| x.rem_euclid(c)),
+            self.rows.map_or(y, |r| y.rem_euclid(r)),
+        ))
+    }
+
+    /// Is the given coordinate within the fundamental domain?
+    pub fn is_normalized(&self, coord: &C) -> bool {
+        self.normalize(coord) == *coord
+    }
+
+    /// Produce the coordinates of the fundamental domain.  Should either
+    /// axis not wrap, the domain is infinite, so this is only available for
+    /// topologies wrapping along both axes.
+    pub fn domain(&self) -> Option<HashShape<C>> {
+        let (columns, rows) = (self.columns?, self.rows?);
+        Some(
+            (0..columns)
+                .flat_map(|x| (0..rows).map(move |y| C::array_offset_to_grid((x, y))))
+                .collect(),
+        )
+    }
+
+    /// Move from the coordinate in the given `Direction`, wrapping around
+    /// as necessary.  Returns `None` if the `Direction` is not allowed.
+    pub fn move_in_direction(
+        &self,
+        coord: &C,
+        dir_type: DirectionType,
+        dir: Direction,
+    ) -> Option<C> {
+        coord
+            .move_in_direction(dir_type, dir)
+            .map(|next| self.normalize(&next))
+    }
+
+    /// Move from the coordinate along the given axis, wrapping around as
+    /// necessary.
+    pub fn move_on_axis(&self, coord: &C, axis: C::Axes, positive: bool) -> C {
+        self.normalize(&coord.move_on_axis(axis, positive))
+    }
+
+    /// Produce an iterator that steps from the coordinate in the given
+    /// `Direction`, as with `Coord::direction_iterator`, but wrapping around
+    /// as necessary.  So unless bounded by the `range`, iterating along a
+    /// wrapping axis will revisit coordinates.
+    pub fn direction_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        coord: &C,
+        dir_type: DirectionType,
+        dir: Direction,
+        range: RB,
+    ) -> impl Iterator<Item=C> {
+        let topology = *self;
+        coord
+            .direction_iterator(dir_type, dir, range)
+            .map(move |next| topology.normalize(&next))
+    }
+
+    /// Produce an iterator that steps from the coordinate along the given
+    /// axis, as with `Coord::axis_iterator`, but wrapping around as
+    /// necessary.
+    pub fn axis_iterator<RB: AllowedCoordIterRange>(
+        &self,
+        coord: &C,
+        axis: C::Axes,
+        positive: bool,
+        range: RB,
+    ) -> impl Iterator<Item=C> {
+        let topology = *self;
+        coord
+            .axis_iterator(axis, positive, range)
+            .map(move |next| topology.normalize(&next))
+    }
+
+    /// Compute the distance between the two coordinates, as with
+    /// `Coord::distance`, taking the shortest way around.
+    pub fn distance(&self, from: &C, to: &C) -> usize {
+        let from = self.normalize(from);
+        from.distance(&self.nearest_image(&from, to))
+    }
+
+    /// Produce an iterator that steps through the coordinates between the
+    /// two coordinates, as with `Coord::path_iterator`, taking the shortest
+    /// way around.  The path is inclusive of both coordinates, once
+    /// normalised, and its length is one more than their wrapped
+    /// `distance`.
+    pub fn path_iterator(&self, from: &C, to: &C) -> impl Iterator<Item=C> {
+        let topology = *self;
+        let from = self.normalize(from);
+        let to = self.nearest_image(&from, to);
+        // The path borrows the coordinates, so collect it before they go
+        // out of scope.
+        from.path_iterator(&to)
+            .map(move |next| topology.normalize(&next))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    /// Internal helper to find the copy of the `to` coordinate, among the
+    /// repetitions of the board, that is closest to the normalised `from`
+    /// coordinate.
+    fn nearest_image(&self, from: &C, to: &C) -> C {
+        let (x, y) = self.normalize(to).grid_to_array_offset();
+        // Both coordinates lie within the fundamental domain, so the
+        // nearest copy lies in an adjacent repetition of the board.
+        let shifts = |period: Option<isize>| match period {
+            Some(period) => vec![-period, 0, period],
+            None => vec![0],
+        };
+        shifts(self.columns)
+            .into_iter()
+            .flat_map(|dx| shifts(self.rows).into_iter().map(move |dy| (dx, dy)))
+            .map(|(dx, dy)| C::array_offset_to_grid((x + dx, y + dy)))
+            .min_by_key(|image| from.distance(image))
+            .expect("There should always be at least one image")
+    }
+}
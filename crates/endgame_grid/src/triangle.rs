@@ -1,12 +1,15 @@
 use crate::shape::HashShape;
-use crate::utils::vertices_to_edges;
-use crate::{AllowedCoordIterRange, Color, DirectionType, Point, Shape};
+use crate::notation::{parse_component, parse_tuple, ParseCoordError};
+use crate::utils::{vertices_to_edges, Cover};
+use crate::visibility::SightCell;
+use crate::{AllowedCoordIterRange, Color, DirectionType, Metric, Point, Shape};
 use endgame_direction::{Direction, DirectionSet};
 use glam::{ivec2, ivec3, IVec2, IVec3, Vec2, Vec3Swizzles};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::f32::consts::PI;
 use std::fmt::Display;
+use std::str::FromStr;
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -81,6 +84,54 @@ impl Display for TrianglePoint {
     }
 }
 
+impl FromStr for TrianglePoint {
+    type Err = ParseCoordError;
+
+    /// Parse either of the glyphs produced by `Display`, or the name of
+    /// the variant.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use TrianglePoint::*;
+        match s.trim() {
+            "▲" | "⏶" | "Up" => Ok(Up),
+            "▼" | "⏷" | "Down" => Ok(Down),
+            _ => Err(ParseCoordError::Point(s.to_string())),
+        }
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The offset coordinate schemes for triangular grids, analogous to the
+/// offset coordinate schemes for hexagonal grids.  Each row is a horizontal
+/// strip of triangles alternately pointing upwards and downwards, with the
+/// triangles sharing a column index stacked vertically in screen space, so
+/// that a rectangular range of offsets forms a rectangle on screen.  Rows
+/// increase upwards and columns to the right.  As vertically adjacent
+/// triangles point in opposite directions, the schemes differ only in which
+/// triangles point upwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum OffsetScheme {
+    /// The triangles whose column and row sum to an even number point
+    /// upwards, so even rows start with an upward pointing triangle.  The
+    /// origin is at offset (0, 0).
+    Even,
+    /// The triangles whose column and row sum to an odd number point
+    /// upwards, so even rows start with a downward pointing triangle.  The
+    /// origin is at offset (1, 0).
+    Odd,
+}
+
+impl Display for OffsetScheme {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use OffsetScheme::*;
+        match self {
+            Even => write!(f, "Even"),
+            Odd => write!(f, "Odd"),
+        }
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// This implementation of a triangular grid was influenced significantly by
@@ -172,6 +223,36 @@ impl Coord {
         (self.0, self.1)
     }
 
+    /// Construct a new `Coord` from a column and row, as the x and y
+    /// components respectively, in the given offset scheme.
+    pub const fn from_offset(scheme: OffsetScheme, offset: IVec2) -> Self {
+        use TrianglePoint::*;
+        // Rows slant to the right with respect to the lanes, so undo the
+        // shift of each row to find the position within the row.
+        let position = offset.x - offset.y - match scheme {
+            OffsetScheme::Even => 0,
+            OffsetScheme::Odd => 1,
+        };
+        let point = if position.rem_euclid(2) == 0 { Up } else { Down };
+        Coord::new(position.div_euclid(2), offset.y, point)
+    }
+
+    /// Convert the coordinate to a column and row, as the x and y components
+    /// respectively, in the given offset scheme.
+    pub const fn to_offset(&self, scheme: OffsetScheme) -> IVec2 {
+        use TrianglePoint::*;
+        let position = 2 * self.0.x
+            + match self.1 {
+                Up => 0,
+                Down => 1,
+            };
+        let shift = match scheme {
+            OffsetScheme::Even => 0,
+            OffsetScheme::Odd => 1,
+        };
+        ivec2(position + self.0.y + shift, self.0.y)
+    }
+
     /// Internal helper to convert a cubical coordinate into a `Coord`.
     fn from_cubical(coord: IVec3) -> Self {
         use TrianglePoint::*;
@@ -193,6 +274,35 @@ impl Coord {
         Coord(IVec2::new(x, y), if up { Up } else { Down })
     }
 
+    /// Internal helper for walking the segment between the centers of two
+    /// triangles.  Scaled by three, the center of each triangle lies one or
+    /// two units within the lanes of its cubical coordinates, such that the
+    /// positions sum to zero.
+    fn lane_walk(start: &Coord, end: &Coord, cover: Cover<3>) -> impl Iterator<Item=Coord> + use<> {
+        let lanes = |coord: &Coord| coord.center_lanes().as_i64vec3().to_array();
+        let valid = |[x, y, z]: &[i64; 3]| matches!(x + y + z, 1 | 2);
+        crate::utils::lane_walk(lanes(start), lanes(end), 3, valid, cover)
+            .into_iter()
+            .map(|[x, y, z]| Coord::from_cubical(IVec3::new(x as i32, y as i32, z as i32)))
+    }
+
+    /// Internal helper to find the position of the center of the triangle
+    /// along each of the lanes, scaled by three.
+    fn center_lanes(&self) -> IVec3 {
+        let offset = match self.1 {
+            TrianglePoint::Up => 2,
+            TrianglePoint::Down => 1,
+        };
+        self.to_cubical() * 3 - offset
+    }
+
+    /// Internal helper to find the triangle whose center has the given
+    /// positions along the lanes, scaled by three.
+    fn from_center_lanes(lanes: IVec3) -> Coord {
+        let offset = if lanes.x.rem_euclid(3) == 1 { 2 } else { 1 };
+        Coord::from_cubical((lanes + offset) / 3)
+    }
+
     /// Internal helper to convert a `Coord` into the cube
     /// coordinate system.
     const fn to_cubical(&self) -> IVec3 {
@@ -244,6 +354,115 @@ impl Coord {
         }
         HashShape::from_iter(coords.into_iter())
     }
+
+    /// Produce a rectangle of the given `width` and `height`, with the
+    /// origin in one corner.  Each of the `height` rows consists of `width`
+    /// triangles along the `B` axis, with each row starting where the
+    /// previous one did in screen space.  Rows only share edges where they
+    /// are at least two triangles wide.
+    pub fn rectangle(width: usize, height: usize) -> HashShape<Coord> {
+        let mut coords = Vec::new();
+        for y in 0..height as i32 {
+            // Rows alternate between starting with an upward and a downward
+            // pointing triangle, and shift back to compensate for the slant
+            // of the lanes.
+            let x = -((y + 1) / 2);
+            let z = (if y % 2 == 0 { 2 } else { 1 }) - x - y;
+            let mut coord = Coord::from_cubical(ivec3(x, y, z));
+            for _ in 0..width {
+                coords.push(coord);
+                coord = <Coord as crate::Coord>::move_on_axis(&coord, Axes::B, true);
+            }
+        }
+        HashShape::from_iter(coords)
+    }
+
+    /// Produce a parallelogram with the origin in one corner, whose sides
+    /// run along the two given axes.  It spans `first_len` pairs of
+    /// triangles along the `first` axis and `second_len` along the
+    /// `second`, and so contains twice as many coordinates as the
+    /// corresponding parallelogram on square or hexagonal grids.
+    pub fn parallelogram(
+        first: Axes,
+        first_len: usize,
+        second: Axes,
+        second_len: usize,
+    ) -> HashShape<Coord> {
+        assert!(first != second, "The axes of a parallelogram must differ");
+        // Moving along an axis leaves the lane for that axis unchanged, so
+        // the extent along each axis is that of the other lane.
+        let lane = |axis: Axes| match axis {
+            Axes::A => 0,
+            Axes::B => 1,
+            Axes::C => 2,
+        };
+        let origin = Coord::default().to_cubical().to_array();
+        let mut bounds = [i32::MIN..=i32::MAX, i32::MIN..=i32::MAX, i32::MIN..=i32::MAX];
+        let (first, second) = (lane(first), lane(second));
+        bounds[second] = origin[second]..=origin[second] + first_len as i32 - 1;
+        bounds[first] = origin[first]..=origin[first] + second_len as i32 - 1;
+        Coord::from_lane_bounds(bounds)
+    }
+
+    /// Produce a triangle with sides of `size` triangles, with the origin in
+    /// one corner.  It contains `size` squared coordinates.
+    pub fn triangle(size: usize) -> HashShape<Coord> {
+        let isize = size as i32;
+        Coord::from_lane_bounds([0..=isize - 1, 0..=isize - 1, 3 - isize..=2])
+    }
+
+    /// Produce a hexagon of the given `radius`, centered on a vertex of the
+    /// origin.  It contains six times `radius` squared coordinates.
+    pub fn hexagon(radius: usize) -> HashShape<Coord> {
+        let iradius = radius as i32;
+        Coord::from_lane_bounds([
+            1 - iradius..=iradius,
+            -iradius..=iradius - 1,
+            2 - iradius..=iradius + 1,
+        ])
+    }
+
+    /// Produce the coordinates at the given `radius` from the origin under
+    /// the given `Metric`.
+    pub fn metric_ring(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_ring(Coord::default(), metric, radius)
+    }
+
+    /// Produce the coordinates within the given `radius` of the origin under
+    /// the given `Metric`.  As the origin is an upward facing triangle, these
+    /// form triangles with `Metric::Face`, hexagons with `Metric::Vertex`,
+    /// and a disc with `Metric::Euclidean`.
+    pub fn metric_range(metric: Metric, radius: usize) -> HashShape<Coord> {
+        crate::utils::metric_range(Coord::default(), metric, radius)
+    }
+
+    /// Internal helper to produce the triangles whose cubical coordinates
+    /// lie within the given bounds, at least two of which must be finite.
+    fn from_lane_bounds(bounds: [std::ops::RangeInclusive<i32>; 3]) -> HashShape<Coord> {
+        // Enumerate the two most constrained lanes, with the third
+        // determined by the orientation of the triangle.
+        let mut lanes = [0, 1, 2];
+        lanes.sort_by_key(|lane| {
+            (*bounds[*lane].end() as i64 - *bounds[*lane].start() as i64).max(0)
+        });
+        let [first, second, third] = lanes;
+        let mut coords = Vec::new();
+        for i in bounds[first].clone() {
+            for j in bounds[second].clone() {
+                for sum in [1, 2] {
+                    let k = sum - i - j;
+                    if bounds[third].contains(&k) {
+                        let mut cube = [0; 3];
+                        cube[first] = i;
+                        cube[second] = j;
+                        cube[third] = k;
+                        coords.push(Coord::from_cubical(IVec3::from_array(cube)));
+                    }
+                }
+            }
+        }
+        HashShape::from_iter(coords)
+    }
 }
 
 impl Default for Coord {
@@ -262,6 +481,17 @@ impl Display for Coord {
     }
 }
 
+impl FromStr for Coord {
+    type Err = ParseCoordError;
+
+    /// Parse a coordinate of the form `(x,y,▲)`, as produced by `Display`
+    /// with or without the alternate flag.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let [x, y, point] = parse_tuple(s, "a triangle coordinate of the form (x,y,▲)")?;
+        Ok(Coord::new(parse_component(x)?, parse_component(y)?, point.parse()?))
+    }
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 impl crate::Coord for Coord {
@@ -275,6 +505,25 @@ impl crate::Coord for Coord {
         (other.to_cubical() - self.to_cubical()).abs().element_sum() as usize
     }
 
+    fn vertex_distance(&self, other: &Self) -> usize {
+        // Each pair of vertex steps moves the center at most six units
+        // along every lane, while a single step is required to change
+        // orientation, moving the center up to four units.
+        let spread = (other.center_lanes() - self.center_lanes()).abs().max_element();
+        if self.1 == other.1 {
+            2 * ((spread + 5) / 6) as usize
+        } else {
+            2 * (((spread - 4).max(0) + 5) / 6) as usize + 1
+        }
+    }
+
+    fn euclidean_distance(&self, other: &Self) -> f32 {
+        // Face neighbours have centers differing by (2, -1, -1) in some
+        // order along the lanes.
+        let diff = (other.center_lanes() - self.center_lanes()).as_vec3();
+        (diff.length_squared() / 6.0).sqrt()
+    }
+
     fn angle_to_direction(&self, dir_type: DirectionType, angle: f32) -> Direction {
         use Direction::*;
         use TrianglePoint::*;
@@ -410,6 +659,17 @@ impl crate::Coord for Coord {
         TrianglePathIter::new(self, other)
     }
 
+    fn line_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        // Only those triangles whose interiors the segment passes through,
+        // so where it passes through a vertex the line will step along a
+        // vertex direction.
+        Coord::lane_walk(self, other, Cover::Thin)
+    }
+
+    fn supercover_iterator(&self, other: &Self) -> impl Iterator<Item=Self> {
+        Coord::lane_walk(self, other, Cover::Super)
+    }
+
     fn axis_iterator<RB: AllowedCoordIterRange>(
         &self,
         axis: Self::Axes,
@@ -487,6 +747,130 @@ impl crate::Coord for Coord {
 
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
+impl crate::canonical::CanonicalCoord for Coord {
+    fn translate_from(&self, anchor: &Self) -> Self {
+        Coord(self.0 - anchor.0, self.1)
+    }
+
+    fn half_turn(&self) -> Option<Self> {
+        // A half turn about the midpoint of the edge shared by the origin
+        // and the downward facing triangle below it.
+        Some(Coord::from_cubical(IVec3::new(0, 0, 3) - self.to_cubical()))
+    }
+}
+
+impl crate::symmetry::SymmetricCoord for Coord {
+    // Rotations are about the center of the origin triangle, so only
+    // three steps are needed to return to the start.
+    const ROTATIONS: usize = 3;
+    const REFLECTION_AXIS: Axes = Axes::A;
+
+    fn reflection_steps(axis: Axes) -> usize {
+        use Axes::*;
+        match axis {
+            A => 0,
+            B => 2,
+            C => 1,
+        }
+    }
+}
+
+impl crate::text::TextCoord for Coord {
+    fn to_text_offset(&self) -> (i32, i32) {
+        let offset = self.to_offset(OffsetScheme::Even);
+        (offset.x, offset.y)
+    }
+
+    fn from_text_offset(offset: (i32, i32)) -> Self {
+        Coord::from_offset(OffsetScheme::Even, ivec2(offset.0, offset.1))
+    }
+
+    fn glyph(&self) -> Option<char> {
+        self.1.to_string().chars().next()
+    }
+}
+
+impl crate::parts::PartCoord for Coord {
+    fn boundary(&self) -> Vec<(Direction, Direction)> {
+        use Direction::*;
+        if self.is_up() {
+            vec![(North, NorthEast), (SouthEast, South), (SouthWest, NorthWest)]
+        } else {
+            vec![(NorthWest, North), (NorthEast, SouthEast), (South, SouthWest)]
+        }
+    }
+}
+
+impl crate::visibility::SightCoord for Coord {
+    fn sight_lines(&self, other: &Self) -> [Vec<Self>; 2] {
+        let delta = other.center_lanes() - self.center_lanes();
+        let shift = crate::utils::triangular_shift(delta.as_i64vec3().to_array());
+        [shift, shift.map(|s| -s)]
+            .map(|shift| Coord::lane_walk(self, other, Cover::Displaced(shift)).collect())
+    }
+
+    fn sight_sectors(&self) -> Vec<[[i64; 2]; 2]> {
+        [[[1, -1], [1, 0]], [[-1, 1], [-1, 0]]].repeat(3)
+    }
+
+    fn sight_row(&self, sector: usize, depth: usize) -> Vec<SightCell<Self>> {
+        // Measured from the center of this triangle, and negated should it
+        // point down, the grid lines lie at one less than the multiples of
+        // three along every lane.  So rotating the lanes is a symmetry, and
+        // each sector is scanned as if it were one of the two where the
+        // first lane has the opposite sign to the others.  Segments within
+        // such a sector only ever cross grid lines in the one direction
+        // along each lane, so the index along the first lane, less those
+        // along the others, increases with every triangle crossed.  Each row
+        // holds two consecutive values of this level, so that the nearest
+        // triangles of both orientations are found in every row.
+        let sign = match self.1 {
+            TrianglePoint::Up => 1,
+            TrianglePoint::Down => -1,
+        };
+        let flip = if sector.is_multiple_of(2) { 1 } else { -1 };
+        let level = 2 * depth as i64 + 1;
+        (level..=level + 1)
+            .flat_map(|m| {
+                // Triangles pointing the same way as this one have indices
+                // summing to zero, and those pointing the other way to
+                // minus one.
+                let other = m % 2;
+                let first = (flip * m - other) / 2;
+                let rest = -first - other;
+                (0..=rest.abs()).map(move |k| {
+                    let second = rest.signum() * k;
+                    ([first, second, rest - second], other)
+                })
+            })
+            .map(|(indices, other)| {
+                let center = indices.map(|index| 3 * index + other);
+                let mut offset = IVec3::ZERO;
+                for (lane, local) in center.iter().enumerate() {
+                    offset[(lane + sector / 2) % 3] = sign * *local as i32;
+                }
+                let side = 1 - 2 * other;
+                let vertices = [[2, -1], [-1, 2], [-1, -1]]
+                    .map(|[x, y]| [center[0] + side * x, center[1] + side * y])
+                    .to_vec();
+                SightCell {
+                    coord: Coord::from_center_lanes(self.center_lanes() + offset),
+                    center: [center[0], center[1]],
+                    vertices,
+                }
+            })
+            .collect()
+    }
+}
+
+impl crate::ArrayOffsetCoord for Coord {
+    fn array_offset_to_grid(array_offset: (isize, isize)) -> Self {
+        Coord::array_offset_to_grid(array_offset)
+    }
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
 pub struct DirectionIter<RB: AllowedCoordIterRange> {
     pub current: Coord,
     pub dir_type: DirectionType,
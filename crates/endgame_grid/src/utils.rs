@@ -1,5 +1,5 @@
 use crate::shape::HashShape;
-use crate::Coord;
+use crate::{Coord, DirectionType, Metric};
 pub(crate) use crate::{AllowedCoordIterRange, ModuleCoord};
 use glam::Vec2;
 use itertools::Itertools;
@@ -133,6 +133,68 @@ pub fn ring<C: Coord>(
     HashShape::from_iter(coords.into_iter())
 }
 
+/// A generic implementation for producing parallelograms by repeatedly
+/// moving along two axes from the `origin`, for those grids where moving
+/// along an axis is a translation.
+pub fn parallelogram<C: Coord>(
+    origin: C,
+    first: C::Axes,
+    first_len: usize,
+    second: C::Axes,
+    second_len: usize,
+) -> HashShape<C> {
+    assert!(first != second, "The axes of a parallelogram must differ");
+    let mut coords = Vec::new();
+    let mut row_coord = origin;
+    for _ in 0..second_len {
+        let mut coord = row_coord.clone();
+        for _ in 0..first_len {
+            coords.push(coord.clone());
+            coord = coord.move_on_axis(first, true);
+        }
+        row_coord = row_coord.move_on_axis(second, true);
+    }
+    HashShape::from_iter(coords)
+}
+
+/// A generic implementation for producing the coordinates within the given
+/// `radius` of the `origin` under a `Metric`, by searching outwards from the
+/// origin in face and vertex directions.  This relies upon the coordinates
+/// within the radius being connected by such steps, which holds for all the
+/// metrics.
+pub fn metric_range<C: Coord>(origin: C, metric: Metric, radius: usize) -> HashShape<C> {
+    let limit = radius as f32;
+    let mut coords = HashSet::from([origin.clone()]);
+    let mut frontier = vec![origin.clone()];
+    while let Some(coord) = frontier.pop() {
+        for dir_type in [DirectionType::Face, DirectionType::Vertex] {
+            for dir in coord.allowed_directions(dir_type).iter() {
+                let Some(next) = coord.move_in_direction(dir_type, dir) else {
+                    continue;
+                };
+                if !coords.contains(&next) && metric.distance(&origin, &next) <= limit {
+                    coords.insert(next.clone());
+                    frontier.push(next);
+                }
+            }
+        }
+    }
+    HashShape::from_iter(coords)
+}
+
+/// A generic implementation for producing the coordinates at the given
+/// `radius` from the `origin` under a `Metric`.  For the `Euclidean` metric,
+/// whose distances need not be integers, these are the coordinates further
+/// than `radius - 1` from the origin, but within `radius`.  So the rings for
+/// each radius are disjoint and together make up the range.
+pub fn metric_ring<C: Coord>(origin: C, metric: Metric, radius: usize) -> HashShape<C> {
+    let inner = radius as f32 - 1.0;
+    metric_range(origin.clone(), metric, radius)
+        .into_iter()
+        .filter(|coord| metric.distance(&origin, coord) > inner)
+        .collect()
+}
+
 //////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// A generic iterator for traversing grids where the coordinates form an
@@ -172,3 +234,165 @@ where
         Some(result)
     }
 }
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Which coordinates `lane_walk` produces where the segment lies exactly
+/// upon a grid line, either passing through a vertex or running along an
+/// edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cover<const N: usize> {
+    /// Every coordinate touched by the segment.
+    Super,
+    /// Only the coordinates whose interiors the segment crosses.
+    Thin,
+    /// The coordinates whose interiors are crossed by the segment after
+    /// displacing it infinitesimally to one side.  The sign of each entry
+    /// is the direction in which the displacement moves positions along the
+    /// corresponding lane.
+    Displaced([i64; N]),
+}
+
+/// A generic implementation for walking the segment between two points of a
+/// grid, described by their positions along `N` "lanes".  Each lane is a
+/// projection of the plane, divided by grid lines at the multiples of
+/// `scale`, such that the index of a coordinate along a lane is the ceiling
+/// of the position divided by `scale`.  The `valid` predicate determines
+/// which combinations of indices correspond to coordinates.
+///
+/// Produces the indices of the coordinates touched by the segment, as
+/// selected by the `cover`, in order along the segment.  As positions are
+/// integers, the walk is exact.
+pub fn lane_walk<const N: usize, F>(
+    start: [i64; N],
+    end: [i64; N],
+    scale: i64,
+    valid: F,
+    cover: Cover<N>,
+) -> Vec<[i64; N]>
+where
+    F: Fn(&[i64; N]) -> bool,
+{
+    let scale = scale as i128;
+    let start = start.map(|s| s as i128);
+    let delta: [i128; N] = std::array::from_fn(|k| end[k] as i128 - start[k]);
+
+    // Find the parameters, as fractions, at which the segment crosses a
+    // grid line, along with the endpoints.
+    let mut events: Vec<(i128, i128)> = vec![(0, 1), (1, 1)];
+    for k in 0..N {
+        if delta[k] == 0 {
+            continue;
+        }
+        let (lo, hi) = (start[k].min(start[k] + delta[k]), start[k].max(start[k] + delta[k]));
+        let mut line = -(-lo).div_euclid(scale) * scale;
+        while line <= hi {
+            let (num, den) = (line - start[k], delta[k]);
+            events.push(if den < 0 { (-num, -den) } else { (num, den) });
+            line += scale;
+        }
+    }
+    events.sort_by(|a, b| (a.0 * b.1).cmp(&(b.0 * a.1)));
+    events.dedup_by(|a, b| a.0 * b.1 == b.0 * a.1);
+
+    // Examine the segment at each event, and between each pair of events.
+    let mut moments = Vec::new();
+    for (index, event) in events.iter().enumerate() {
+        moments.push(*event);
+        if let Some(next) = events.get(index + 1) {
+            moments.push((event.0 * next.1 + next.0 * event.1, 2 * event.1 * next.1));
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    for (num, den) in moments {
+        // For each lane, the indices of the coordinates touched, ordered
+        // from before to after the moment.
+        let mut on_line = false;
+        let lanes: [(i128, i128); N] = std::array::from_fn(|k| {
+            let position = start[k] * den + num * delta[k];
+            let index = -(-position).div_euclid(scale * den);
+            if position.rem_euclid(scale * den) != 0 {
+                return (index, index);
+            }
+            let crossing = if delta[k] < 0 { (index + 1, index) } else { (index, index + 1) };
+            match cover {
+                Cover::Super => crossing,
+                Cover::Thin => {
+                    on_line = true;
+                    crossing
+                }
+                // Displacing a segment running along the line moves it off
+                // the line, to the side with the greater index if the
+                // position increases.  Otherwise the line is still crossed.
+                Cover::Displaced(shift) if delta[k] == 0 => match shift[k].signum() {
+                    1 => (index + 1, index + 1),
+                    -1 => (index, index),
+                    _ => crossing,
+                },
+                Cover::Displaced(_) => crossing,
+            }
+        });
+        // For a thin cover, the coordinates either side of a grid line are
+        // found between the events.
+        if on_line {
+            continue;
+        }
+        let crossed: Vec<usize> = (0..N).filter(|&k| lanes[k].0 != lanes[k].1).collect();
+        let combinations: Vec<u32> = match cover {
+            // A displaced segment crosses the lines meeting here one after
+            // another, the line along each lane offset in time by the
+            // displacement over the change in position.  So only the
+            // combinations where the earliest lines have been crossed occur.
+            Cover::Displaced(shift) => {
+                let offset = |k: usize| (-shift[k] as i128 * delta[k].signum(), delta[k].abs());
+                let mut order = crossed.clone();
+                order.sort_by(|a, b| {
+                    let (a, b) = (offset(*a), offset(*b));
+                    (a.0 * b.1).cmp(&(b.0 * a.1))
+                });
+                (0..=order.len())
+                    .filter(|count| {
+                        let simultaneous = |k: usize| {
+                            let (a, b) = (offset(order[k - 1]), offset(order[k]));
+                            a.0 * b.1 == b.0 * a.1
+                        };
+                        *count == 0 || *count == order.len() || !simultaneous(*count)
+                    })
+                    .map(|count| order[..count].iter().map(|k| 1 << k).sum())
+                    .collect()
+            }
+            _ => (0..1u32 << N)
+                .filter(|bits| crossed.iter().fold(*bits, |bits, k| bits & !(1 << k)) == 0)
+                .collect(),
+        };
+        // Order the combinations by how many lanes have moved past the
+        // moment, so that coordinates are produced in order of progress.
+        // Lanes not on a grid line only have the one index to choose from.
+        let mut candidates: Vec<(u32, [i64; N])> = combinations
+            .into_iter()
+            .map(|bits| {
+                let indices = std::array::from_fn(|k| {
+                    let (before, after) = lanes[k];
+                    (if bits & (1 << k) != 0 { after } else { before }) as i64
+                });
+                (bits.count_ones(), indices)
+            })
+            .collect();
+        candidates.sort();
+        for (_, indices) in candidates {
+            if valid(&indices) && seen.insert(indices) {
+                result.push(indices);
+            }
+        }
+    }
+    result
+}
+
+/// The `Cover::Displaced` for displacing a segment with the given change in
+/// position along each of three lanes at 120 degrees to one another.  For
+/// each lane, this is the difference in the changes along the other two.
+pub fn triangular_shift([u, v, w]: [i64; 3]) -> [i64; 3] {
+    [w - v, u - w, v - u]
+}
@@ -0,0 +1,178 @@
+//! Line of sight and field of view computations.
+//!
+//! One coordinate is visible from another if the segment between their
+//! centers does not cross the interior of any opaque coordinate other than
+//! the two endpoints.  On many grids such segments may pass exactly
+//! through vertices, or along the edges between coordinates, so the segment
+//! is considered displaced infinitesimally to either side, and sight is
+//! only blocked if both are obstructed.  This allows seeing past the corner
+//! of a single opaque coordinate, but not between two opaque coordinates
+//! that meet at a vertex.  As the segment is the same in either direction,
+//! visibility is symmetric: if `a` can see `b`, then `b` can see `a`.
+//!
+//! The segments are walked using the exact integer lattice math of each
+//! kind of grid, rather than the screen space geometry of a `SizedGrid`.
+//! Which coordinates a segment crosses is unchanged by scaling, rotating or
+//! shearing the plane, so neither the size nor the orientation of a
+//! `SizedGrid` could affect the results, while integer math avoids rounding
+//! altogether.  The cost of `has_line_of_sight` is proportional to the
+//! distance between the coordinates.
+//!
+//! The field of view is instead computed by shadowcasting.  The plane
+//! around the origin is divided into sectors, octants on square grids and
+//! sextants on hex and triangle grids, and each sector is scanned row by
+//! row moving away from the origin, tracking the intervals of directions
+//! that remain lit.  Every opaque coordinate removes the directions that
+//! cross its interior from those lit, and a coordinate is visible when
+//! the direction to its center is lit.  So the cost is proportional to the
+//! number of coordinates within the radius, and a sector stops being
+//! scanned once it is entirely in shadow.  The result is the same as
+//! checking `has_line_of_sight` for each coordinate within the radius.
+
+use crate::shape::HashShape;
+use crate::{Coord, Metric};
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An extension of `Coord` for those grids which support computing sight
+/// lines.
+pub trait SightCoord: Coord {
+    /// Produce the coordinates whose interiors are crossed by the segment
+    /// between the centers of this coordinate and `other`, after displacing
+    /// it infinitesimally to either side.  Each of the two sight lines is
+    /// ordered from this coordinate to `other`, including both.
+    fn sight_lines(&self, other: &Self) -> [Vec<Self>; 2];
+
+    /// Produce the pairs of directions bounding the sectors scanned by
+    /// shadowcasting around this coordinate, which together cover the whole
+    /// plane.  Each pair is in counter-clockwise order and less than a half
+    /// turn apart, within the integer frame of its sector.
+    fn sight_sectors(&self) -> Vec<[[i64; 2]; 2]>;
+
+    /// Produce the coordinates in the row at the given `depth` of a sector
+    /// around this coordinate, starting from zero.  Every segment leaving the
+    /// center of this coordinate in a direction strictly within the sector
+    /// must cross the rows in order, and the coordinates within each row in
+    /// order.  Under every `Metric`, the nearest coordinates of each row
+    /// must be no further from this coordinate than those of later rows.
+    fn sight_row(&self, sector: usize, depth: usize) -> Vec<SightCell<Self>>;
+}
+
+/// A coordinate within a row scanned by shadowcasting, along with the
+/// positions of its center and vertices within the integer frame of the
+/// sector, relative to the center of the origin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SightCell<C> {
+    pub coord: C,
+    pub center: [i64; 2],
+    pub vertices: Vec<[i64; 2]>,
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Is the coordinate `to` visible from the coordinate `from`?  The `blocks`
+/// predicate determines which coordinates are opaque, for example
+/// `|coord| walls.contains(coord)` for a `Shape` of walls.  Whether the
+/// endpoints themselves are opaque is irrelevant, so opaque coordinates may
+/// be seen, but not seen past.
+pub fn has_line_of_sight<C: SightCoord, B>(from: &C, to: &C, blocks: B) -> bool
+where
+    B: Fn(&C) -> bool,
+{
+    from.sight_lines(to).iter().any(|line| {
+        line.iter()
+            .all(|coord| coord == from || coord == to || !blocks(coord))
+    })
+}
+
+/// Compute the coordinates visible from `origin`, out to the given `radius`
+/// as measured by the `metric`.  The `blocks` predicate determines which
+/// coordinates are opaque.  A coordinate is in the result exactly when it is
+/// within the radius and `has_line_of_sight` from the `origin`, so the
+/// result will always include the `origin`, along with any opaque
+/// coordinates that can be seen.
+pub fn field_of_view<C: SightCoord, B>(
+    origin: &C,
+    metric: Metric,
+    radius: usize,
+    blocks: B,
+) -> HashShape<C>
+where
+    B: Fn(&C) -> bool,
+{
+    let limit = radius as f32;
+    let mut visible = HashSet::from([origin.clone()]);
+    for (sector, [low, high]) in origin.sight_sectors().into_iter().enumerate() {
+        let axis = [low[0] + high[0], low[1] + high[1]];
+        let less = |u: &[i64; 2], v: &[i64; 2]| angle_cmp(axis, *u, *v) == Ordering::Less;
+        // The directions still lit, as disjoint closed intervals in order.
+        // Intervals that narrow to a single direction are dropped, as sight
+        // lines are displaced to one side or the other.
+        let mut lit = vec![(low, high)];
+        let mut depth = 0;
+        while !lit.is_empty() {
+            let mut reached = false;
+            for cell in origin.sight_row(sector, depth) {
+                let in_range = metric.distance(origin, &cell.coord) <= limit;
+                reached |= in_range;
+                let (Some(start), Some(end)) = (
+                    cell.vertices.iter().min_by(|u, v| angle_cmp(axis, **u, **v)),
+                    cell.vertices.iter().max_by(|u, v| angle_cmp(axis, **u, **v)),
+                ) else {
+                    continue;
+                };
+                // The lit intervals overlapping the directions crossing the
+                // interior of the coordinate.
+                let first = lit.partition_point(|(_, high)| !less(start, high));
+                let last = lit.partition_point(|(low, _)| less(low, end));
+                if first >= last {
+                    continue;
+                }
+                if in_range
+                    && lit[first..last]
+                        .iter()
+                        .any(|(low, high)| !less(&cell.center, low) && !less(high, &cell.center))
+                {
+                    visible.insert(cell.coord.clone());
+                }
+                if blocks(&cell.coord) {
+                    let (low, high) = (lit[first].0, lit[last - 1].1);
+                    let mut remaining = Vec::new();
+                    if less(&low, start) {
+                        remaining.push((low, *start));
+                    }
+                    if less(end, &high) {
+                        remaining.push((*end, high));
+                    }
+                    lit.splice(first..last, remaining);
+                }
+            }
+            if !reached {
+                break;
+            }
+            depth += 1;
+        }
+    }
+    visible.into_iter().collect()
+}
+
+//////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Internal helper to compare two directions by their angle from the
+/// direction opposite `axis`, such that directions either side of `axis`
+/// are ordered counter-clockwise.
+fn angle_cmp(axis: [i64; 2], u: [i64; 2], v: [i64; 2]) -> Ordering {
+    let [a, u, v] = [axis, u, v].map(|w| w.map(|c| c as i128));
+    let cross = |s: [i128; 2], t: [i128; 2]| s[0] * t[1] - s[1] * t[0];
+    // Which half of the plane each direction lies in, with the directions
+    // along and opposite the axis in between.
+    let half = |w: [i128; 2]| match cross(a, w).signum() {
+        -1 => 0,
+        1 => 2,
+        _ if a[0] * w[0] + a[1] * w[1] > 0 => 1,
+        _ => 3,
+    };
+    half(u).cmp(&half(v)).then_with(|| 0.cmp(&cross(u, v)))
+}
@@ -3,12 +3,28 @@
 // Bring the macros and other important things into scope.
 use endgame_direction::Direction;
 use endgame_grid::triangle::TrianglePoint;
-use endgame_grid::{dynamic, hex, square, triangle, ModuleCoord, Shape};
-use endgame_grid::{Coord, DirectionType, SizedGrid};
+use endgame_grid::shape::{ArrayShapeContainer, BitShape, HashShape, HashShapeContainer};
+use endgame_grid::shape::ChunkedShapeContainer;
+use endgame_grid::{connectivity, dynamic, hex, outline, pathfinding, polyform};
+use endgame_grid::{cubic, layered, prism, square, svg, text, tiling, triangle, visibility};
+use endgame_grid::layered::{LayeredCoord, Vertical};
+use endgame_grid::text::TextCoord;
+use endgame_grid::board::Board;
+use endgame_grid::parts::{Edge, PartCoord, Vertex};
+use endgame_grid::canonical::{canonicalize, canonicalize_container, is_congruent};
+use endgame_grid::canonical::{canonicalize_with, is_container_congruent};
+use endgame_grid::canonical::{orientations, CanonicalCoord, Equivalence};
+use endgame_grid::symmetry::{Isometry, SymmetricCoord, Transform};
+use endgame_grid::topology::{Topology, Wrap, WrappingCoord};
+use endgame_grid::{ModuleCoord, Shape};
+use endgame_grid::{ArrayOffsetCoord, ModuleShape, ModuleShapeContainer, ShapeContainer};
+use endgame_grid::{Coord, DirectionType, Metric, SizedGrid};
 use glam::{IVec2, Vec2};
 use proptest::prelude::*;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
 use std::iter::Iterator;
 
 //////////////////////////////////////////////////////////////////////////////
@@ -118,6 +134,50 @@ fn small_dynamic_coord_strategy() -> impl Strategy<Value=dynamic::Coord> {
     ]
 }
 
+/// A helper function to generate a strategy for coordinates clustered
+/// around the origin.  This is useful when testing dense representations
+/// whose size depends upon the extent of the coordinates.
+fn tiny_coord_strategy() -> impl Strategy<Value=(i32, i32)> {
+    (-20..20i32, -20..20i32)
+}
+
+fn tiny_squarecoord_strategy() -> impl Strategy<Value=square::Coord> {
+    tiny_coord_strategy().prop_map(|(x, y)| square::Coord::new(x, y))
+}
+
+fn tiny_hexcoord_strategy() -> impl Strategy<Value=hex::Coord> {
+    tiny_coord_strategy().prop_map(|(x, y)| hex::Coord::new(x, y))
+}
+
+fn tiny_trianglecoord_strategy() -> impl Strategy<Value=triangle::Coord> {
+    (
+        tiny_coord_strategy(),
+        prop_oneof![Just(TrianglePoint::Up), Just(TrianglePoint::Down)],
+    )
+        .prop_map(|((x, y), p)| triangle::Coord::new(x, y, p))
+}
+
+fn tiny_dynamic_coord_strategy() -> impl Strategy<Value=dynamic::Coord> {
+    prop_oneof![
+        tiny_hexcoord_strategy().prop_map(dynamic::Coord::Hex),
+        tiny_squarecoord_strategy().prop_map(dynamic::Coord::Square),
+        tiny_trianglecoord_strategy().prop_map(dynamic::Coord::Triangle),
+    ]
+}
+
+fn tiny_layered_coord_strategy() -> impl Strategy<Value=layered::Coord<dynamic::Coord>> {
+    (tiny_dynamic_coord_strategy(), -5..5i32)
+        .prop_map(|(coord, level)| layered::Coord::new(coord, level))
+}
+
+fn tiny_cubiccoord_strategy() -> impl Strategy<Value=cubic::Coord> {
+    (tiny_coord_strategy(), -20..20i32).prop_map(|((x, y), z)| cubic::Coord::new(x, y, z))
+}
+
+fn tiny_prismcoord_strategy() -> impl Strategy<Value=prism::Coord> {
+    (tiny_coord_strategy(), -20..20i32).prop_map(|((x, y), z)| prism::Coord::new(x, y, z))
+}
+
 //////////////////////////////////////////////////////////////////////////////
 
 /// Helper to check if two coordinates are adjacent by face direction.
@@ -442,6 +502,112 @@ fn grid_path<C: Coord + Copy>(coord1: C, coord2: C) -> Result<(), TestCaseError>
     Ok(())
 }
 
+/// Helper to compute the distance between two line segments.
+fn segment_distance(a: (Vec2, Vec2), b: (Vec2, Vec2)) -> f32 {
+    let side = |p: Vec2, (s, e): (Vec2, Vec2)| (e - s).perp_dot(p - s);
+    let crosses = |a: (Vec2, Vec2), b: (Vec2, Vec2)| side(a.0, b) * side(a.1, b) <= 0.0;
+    if crosses(a, b) && crosses(b, a) {
+        return 0.0;
+    }
+    let point_distance = |p: Vec2, (s, e): (Vec2, Vec2)| {
+        let t = ((p - s).dot(e - s) / (e - s).length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        p.distance(s.lerp(e, t))
+    };
+    [
+        point_distance(a.0, b),
+        point_distance(a.1, b),
+        point_distance(b.0, a),
+        point_distance(b.1, a),
+    ]
+        .into_iter()
+        .fold(f32::INFINITY, f32::min)
+}
+
+fn grid_lines(coord1: dynamic::Coord, coord2: dynamic::Coord) -> Result<(), TestCaseError> {
+    let distance = coord1.distance(&coord2);
+    let line: Vec<dynamic::Coord> = coord1.line_iterator(&coord2).collect();
+    prop_assert_eq!(line.first(), Some(&coord1), "The line should start at {}.", coord1);
+    prop_assert_eq!(line.last(), Some(&coord2), "The line should end at {}.", coord2);
+    prop_assert_eq!(
+        line.iter().collect::<HashSet<_>>().len(),
+        line.len(),
+        "There should be no duplicate coordinates in the line."
+    );
+    for (prev, next) in line.iter().zip(line.iter().skip(1)) {
+        let neighbours = [DirectionType::Face, DirectionType::Vertex].into_iter().any(|dt| {
+            prev.allowed_directions(dt)
+                .iter()
+                .any(|dir| prev.move_in_direction(dt, dir).as_ref() == Some(next))
+        });
+        prop_assert!(neighbours, "{} and {} should be neighbours.", prev, next);
+    }
+    prop_assert!(line.len() <= distance + 1, "The line should be no longer than the path.");
+    match (coord1, coord2) {
+        (dynamic::Coord::Hex(_), _) => prop_assert_eq!(line.len(), distance + 1),
+        (dynamic::Coord::Square(a), dynamic::Coord::Square(b)) => {
+            let offset = b.to_ivec2() - a.to_ivec2();
+            prop_assert_eq!(line.len() as i32, offset.abs().max_element() + 1);
+        }
+        _ => {}
+    }
+
+    let supercover: Vec<dynamic::Coord> = coord1.supercover_iterator(&coord2).collect();
+    let cover_set: HashSet<dynamic::Coord> = supercover.iter().cloned().collect();
+    prop_assert_eq!(supercover.first(), Some(&coord1), "The supercover should start at {}.", coord1);
+    prop_assert_eq!(supercover.last(), Some(&coord2), "The supercover should end at {}.", coord2);
+    prop_assert_eq!(cover_set.len(), supercover.len(), "There should be no duplicates.");
+    prop_assert!(supercover.len() > distance, "The supercover should cover a path.");
+    prop_assert!(line.iter().all(|coord| cover_set.contains(coord)));
+    prop_assert_eq!(
+        &cover_set,
+        &coord2.supercover_iterator(&coord1).collect::<HashSet<_>>(),
+        "The supercover should be symmetric."
+    );
+    // The supercover should be connected by face steps.
+    let mut reached = HashSet::from([coord1]);
+    let mut frontier = vec![coord1];
+    while let Some(coord) = frontier.pop() {
+        for dir in coord.allowed_directions(DirectionType::Face).iter() {
+            if let Some(next) = coord.move_in_direction(DirectionType::Face, dir)
+                && cover_set.contains(&next)
+                && reached.insert(next)
+            {
+                frontier.push(next);
+            }
+        }
+    }
+    prop_assert_eq!(&reached, &cover_set, "The supercover should be connected.");
+
+    Ok(())
+}
+
+fn supercover_geometry(coord1: dynamic::Coord, coord2: dynamic::Coord) -> Result<(), TestCaseError> {
+    let supercover: Vec<dynamic::Coord> = coord1.supercover_iterator(&coord2).collect();
+    let cover_set: HashSet<dynamic::Coord> = supercover.iter().cloned().collect();
+    // Geometrically, every coordinate touching the segment should be in the
+    // supercover, and every coordinate in the supercover should touch it.
+    let grid = dynamic::SizedGrid::new(coord1.kind(), 1.0);
+    let segment = (grid.grid_to_screen(&coord1), grid.grid_to_screen(&coord2));
+    for coord in &supercover {
+        let vertices = grid.vertices(coord);
+        let touches = vertices
+            .iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(a, b)| segment_distance(segment, (*a, *b)))
+            .any(|d| d < 1e-3)
+            || *coord == coord1;
+        prop_assert!(touches, "{} should touch the segment from {} to {}.", coord, coord1, coord2);
+    }
+    // Sample away from the endpoints, and at awkward fractions, so as to
+    // avoid landing exactly upon a vertex.
+    for step in 0..96 {
+        let point = segment.0.lerp(segment.1, (step as f32 + 0.5) / 96.0);
+        prop_assert!(cover_set.contains(&grid.screen_to_grid(point)));
+    }
+
+    Ok(())
+}
+
 /// Helper function that tests that for given grid coordinate, that
 /// moving in all allowed directions is possible, and that moving the
 /// opposite direction returns to the original coordinate.
@@ -819,13 +985,13 @@ where
         );
         let moved_coord = opt_moved_coord.unwrap();
         let moved_screen_coord = sized_grid.grid_to_screen(&moved_coord);
-        let opt_angle = coord.direction_angle(dir_type, dir);
+        let opt_angle = sized_grid.direction_angle(&coord, dir_type, dir);
         prop_assert!(
             opt_angle.is_some(),
             "Direction {dir} should be allowed from coordinate {moved_coord}"
         );
         let angle = opt_angle.unwrap();
-        let opt_back_angle = moved_coord.direction_angle(dir_type, dir.opposite());
+        let opt_back_angle = sized_grid.direction_angle(&moved_coord, dir_type, dir.opposite());
         prop_assert!(
             opt_back_angle.is_some(),
             "Direction {dir} should be allowed from coordinate {moved_coord}"
@@ -942,183 +1108,1976 @@ fn grid_shapes(kind: dynamic::Kind) -> Result<(), TestCaseError> {
     Ok(())
 }
 
-static SIZE_RANGE: std::ops::Range<f32> = 0.001..65535.0f32;
-
-proptest! {
-    #[test]
-    fn hex_unary_op(coord1 in hexcoord_strategy()) {
-        coord_neg(coord1)?;
-        coord_add_ident(coord1)?;
-        coord_sub_unit(coord1)?;
-        coord_mul_unit(coord1)?;
+/// Helper that verifies that an `ArrayShapeContainer` built from the given
+/// coordinates behaves identically to the equivalent `HashShapeContainer`.
+fn array_shape_container<C: ArrayOffsetCoord + Copy>(coords: Vec<C>) -> Result<(), TestCaseError> {
+    let hash_container: HashShapeContainer<C, usize> =
+        coords.iter().cloned().enumerate().map(|(i, c)| (c, i)).collect();
 
-        // Verify that the origin is the same as the default coordinate.
-        prop_assert_eq!(dynamic::Coord::Hex(hex::Coord::default()),
-        dynamic::Coord::origin(dynamic::Kind::Hex))
+    // Build incrementally, so that growing the bounds is exercised.
+    let mut array_container = ArrayShapeContainer::new();
+    for (i, c) in coords.iter().enumerate() {
+        array_container.insert(*c, i);
+    }
+    prop_assert_eq!(array_container.len(), hash_container.len());
+    prop_assert_eq!(array_container.is_empty(), hash_container.is_empty());
+    for (c, v) in hash_container.iter() {
+        prop_assert!(array_container.contains(c), "Container should contain {}", c);
+        prop_assert_eq!(array_container.get(c), Some(v));
+    }
+    prop_assert_eq!(HashShape::from(array_container.as_shape()), hash_container.as_shape());
+
+    // Converting back and forth should preserve the contents.
+    let converted = ArrayShapeContainer::from(hash_container.clone());
+    prop_assert_eq!(&converted, &array_container);
+    prop_assert_eq!(HashShapeContainer::from(converted), hash_container.clone());
+
+    // Equal containers built in a different order should hash the same.
+    let mut pairs: Vec<(C, usize)> = hash_container.clone().into_iter().collect();
+    pairs.reverse();
+    let reversed: ArrayShapeContainer<C, usize> = pairs.into_iter().collect();
+    let hash_of = |container: &ArrayShapeContainer<C, usize>| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        container.hash(&mut hasher);
+        hasher.finish()
+    };
+    prop_assert_eq!(&reversed, &array_container);
+    prop_assert_eq!(hash_of(&reversed), hash_of(&array_container));
+
+    // Mutation of a clone should not affect the original.
+    let mut cloned = array_container.clone();
+    for c in &coords {
+        if let Some(v) = cloned.get_mut(c) {
+            *v += 1;
+        }
+        cloned.remove(c);
+        prop_assert!(!cloned.contains(c), "Removed {} should not be contained", c);
     }
+    prop_assert!(cloned.is_empty(), "Removing all coordinates should empty the container");
+    prop_assert_eq!(HashShape::from(array_container.as_shape()), hash_container.as_shape());
 
-    #[test]
-    fn square_unary_op(coord1 in squarecoord_strategy()) {
-        coord_neg(coord1)?;
-        coord_add_ident(coord1)?;
-        coord_sub_unit(coord1)?;
-        coord_mul_unit(coord1)?;
+    Ok(())
+}
 
-        // Verify that the origin is the same as the default coordinate.
-        prop_assert_eq!(dynamic::Coord::Square(square::Coord::default()),
-        dynamic::Coord::origin(dynamic::Kind::Square))
+/// Helper that verifies that a `ChunkedShapeContainer` built from the given
+/// coordinates behaves identically to the equivalent `HashShapeContainer`,
+/// and that its chunks partition the coordinates.
+fn chunked_shape_container<MC: ModuleCoord + ArrayOffsetCoord + Copy>(
+    coords: Vec<MC>,
+    offset: MC,
+    chunk_size: usize,
+) -> Result<(), TestCaseError>
+where
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    let hash_container: HashShapeContainer<MC, usize> =
+        coords.iter().cloned().enumerate().map(|(i, c)| (c, i)).collect();
+
+    // Record the chunks passing through the hooks.
+    let loaded = Arc::new(Mutex::new(Vec::new()));
+    let unloaded = Arc::new(Mutex::new(Vec::new()));
+    let (load_log, unload_log) = (loaded.clone(), unloaded.clone());
+    let mut chunked = ChunkedShapeContainer::with_chunk_size(chunk_size)
+        .on_load(move |chunk| load_log.lock().unwrap().push(chunk.key()))
+        .on_unload(move |chunk| unload_log.lock().unwrap().push((chunk.key(), chunk.len())));
+    for (i, c) in coords.iter().enumerate() {
+        chunked.insert(*c, i);
     }
-
-    #[test]
-    fn hex_mul_assoc_distrib(coord in hexcoord_strategy(), x in -100..100isize, y in -100..100isize) {
-        coord_mul_assoc(coord, x, y)?;
-        coord_mul_distributive_ring(coord, x, y)?;
+    prop_assert_eq!(chunked.len(), hash_container.len());
+    for (c, v) in hash_container.iter() {
+        prop_assert_eq!(chunked.get(c), Some(v));
     }
-
-    #[test]
-    fn hex_mul_distrib(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy(), x in -100..100isize) {
-        coord_mul_distributive_coord(coord1, coord2, x)?;
+    prop_assert_eq!(chunked.as_shape(), hash_container.as_shape());
+    prop_assert_eq!(HashShapeContainer::from(chunked.clone()), hash_container.clone());
+
+    // Each coordinate should lie in the chunk for its key, and a chunk
+    // should only have been loaded once, when it was first needed.
+    let keys: HashSet<(isize, isize)> = coords.iter().map(|c| chunked.chunk_key(c)).collect();
+    let loaded_keys: HashSet<(isize, isize)> = chunked.chunks().map(|c| c.key()).collect();
+    prop_assert_eq!(&loaded_keys, &keys);
+    prop_assert_eq!(loaded.lock().unwrap().len(), keys.len());
+    for chunk in chunked.chunks() {
+        prop_assert_eq!(chunk.coords().count(), chunk_size * chunk_size);
+        for (c, _) in chunk.iter() {
+            prop_assert_eq!(chunked.chunk_key(c), chunk.key());
+        }
     }
 
-    #[test]
-    fn square_mul_assoc_distrib(coord in squarecoord_strategy(), x in -100..100isize, y in -100..100isize) {
-        coord_mul_assoc(coord, x, y)?;
-        coord_mul_distributive_ring(coord, x, y)?;
-    }
+    // Equality should not depend upon the chunk size.
+    let resized: ChunkedShapeContainer<MC, usize> =
+        ChunkedShapeContainer::from(hash_container.clone());
+    prop_assert_eq!(&resized, &chunked);
+    let hash_of = |container: &ChunkedShapeContainer<MC, usize>| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        container.hash(&mut hasher);
+        hasher.finish()
+    };
+    prop_assert_eq!(hash_of(&resized), hash_of(&chunked));
+
+    let translated = ChunkedShapeContainer::from(hash_container.clone()).translate(&offset);
+    prop_assert_eq!(HashShapeContainer::from(translated), hash_container.translate(&offset));
+
+    // Unloading every chunk should pass all the values to the unload hook.
+    chunked.retain_chunks(|_| false);
+    prop_assert!(chunked.is_empty(), "Unloading every chunk should empty the container");
+    let unloaded = unloaded.lock().unwrap();
+    prop_assert_eq!(unloaded.len(), keys.len());
+    prop_assert_eq!(unloaded.iter().map(|(_, len)| len).sum::<usize>(), hash_container.len());
 
-    #[test]
-    fn square_mul_distrib(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy(), x in -100..100isize) {
-        coord_mul_distributive_coord(coord1, coord2, x)?;
-    }
+    Ok(())
+}
 
-    #[test]
-    fn triangle_unary_op(coord1 in trianglecoord_strategy()) {
-        // Also verify the `is_up` method for triangle coordinates.
-        let coord_point = coord1.to_ivec2().1;
-        prop_assert!(!coord1.is_up() || matches!(coord_point, TrianglePoint::Up),
-            "The is_up method does not match the TrianglePoint value.");
-                // Also verify the `is_up` method for triangle coordinates.
-        prop_assert!(!matches!(coord_point, TrianglePoint::Up) || coord1.is_up(),
-            "The is_up method does not match the TrianglePoint value.");
+/// Helper that verifies translating an `ArrayShapeContainer` agrees with
+/// translating the equivalent `HashShapeContainer`.
+fn array_shape_container_translate<MC: ModuleCoord + ArrayOffsetCoord + Copy>(
+    coords: Vec<MC>,
+    offset: MC,
+) -> Result<(), TestCaseError>
+where
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    let array_container = ArrayShapeContainer::from_iter_value(coords.iter().cloned(), ());
+    let hash_container = HashShapeContainer::from_iter_value(coords, ());
+    prop_assert_eq!(
+        HashShapeContainer::from(array_container.translate(&offset)),
+        hash_container.translate(&offset)
+    );
+    Ok(())
+}
 
-        // Verify that the origin is the same as the default coordinate.
-        prop_assert_eq!(dynamic::Coord::Triangle(triangle::Coord::default()),
-        dynamic::Coord::origin(dynamic::Kind::Triangle))
+/// Helper that verifies that the set operations on `BitShape` agree with
+/// those on the equivalent `HashShape`s.
+fn bit_shape<C: ArrayOffsetCoord + Copy>(
+    coords1: Vec<C>,
+    coords2: Vec<C>,
+) -> Result<(), TestCaseError> {
+    let hash1: HashShape<C> = coords1.iter().cloned().collect();
+    let hash2: HashShape<C> = coords2.iter().cloned().collect();
+    let bit1 = BitShape::from(&hash1);
+    let bit2 = BitShape::from(&hash2);
+
+    prop_assert_eq!(bit1.len(), hash1.iter().count());
+    prop_assert_eq!(bit1.is_empty(), hash1.is_empty());
+    for c in coords1.iter().chain(coords2.iter()) {
+        prop_assert_eq!(bit1.contains(c), hash1.contains(c), "Membership of {}", c);
     }
+    prop_assert_eq!(HashShape::from(&bit1), hash1.clone());
+
+    // The shapes will usually have different regions, so these exercise
+    // realignment, while operations on the results exercise the
+    // word-parallel case.
+    let union = bit1.union(&bit2);
+    prop_assert_eq!(HashShape::from(&union), hash1.union(&hash2));
+    prop_assert_eq!(HashShape::from(&bit1 - &bit2), &hash1 - &hash2);
+    let intersection = bit1.intersection(&bit2);
+    prop_assert_eq!(HashShape::from(&intersection), hash1.intersection(&hash2));
+    prop_assert_eq!(
+        HashShape::from(bit1.symmetric_difference(&bit2)),
+        hash1.symmetric_difference(&hash2)
+    );
+    prop_assert_eq!(HashShape::from(&bit1 | &bit2), &hash1 | &hash2);
+    prop_assert_eq!(HashShape::from(&bit1 & &bit2), &hash1 & &hash2);
+    prop_assert_eq!(HashShape::from(&bit1 ^ &bit2), &hash1 ^ &hash2);
+    prop_assert_eq!(bit1.is_subshape(&bit2), hash1.is_subshape(&hash2));
+    prop_assert_eq!(bit1.is_supershape(&bit2), hash1.is_supershape(&hash2));
+    prop_assert_eq!(bit1.is_disjoint(&bit2), hash1.is_disjoint(&hash2));
+    prop_assert!(union.is_supershape(&bit1), "The union should contain both shapes");
+    prop_assert!(intersection.is_subshape(&bit2), "The intersection should be within both shapes");
+    prop_assert_eq!(&(&union - &bit2), &(&bit1 - &bit2));
+
+    // The complement is relative to the region of the shape.
+    let complement = union.complement();
+    prop_assert!(complement.is_disjoint(&union), "The complement should be disjoint");
+    prop_assert_eq!(complement.union(&union), union.full_like());
+    prop_assert_eq!(complement.len() + union.len(), union.full_like().len());
+    prop_assert_eq!(&complement.complement(), &union);
+    prop_assert_eq!(&!&complement, &union);
+
+    // Equality and hashing should not depend upon the region.
+    let widened = bit1.union(&bit2.empty_like());
+    let hash_of = |shape: &BitShape<C>| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        shape.hash(&mut hasher);
+        hasher.finish()
+    };
+    prop_assert_eq!(&widened, &bit1);
+    prop_assert_eq!(hash_of(&widened), hash_of(&bit1));
 
-    #[test]
-    fn hex_binary_op(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy()) {
-        coord_add_comm(coord1, coord2)?;
-        coord_sub_anticomm(coord1, coord2)?;
-    }
+    Ok(())
+}
 
-    #[test]
-    fn square_binary_op(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy()) {
-        coord_add_comm(coord1, coord2)?;
-        coord_sub_anticomm(coord1, coord2)?;
-    }
+/// Helper that verifies the set algebra of a `Shape` agrees with that of
+/// `HashSet`, and that the algebraic identities relating the operations
+/// hold.
+fn shape_algebra<C: Coord + Copy, S>(coords1: Vec<C>, coords2: Vec<C>) -> Result<(), TestCaseError>
+where
+    S: Shape<C> + FromIterator<C> + std::ops::Sub<Output=S>,
+    for<'a> S: std::ops::Sub<&'a S, Output=S>,
+    for<'b> S: std::ops::Sub<&'b S, Output=S>,
+    for<'a, 'b> &'a S: std::ops::Sub<&'b S, Output=S>,
+{
+    let set1: HashSet<C> = coords1.iter().cloned().collect();
+    let set2: HashSet<C> = coords2.iter().cloned().collect();
+    let shape1: S = coords1.iter().cloned().collect();
+    let shape2: S = coords2.iter().cloned().collect();
+    let as_set = |shape: &S| shape.iter().cloned().collect::<HashSet<C>>();
+
+    prop_assert_eq!(shape1.len(), set1.len());
+    prop_assert_eq!(as_set(&shape1.union(&shape2)), &set1 | &set2);
+    prop_assert_eq!(as_set(&shape1.intersection(&shape2)), &set1 & &set2);
+    prop_assert_eq!(as_set(&shape1.difference(&shape2)), &set1 - &set2);
+    prop_assert_eq!(as_set(&shape1.symmetric_difference(&shape2)), &set1 ^ &set2);
+
+    // Identities relating the operations.
+    let union = shape1.union(&shape2);
+    let intersection = shape1.intersection(&shape2);
+    let symmetric_difference = shape1.symmetric_difference(&shape2);
+    prop_assert_eq!(&symmetric_difference, &(&union - &intersection));
+    prop_assert_eq!(union.len(), intersection.len() + symmetric_difference.len());
+    prop_assert_eq!(&shape1.intersection(&shape1), &shape1);
+    prop_assert!(
+        shape1.symmetric_difference(&shape1).is_empty(),
+        "The symmetric difference of a shape with itself should be empty"
+    );
 
-    #[test]
-    fn hex_assoc(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy(), coord3 in hexcoord_strategy()) {
-        coord_add_assoc(coord1, coord2, coord3)?;
+    // Build the second shape incrementally, and then tear it down again.
+    let mut shape = S::new();
+    let mut model = HashSet::new();
+    for c in &coords2 {
+        prop_assert_eq!(shape.insert(*c), model.insert(*c), "Inserting {}", c);
+        prop_assert!(shape.contains(c), "Inserted {} should be contained", c);
     }
-
-    #[test]
-    fn square_assoc(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy(), coord3 in squarecoord_strategy()) {
-        coord_add_assoc(coord1, coord2, coord3)?;
+    prop_assert_eq!(&shape, &shape2);
+    for c in &coords1 {
+        prop_assert_eq!(shape.remove(c), model.remove(c), "Removing {}", c);
+        prop_assert!(!shape.contains(c), "Removed {} should not be contained", c);
     }
-
-    #[test]
-    fn test_grid_color(coord in dynamic_coord_strategy()) {
-        grid_color(coord)?
+    prop_assert_eq!(as_set(&shape), &set2 - &set1);
+    for c in &coords2 {
+        shape.remove(c);
     }
+    prop_assert!(shape.is_empty(), "Removing all coordinates should empty the shape");
+    prop_assert_eq!(shape.len(), 0);
 
-    #[test]
-    fn test_grid_rotation(coord in dynamic_coord_strategy()) {
-        grid_rotation(coord)?
-    }
+    Ok(())
+}
 
-    #[test]
-    fn test_grid_reflection(coord in dynamic_coord_strategy()) {
-        grid_reflection(coord,coord.kind().axes().as_slice())?
-    }
+/// Helper that verifies the `ShapeContainer` combinators agree with the
+/// equivalent operations on a `HashMap`.
+fn shape_container_algebra<C: Coord + Copy, SC>(
+    coords1: Vec<C>,
+    coords2: Vec<C>,
+) -> Result<(), TestCaseError>
+where
+    SC: ShapeContainer<C, usize> + FromIterator<(C, usize)>,
+    SC::Shape: FromIterator<C>,
+    SC::Shape: std::ops::Sub<Output=SC::Shape>,
+    for<'a> SC::Shape: std::ops::Sub<&'a SC::Shape, Output=SC::Shape>,
+    for<'b> SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+    for<'a, 'b> &'a SC::Shape: std::ops::Sub<&'b SC::Shape, Output=SC::Shape>,
+{
+    let map1: HashMap<C, usize> = coords1.iter().cloned().zip(0..).collect();
+    let map2: HashMap<C, usize> = coords2.iter().cloned().zip(1000..).collect();
+    let container1: SC = map1.clone().into_iter().collect();
+    let container2: SC = map2.clone().into_iter().collect();
+    let as_map = |container: &SC| {
+        container
+            .iter()
+            .map(|(c, v)| (*c, *v))
+            .collect::<HashMap<C, usize>>()
+    };
+    prop_assert_eq!(container1.len(), map1.len());
+
+    // Restricting to a shape should drop exactly those coordinates outside it.
+    let shape: SC::Shape = coords2.iter().cloned().collect();
+    let restricted = container1.restrict(&shape);
+    let expected: HashMap<C, usize> = map1
+        .iter()
+        .filter(|(c, _)| map2.contains_key(c))
+        .map(|(c, v)| (*c, *v))
+        .collect();
+    prop_assert_eq!(as_map(&restricted), expected);
+    prop_assert_eq!(&container1.restrict(&container1.as_shape()), &container1);
+    prop_assert!(
+        container1.restrict(&SC::Shape::new()).is_empty(),
+        "Restricting to the empty shape should empty the container"
+    );
 
-    #[test]
-    fn test_grid_direction(coord in dynamic_coord_strategy()) {
-        for dt in [DirectionType::Face, DirectionType::Vertex] {
-            grid_direction(coord, dt)?;
-            grid_angle_to_direction(coord, dt)?;
-        }
+    // Merging should call the closure exactly once per shared coordinate.
+    let mut conflicts = 0;
+    let mut consistent = true;
+    let merged = container1.merge(&container2, |c, v1, v2| {
+        conflicts += 1;
+        consistent &= map1.get(c) == Some(v1) && map2.get(c) == Some(v2);
+        v1 + v2
+    });
+    prop_assert!(consistent, "The closure should receive the value from each container");
+    let mut expected = map1.clone();
+    for (c, v) in &map2 {
+        *expected.entry(*c).or_insert(0) += v;
     }
+    prop_assert_eq!(as_map(&merged), expected);
+    prop_assert_eq!(conflicts, map1.keys().filter(|c| map2.contains_key(c)).count());
+    prop_assert_eq!(
+        container1.merge(&container2, |_, _, v2| *v2).as_shape(),
+        container1.as_shape().union(&container2.as_shape())
+    );
 
-    #[test]
-    fn test_grid_direction_iterator(coord in small_dynamic_coord_strategy()) {
-        for dt in [DirectionType::Face, DirectionType::Vertex] {
-            grid_direction_iterator(coord, dt)?;
-        }
+    let mut removed = merged.clone();
+    let mut model = as_map(&merged);
+    for c in &coords1 {
+        prop_assert_eq!(removed.remove(c), model.remove(c), "Removing {}", c);
     }
+    prop_assert_eq!(removed.len(), map2.keys().filter(|c| !map1.contains_key(c)).count());
 
-    #[test]
-    fn test_grid_axis_iterator(coord in small_dynamic_coord_strategy()) {
-        grid_axis_iterator(coord, coord.kind().axes().as_slice())?;
-    }
+    Ok(())
+}
 
-    #[test]
-    fn hex_grid_to_array_offset(coord in hexcoord_strategy()) {
-        let array_offset = coord.grid_to_array_offset();
-        prop_assert_eq!(coord, hex::Coord::array_offset_to_grid(array_offset),
-        "With array offset {:?}", array_offset);
+/// Helper that verifies that paths found by `astar` and `dijkstra` are
+/// valid and agree in cost, over a bounded board with pseudo-randomly
+/// placed walls and step costs derived from the given seed.
+fn pathfinding(
+    kind: dynamic::Kind,
+    dir_type: DirectionType,
+    seed: u64,
+    goal_index: usize,
+) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    let start = dynamic::Coord::origin(kind);
+    // Sort the board so that the choice of goal is deterministic.
+    let mut board: Vec<dynamic::Coord> = dynamic::Coord::range(kind, 6).into_iter().collect();
+    board.sort_by_key(|coord| coord.grid_to_array_offset());
+    let goal = board[goal_index % board.len()];
+    let costs: HashShapeContainer<dynamic::Coord, u32> = board
+        .iter()
+        .filter(|coord| **coord == start || **coord == goal || hash_of(coord) % 4 != 0)
+        .map(|coord| (*coord, 1 + (hash_of(coord) % 5) as u32))
+        .collect();
 
-        let dyn_coord = dynamic::Coord::Hex(coord);
-        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+    let found = pathfinding::astar(&start, &goal, dir_type, pathfinding::entry_cost(&costs));
+    let expected = pathfinding::dijkstra(&start, &goal, dir_type, pathfinding::entry_cost(&costs));
+    prop_assert_eq!(
+        found.as_ref().map(|(_, cost)| *cost),
+        expected.as_ref().map(|(_, cost)| *cost),
+        "A* and Dijkstra should agree on the cost from {} to {}",
+        start,
+        goal
+    );
+    if let Some((path, cost)) = found {
+        prop_assert_eq!(path.first(), Some(&start));
+        prop_assert_eq!(path.last(), Some(&goal));
+        let mut total = 0;
+        for step in path.windows(2) {
+            let (from, to) = (&step[0], &step[1]);
+            prop_assert!(
+                from.allowed_directions(dir_type)
+                    .iter()
+                    .any(|dir| from.move_in_direction(dir_type, dir) == Some(*to)),
+                "{} and {} should be neighbours",
+                from,
+                to
+            );
+            total += costs.get(to).expect("Paths should remain on the board");
+        }
+        prop_assert_eq!(total, cost);
     }
 
-    #[test]
-    fn square_grid_to_array_offset(coord in squarecoord_strategy()) {
-        let array_offset = coord.grid_to_array_offset();
-        prop_assert_eq!(coord, square::Coord::array_offset_to_grid(array_offset),
-        "With array offset {:?}", array_offset);
+    // Without obstacles and with unit costs, a face path should be as long
+    // as the distance between the coordinates.
+    let (path, cost) = pathfinding::astar(&start, &goal, DirectionType::Face, |_, _| Some(1))
+        .expect("The goal should be reachable without obstacles");
+    prop_assert_eq!(cost as usize, start.distance(&goal));
+    prop_assert_eq!(path.len(), start.distance(&goal) + 1);
 
-        let dyn_coord = dynamic::Coord::Square(coord);
-        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+    // Removing the goal from the board should make it unreachable.
+    if goal != start {
+        let walled = costs.restrict(&board.iter().cloned().filter(|c| *c != goal).collect());
+        prop_assert_eq!(
+            pathfinding::astar(&start, &goal, dir_type, pathfinding::entry_cost(&walled)),
+            None
+        );
     }
 
-    #[test]
-    fn triangle_grid_to_array_offset(coord in trianglecoord_strategy()) {
-        let array_offset = coord.grid_to_array_offset();
-        prop_assert_eq!(coord, triangle::Coord::array_offset_to_grid(array_offset),
-        "With array offset {:?}", array_offset);
+    Ok(())
+}
 
-        let dyn_coord = dynamic::Coord::Triangle(coord);
-        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+/// Helper that verifies that the distance and flow fields computed towards
+/// several sources agree with one another, and with the lowest cost paths
+/// found by `dijkstra`, over a bounded board with pseudo-randomly placed
+/// walls and step costs derived from the given seed.
+fn distance_flow_fields(
+    kind: dynamic::Kind,
+    dir_type: DirectionType,
+    seed: u64,
+    source_indices: Vec<usize>,
+) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    // Sort the board so that the choice of sources is deterministic.
+    let mut board: Vec<dynamic::Coord> = dynamic::Coord::range(kind, 5).into_iter().collect();
+    board.sort_by_key(|coord| coord.grid_to_array_offset());
+    let sources: Vec<dynamic::Coord> =
+        source_indices.iter().map(|index| board[index % board.len()]).collect();
+    let costs: HashShapeContainer<dynamic::Coord, u32> = board
+        .iter()
+        .filter(|coord| sources.contains(coord) || hash_of(coord) % 4 != 0)
+        .map(|coord| (*coord, 1 + (hash_of(coord) % 5) as u32))
+        .collect();
+    let bounds = costs.as_shape();
+
+    let distances = pathfinding::distance_field(
+        sources.iter().cloned(),
+        &bounds,
+        dir_type,
+        pathfinding::entry_cost(&costs),
+    );
+    let flow = pathfinding::flow_field(
+        sources.iter().cloned(),
+        &bounds,
+        dir_type,
+        pathfinding::entry_cost(&costs),
+    );
+    prop_assert!(distances.as_shape().is_subshape(&bounds), "The field should remain in bounds");
+    prop_assert_eq!(
+        flow.as_shape(),
+        &distances.as_shape() - &sources.iter().cloned().collect::<HashShape<_>>()
+    );
+
+    for coord in &board {
+        // The cost closure only charges for entering a coordinate, so ensure
+        // the search does not begin from within a wall.
+        let nearest = sources
+            .iter()
+            .filter(|_| bounds.contains(coord))
+            .filter_map(|source| {
+                pathfinding::dijkstra(coord, source, dir_type, pathfinding::entry_cost(&costs))
+            })
+            .map(|(_, cost)| cost)
+            .min();
+        prop_assert_eq!(distances.get(coord).copied(), nearest, "Distance from {}", coord);
+
+        // Following the flow should descend the distance field to a source.
+        if let Some(dir) = flow.get(coord) {
+            let next = coord
+                .move_in_direction(dir_type, *dir)
+                .expect("Flow directions should be allowed");
+            let remaining = distances.get(&next).expect("Flow should remain in the field");
+            prop_assert_eq!(
+                distances.get(coord).copied(),
+                Some(remaining + costs.get(&next).unwrap()),
+                "Flowing {} from {}",
+                dir,
+                coord
+            );
+        }
     }
 
-    #[test]
-    fn test_path(coord1 in small_dynamic_coord_strategy(), coord2 in small_dynamic_coord_strategy()) {
-        prop_assume!(coord1.kind() == coord2.kind(), "Coordinates should be of the same kind.");
-        grid_path(coord1, coord2)?;
+    Ok(())
+}
+
+/// Helper that verifies that line of sight is symmetric, and that the field
+/// of view agrees with it, over a board with pseudo-randomly placed walls
+/// derived from the given seed.  The field of view is also checked to be
+/// symmetric, and to never reveal more once further walls are added.
+fn line_of_sight(kind: dynamic::Kind, seed: u64, from_index: usize) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    // Sort the board so that the choice of viewpoint is deterministic.
+    let mut board: Vec<dynamic::Coord> = dynamic::Coord::range(kind, 5).into_iter().collect();
+    board.sort_by_key(|coord| coord.grid_to_array_offset());
+    let from = board[from_index % board.len()];
+    let walls: HashShape<dynamic::Coord> =
+        board.iter().cloned().filter(|coord| hash_of(coord) % 4 == 0).collect();
+    let blocks = |coord: &dynamic::Coord| walls.contains(coord);
+
+    for to in &board {
+        prop_assert_eq!(
+            visibility::has_line_of_sight(&from, to, blocks),
+            visibility::has_line_of_sight(to, &from, blocks),
+            "Line of sight between {} and {} should be symmetric",
+            from,
+            to
+        );
     }
 
-    #[test]
-    fn test_sized_grid_commutation(size in &SIZE_RANGE,
-        coord in dynamic_coord_strategy()) {
-        let sized_grid = dynamic::SizedGrid::new(coord.kind(), size);
-        prop_assert_eq!(sized_grid.kind(), coord.kind(),
-            "Sized grid kind should match coordinate kind.");
-        let fudge = match coord {
-            dynamic::Coord::Hex(_) => sized_grid.edge_length(),
-            _ => 0.0,
-        };
-        for dt in [DirectionType::Face, DirectionType::Vertex] {
-            sized_grid_commutation(sized_grid, coord, dt, fudge)?;
+    let radius = 3;
+    // Every metric is within a factor of three of the face distance, so
+    // this is certain to contain every coordinate within the radius.
+    let bounds = dynamic::Coord::hexagon(kind, 3 * radius + 8);
+    let more_walls: HashShape<dynamic::Coord> =
+        board.iter().cloned().filter(|coord| hash_of(coord) % 7 == 1).collect();
+    for metric in Metric::ALL {
+        let within: Vec<&dynamic::Coord> = bounds
+            .iter()
+            .filter(|to| metric.distance(&from, *to) <= radius as f32)
+            .collect();
+        let visible = visibility::field_of_view(&from, metric, radius, blocks);
+        prop_assert!(visible.contains(&from), "The origin should always be visible");
+        for to in &within {
+            // Shadowcasting and walking sight lines should agree.
+            prop_assert_eq!(
+                visible.contains(to),
+                visibility::has_line_of_sight(&from, to, blocks),
+                "{} should only be in view of {} when in sight",
+                to,
+                from
+            );
+            prop_assert_eq!(
+                visible.contains(to),
+                visibility::field_of_view(*to, metric, radius, blocks).contains(&from),
+                "Field of view between {} and {} should be symmetric",
+                from,
+                to
+            );
         }
-        sized_grid_radius(sized_grid)?;
-        sized_grid_identity(sized_grid, coord)?;
+        prop_assert!(
+            visible.iter().all(|to| within.contains(&to)),
+            "Field of view should lie within the radius"
+        );
+        let fewer = visibility::field_of_view(&from, metric, radius, |coord| {
+            walls.contains(coord) || more_walls.contains(coord)
+        });
+        prop_assert!(fewer.is_subshape(&visible), "Adding walls should not reveal anything");
     }
 
-    #[test]
-    fn sized_vertices(size in &SIZE_RANGE,
-        coord in dynamic_coord_strategy()) {
+    // Without walls everything within the radius should be visible.
+    let origin = dynamic::Coord::origin(kind);
+    for metric in Metric::ALL {
+        prop_assert_eq!(
+            visibility::field_of_view(&origin, metric, radius, |_| false),
+            dynamic::Coord::metric_range(kind, metric, radius)
+        );
+    }
+    // Whereas surrounding the origin should hide everything beyond the walls.
+    let surrounding = dynamic::Coord::metric_ring(kind, Metric::Face, 1);
+    prop_assert_eq!(
+        visibility::field_of_view(&origin, Metric::Face, radius, |coord| {
+            surrounding.contains(coord)
+        }),
+        surrounding.union(&HashShape::from([origin]))
+    );
+
+    Ok(())
+}
+
+static SIZE_RANGE: std::ops::Range<f32> = 0.001..65535.0f32;
+
+fn connectivity(kind: dynamic::Kind, dir_type: DirectionType, seed: u64) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    let board = dynamic::Coord::range(kind, 4);
+    let neighbours = |coord: &dynamic::Coord| -> Vec<dynamic::Coord> {
+        coord
+            .allowed_directions(dir_type)
+            .iter()
+            .filter_map(|dir| coord.move_in_direction(dir_type, dir))
+            .collect()
+    };
+
+    let shape: HashShape<dynamic::Coord> =
+        board.iter().cloned().filter(|coord| hash_of(coord) % 3 != 0).collect();
+    let components = connectivity::connected_components(&shape, dir_type);
+    prop_assert_eq!(
+        components.iter().map(|component| component.len()).sum::<usize>(),
+        shape.len(),
+        "The components should be disjoint."
+    );
+    prop_assert_eq!(
+        components.iter().fold(HashShape::new(), |acc, component| acc.union(component)),
+        shape.clone(),
+        "The union of the components should be the shape."
+    );
+    prop_assert_eq!(connectivity::is_connected(&shape, dir_type), components.len() <= 1);
+    for component in &components {
+        prop_assert!(!component.is_empty(), "Components should not be empty.");
+        prop_assert!(connectivity::is_connected(component, dir_type));
+        // No coordinate outside a component should neighbour it within the shape.
+        for coord in component.iter() {
+            for next in neighbours(coord) {
+                prop_assert!(!shape.contains(&next) || component.contains(&next));
+            }
+            prop_assert_eq!(
+                &connectivity::flood_fill(coord, dir_type, |c| shape.contains(c)),
+                component
+            );
+        }
+    }
+    prop_assert!(connectivity::is_connected(&HashShape::<dynamic::Coord>::new(), dir_type));
+    let origin = dynamic::Coord::origin(kind);
+    prop_assert!(connectivity::flood_fill(&origin, dir_type, |_| false).is_empty());
+
+    let container: HashShapeContainer<dynamic::Coord, u64> =
+        board.iter().map(|coord| (*coord, hash_of(coord) % 3)).collect();
+    let regions = connectivity::value_components(&container, dir_type);
+    prop_assert_eq!(
+        regions.iter().map(|(_, region)| region.len()).sum::<usize>(),
+        container.len(),
+        "The regions should be disjoint."
+    );
+    for (value, region) in &regions {
+        prop_assert!(connectivity::is_connected(region, dir_type));
+        for coord in region.iter() {
+            prop_assert_eq!(container.get(coord), Some(value));
+            // Neighbours holding the same value should be in the same region.
+            for next in neighbours(coord) {
+                prop_assert!(container.get(&next) != Some(value) || region.contains(&next));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn outlines(kind: dynamic::Kind, size: f32, seed: u64) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    let grid = dynamic::SizedGrid::new(kind, size);
+    let shape: HashShape<dynamic::Coord> = dynamic::Coord::range(kind, 4)
+        .into_iter()
+        .filter(|coord| hash_of(coord) % 3 != 0)
+        .collect();
+
+    let edges = outline::boundary_edges(&shape);
+    for coord in shape.iter() {
+        for dir in coord.allowed_directions(DirectionType::Face).iter() {
+            let next = coord.move_in_direction(DirectionType::Face, dir).unwrap();
+            prop_assert_eq!(edges.contains(&(*coord, dir)), !shape.contains(&next));
+        }
+    }
+    for dir_type in [DirectionType::Face, DirectionType::Vertex] {
+        let inner = outline::inner_boundary(&shape, dir_type);
+        let outer = outline::outer_boundary(&shape, dir_type);
+        prop_assert!(inner.is_subshape(&shape));
+        prop_assert!(outer.is_disjoint(&shape));
+        let complement: HashShape<dynamic::Coord> = dynamic::Coord::range(kind, 6)
+            .into_iter()
+            .filter(|coord| !shape.contains(coord))
+            .collect();
+        // Each boundary is the boundary of the other side.
+        prop_assert_eq!(
+            &outline::outer_boundary(&complement, dir_type)
+                .intersection(&dynamic::Coord::range(kind, 4)),
+            &inner
+        );
+        prop_assert_eq!(
+            &outline::inner_boundary(&complement, dir_type).intersection(&outer),
+            &outer
+        );
+    }
+
+    let outlines = outline::outlines(&grid, &shape);
+    prop_assert_eq!(
+        outlines.len(),
+        connectivity::connected_components(&shape, DirectionType::Face).len(),
+        "There should be an outline for each face connected region."
+    );
+    let area = |polygon: &[Vec2]| -> f32 {
+        polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| a.perp_dot(*b))
+            .sum::<f32>()
+            / 2.0
+    };
+    let cell_area = area(&grid.vertices(&dynamic::Coord::origin(kind)));
+    let mut points = 0;
+    let mut total = 0.0;
+    for outline in &outlines {
+        prop_assert!(area(&outline.exterior) * cell_area > 0.0, "Exteriors should wind as cells.");
+        total += area(&outline.exterior);
+        points += outline.exterior.len();
+        for hole in &outline.holes {
+            prop_assert!(area(hole) * cell_area < 0.0, "Holes should wind opposite to cells.");
+            total += area(hole);
+            points += hole.len();
+        }
+    }
+    prop_assert_eq!(points, edges.len(), "Each boundary edge should be traced once.");
+    let expected = cell_area * shape.len() as f32;
+    prop_assert!(
+        (total - expected).abs() <= expected.abs() * 1e-3,
+        "The outlines should enclose the area of the shape, {} vs {}",
+        total,
+        expected
+    );
+
+    Ok(())
+}
+
+fn shape_constructors(
+    kind: dynamic::Kind,
+    first_len: usize,
+    second_len: usize,
+) -> Result<(), TestCaseError> {
+    let origin = dynamic::Coord::origin(kind);
+    let triangular = kind == dynamic::Kind::Triangle;
+    let check = |shape: &HashShape<dynamic::Coord>, len: usize, connected: bool| {
+        prop_assert_eq!(shape.len(), len);
+        prop_assert!(!connected || connectivity::is_connected(shape, DirectionType::Face));
+        prop_assert_eq!(shape.contains(&origin), len > 0, "Shapes should include the origin.");
+        let container = HashShapeContainer::from_shape_value(shape.clone(), 7);
+        prop_assert_eq!(container.as_shape(), shape.clone());
+        prop_assert!(container.iter().all(|(_, v)| *v == 7));
+        Ok::<(), TestCaseError>(())
+    };
+
+    let rectangle = dynamic::Coord::rectangle(kind, first_len, second_len);
+    // Rows of triangles only meet where they are at least two triangles wide.
+    check(&rectangle, first_len * second_len, !triangular || first_len >= 2 || second_len <= 1)?;
+    // In screen space, the rectangle should consist of exactly those
+    // coordinates whose centers lie within the bounds of its centers.
+    if !rectangle.is_empty() {
+        let grid = dynamic::SizedGrid::new(kind, 1.0);
+        let centers: Vec<Vec2> = rectangle.iter().map(|c| grid.grid_to_screen(c)).collect();
+        let min = centers.iter().fold(Vec2::INFINITY, |acc, c| acc.min(*c)) - 1e-3;
+        let max = centers.iter().fold(Vec2::NEG_INFINITY, |acc, c| acc.max(*c)) + 1e-3;
+        for coord in dynamic::Coord::range(kind, 2 * (first_len + second_len) + 2).iter() {
+            let center = grid.grid_to_screen(coord);
+            prop_assert_eq!(
+                rectangle.contains(coord),
+                center.cmpge(min).all() && center.cmple(max).all(),
+                "Coordinate {} does not match the bounds of the rectangle.",
+                coord
+            );
+        }
+    }
+
+    for first in kind.axes() {
+        for second in kind.axes() {
+            if first != second {
+                let parallelogram =
+                    dynamic::Coord::parallelogram(first, first_len, second, second_len);
+                let scale = if triangular { 2 } else { 1 };
+                check(&parallelogram, scale * first_len * second_len, true)?;
+            }
+        }
+    }
+
+    let size = first_len;
+    let triangle = dynamic::Coord::triangle(kind, size);
+    check(&triangle, if triangular { size * size } else { size * (size + 1) / 2 }, true)?;
+
+    let radius = second_len;
+    let hexagon = dynamic::Coord::hexagon(kind, radius);
+    let expected = if triangular { 6 * radius * radius } else { 3 * radius * (radius + 1) + 1 };
+    check(&hexagon, expected, true)?;
+    if kind == dynamic::Kind::Hex {
+        prop_assert_eq!(hexagon, dynamic::Coord::range(kind, radius));
+    }
+
+    Ok(())
+}
+
+fn metric_distances(coord1: dynamic::Coord, coord2: dynamic::Coord) -> Result<(), TestCaseError> {
+    let face = coord1.distance(&coord2);
+    let vertex = coord1.vertex_distance(&coord2);
+    let euclidean = coord1.euclidean_distance(&coord2);
+    prop_assert_eq!(vertex, coord2.vertex_distance(&coord1));
+    prop_assert_eq!(euclidean, coord2.euclidean_distance(&coord1));
+    prop_assert!(vertex <= face, "Vertex steps should never lengthen a path.");
+    prop_assert!(euclidean <= face as f32 + 1e-3, "No path should be shorter than a line.");
+    prop_assert_eq!(Metric::Face.distance(&coord1, &coord2), face as f32);
+    prop_assert_eq!(Metric::Vertex.distance(&coord1, &coord2), vertex as f32);
+    prop_assert_eq!(Metric::Euclidean.distance(&coord1, &coord2), euclidean);
+
+    // The vertex distance should match a breadth first search using both
+    // face and vertex steps.
+    let mut reached = HashSet::from([coord1]);
+    let mut frontier = vec![coord1];
+    let mut steps = 0;
+    while !reached.contains(&coord2) {
+        let mut next_frontier = Vec::new();
+        for coord in frontier {
+            for dt in [DirectionType::Face, DirectionType::Vertex] {
+                for dir in coord.allowed_directions(dt).iter() {
+                    if let Some(next) = coord.move_in_direction(dt, dir)
+                        && reached.insert(next)
+                    {
+                        next_frontier.push(next);
+                    }
+                }
+            }
+        }
+        frontier = next_frontier;
+        steps += 1;
+    }
+    prop_assert_eq!(vertex, steps, "Expected {} steps from {} to {}.", steps, coord1, coord2);
+
+    // The Euclidean distance should match that between the centers on a
+    // sized grid, relative to the spacing of face neighbours.
+    let grid = dynamic::SizedGrid::new(coord1.kind(), 1.0);
+    let dir = coord1.allowed_directions(DirectionType::Face).iter().next().unwrap();
+    let neighbour = coord1.move_in_direction(DirectionType::Face, dir).unwrap();
+    let spacing = grid.grid_to_screen(&coord1).distance(grid.grid_to_screen(&neighbour));
+    let expected = grid.grid_to_screen(&coord1).distance(grid.grid_to_screen(&coord2)) / spacing;
+    prop_assert!(
+        (euclidean - expected).abs() <= 1e-3 * expected.max(1.0),
+        "Expected a distance of {} from {} to {}, got {}.",
+        expected,
+        coord1,
+        coord2,
+        euclidean
+    );
+
+    Ok(())
+}
+
+fn metric_shapes(kind: dynamic::Kind, radius: usize) -> Result<(), TestCaseError> {
+    let origin = dynamic::Coord::origin(kind);
+    // Every metric is within a factor of three of the face distance, so
+    // this is certain to contain every shape of the given radius.
+    let bounds = dynamic::Coord::hexagon(kind, 3 * radius + 3);
+    for metric in Metric::ALL {
+        let range = dynamic::Coord::metric_range(kind, metric, radius);
+        let expected: HashShape<dynamic::Coord> = bounds
+            .iter()
+            .filter(|coord| metric.distance(&origin, *coord) <= radius as f32)
+            .cloned()
+            .collect();
+        prop_assert_eq!(&range, &expected, "Unexpected {} range of radius {}.", metric, radius);
+
+        let ring = dynamic::Coord::metric_ring(kind, metric, radius);
+        let inner = match radius {
+            0 => HashShape::new(),
+            _ => dynamic::Coord::metric_range(kind, metric, radius - 1),
+        };
+        prop_assert_eq!(&ring, &(&range - &inner), "Rings should partition the range.");
+        if metric != Metric::Euclidean {
+            prop_assert!(ring.iter().all(|c| metric.distance(&origin, c) == radius as f32));
+        }
+    }
+
+    // Some metrics coincide with the existing shapes.
+    let (same_metric, same_ring) = match kind {
+        dynamic::Kind::Square => (Some(Metric::Vertex), true),
+        dynamic::Kind::Hex => (Some(Metric::Face), true),
+        dynamic::Kind::Triangle => (None, false),
+    };
+    if let Some(metric) = same_metric {
+        prop_assert_eq!(
+            dynamic::Coord::metric_range(kind, metric, radius),
+            dynamic::Coord::range(kind, radius)
+        );
+        if same_ring {
+            prop_assert_eq!(
+                dynamic::Coord::metric_ring(kind, metric, radius),
+                dynamic::Coord::ring(kind, radius)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn hex_offsets(coord: hex::Coord) -> Result<(), TestCaseError> {
+    use hex::{DoubledScheme, OffsetScheme};
+    let grid = hex::SizedGrid::new(1.0);
+    let north = coord.move_in_direction(DirectionType::Face, Direction::North).unwrap();
+    // Measure the spacing at the origin, as far from it the rounding error
+    // of the neighbouring screen positions is magnified by the row.
+    let origin_north =
+        hex::Coord::default().move_in_direction(DirectionType::Face, Direction::North).unwrap();
+    let spacing = grid.grid_to_screen(&origin_north).length();
+    let arbitrary = coord.to_ivec2();
+    let schemes = [OffsetScheme::OddR, OffsetScheme::EvenR, OffsetScheme::OddQ, OffsetScheme::EvenQ];
+    for scheme in schemes {
+        let offset = coord.to_offset(scheme);
+        let round_trip = hex::Coord::from_offset(scheme, offset);
+        prop_assert_eq!(round_trip, coord, "Round trip for {}.", scheme);
+        prop_assert_eq!(hex::Coord::from_offset(scheme, arbitrary).to_offset(scheme), arbitrary);
+        let (col, row) = (offset.x, offset.y);
+        let odd = |n: i32| n.rem_euclid(2) == 1;
+        match scheme {
+            OffsetScheme::OddR | OffsetScheme::EvenR => {
+                // In doubled width coordinates, the shifted rows lie half
+                // way between the columns.
+                let shift = match (scheme, odd(row)) {
+                    (_, false) => 0,
+                    (OffsetScheme::OddR, true) => 1,
+                    _ => -1,
+                };
+                let doubled = coord.to_doubled(DoubledScheme::Width);
+                prop_assert_eq!(doubled, IVec2::new(2 * col + shift, row));
+            }
+            OffsetScheme::OddQ | OffsetScheme::EvenQ => {
+                // Columns are vertical on the flat-topped grid.
+                let shift = match (scheme, odd(col)) {
+                    (_, false) => 0.0,
+                    (OffsetScheme::OddQ, true) => 0.5,
+                    _ => -0.5,
+                };
+                let center = grid.grid_to_screen(&coord);
+                let origin = grid.grid_to_screen(&hex::Coord::from_offset(scheme, IVec2::ZERO));
+                prop_assert!(((center.y - origin.y) / spacing - (row as f32 + shift)).abs() < 1e-2);
+                prop_assert_eq!(hex::Coord::from_offset(scheme, offset + IVec2::Y), north);
+            }
+        }
+    }
+    let array_offset = coord.grid_to_array_offset();
+    let even_q = coord.to_offset(OffsetScheme::EvenQ);
+    prop_assert_eq!(array_offset, (even_q.x as isize, even_q.y as isize));
+
+    for scheme in [DoubledScheme::Width, DoubledScheme::Height] {
+        let doubled = coord.to_doubled(scheme);
+        prop_assert_eq!(doubled.element_sum().rem_euclid(2), 0);
+        let round_trip = hex::Coord::from_doubled(scheme, doubled);
+        prop_assert_eq!(round_trip, coord, "Round trip for {}.", scheme);
+        for dir in coord.allowed_directions(DirectionType::Face).iter() {
+            let next = coord.move_in_direction(DirectionType::Face, dir).unwrap();
+            let step = next.to_doubled(scheme) - doubled;
+            let straight = match scheme {
+                DoubledScheme::Width => IVec2::new(2, 0),
+                DoubledScheme::Height => IVec2::new(0, 2),
+            };
+            prop_assert!(
+                step.abs() == IVec2::ONE || step.abs() == straight,
+                "Unexpected {} step {} from {} to {}.",
+                scheme,
+                step,
+                coord,
+                next
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn triangle_offsets(coord: triangle::Coord) -> Result<(), TestCaseError> {
+    use triangle::OffsetScheme;
+    let grid = triangle::SizedGrid::new(1.0);
+    let arbitrary = coord.to_ivec2().0;
+    for (scheme, parity) in [(OffsetScheme::Even, 0), (OffsetScheme::Odd, 1)] {
+        let offset = coord.to_offset(scheme);
+        let round_trip = triangle::Coord::from_offset(scheme, offset);
+        prop_assert_eq!(round_trip, coord, "Round trip for {}.", scheme);
+        let reverse = triangle::Coord::from_offset(scheme, arbitrary).to_offset(scheme);
+        prop_assert_eq!(reverse, arbitrary);
+        prop_assert_eq!(coord.is_up(), offset.element_sum().rem_euclid(2) == parity);
+        prop_assert_eq!(triangle::Coord::default().to_offset(scheme), IVec2::new(parity, 0));
+
+        // Triangles sharing a column should be stacked vertically, with
+        // columns evenly spaced.
+        let center =
+            |offset: IVec2| grid.grid_to_screen(&triangle::Coord::from_offset(scheme, offset));
+        let width = center(IVec2::X).x - center(IVec2::ZERO).x;
+        prop_assert!(width > 0.0);
+        let column = center(offset).x - center(IVec2::ZERO).x;
+        prop_assert!((column - offset.x as f32 * width).abs() < 1e-2);
+        prop_assert!(center(offset + IVec2::Y).y > center(offset).y);
+    }
+
+    Ok(())
+}
+
+fn sized_grid_orientation<SG: SizedGrid + Copy>(
+    sized_grid: SG,
+    coord: SG::Coord,
+) -> Result<(), TestCaseError>
+where
+    SG::Coord: Copy,
+{
+    let center = sized_grid.grid_to_screen(&coord);
+    for dt in [DirectionType::Face, DirectionType::Vertex] {
+        for dir in &coord.allowed_directions(dt) {
+            let angle = sized_grid.direction_angle(&coord, dt, dir).unwrap();
+            prop_assert_eq!(sized_grid.angle_to_direction(&coord, dt, angle), dir);
+            // The neighbour should lie in the direction of the angle.
+            let next = coord.move_in_direction(dt, dir).unwrap();
+            let offset = (sized_grid.grid_to_screen(&next) - center).normalize();
+            prop_assert!(
+                offset.dot(Vec2::from_angle(angle)) > 0.999,
+                "Direction {} from {} at angle {} is not towards {}.",
+                dir,
+                coord,
+                angle,
+                next
+            );
+        }
+    }
+    // Each edge should be shared with the neighbour in its direction.
+    // Allow for the limited precision of points far from the origin.
+    let tolerance = 1e-3 * sized_grid.edge_length() + 1e-5 * center.length();
+    for (dir, (a, b)) in sized_grid.edges(&coord) {
+        let next = coord.move_in_direction(DirectionType::Face, dir).unwrap();
+        let midpoint = sized_grid.grid_to_screen(&next).midpoint(center);
+        prop_assert!(
+            a.midpoint(b).distance(midpoint) < tolerance,
+            "Edge {} of {} is not shared with {}.",
+            dir,
+            coord,
+            next
+        );
+    }
+    Ok(())
+}
+
+fn sized_grid_rect(
+    grid: dynamic::SizedGrid,
+    min: Vec2,
+    size: Vec2,
+) -> Result<(), TestCaseError> {
+    let max = min + size;
+    prop_assert!(grid.screen_rect_to_grid(max, min - 1.0).is_none());
+    let found: Vec<dynamic::Coord> = grid.screen_rect_to_grid(min, max).unwrap().collect();
+    let found_set: HashSet<dynamic::Coord> = found.iter().cloned().collect();
+    prop_assert_eq!(found.len(), found_set.len(), "There should be no duplicates.");
+    let expected: HashSet<dynamic::Coord> = dynamic::Coord::range(grid.kind(), 40)
+        .into_iter()
+        .filter(|coord| grid.coord_intersects_rect(coord, min, max))
+        .collect();
+    prop_assert_eq!(found_set, expected);
+    Ok(())
+}
+
+fn wrapping<C: WrappingCoord>(
+    topology: Topology<C>,
+    coord1: C,
+    coord2: C,
+) -> Result<(), TestCaseError> {
+    let start = topology.normalize(&coord1);
+    let end = topology.normalize(&coord2);
+    prop_assert!(topology.is_normalized(&start));
+    prop_assert_eq!(topology.normalize(&start), start, "Normalization should be idempotent.");
+    let (x, y) = start.grid_to_array_offset();
+    prop_assert!(topology.columns().is_none_or(|c| (0..c as isize).contains(&x)));
+    prop_assert!(topology.rows().is_none_or(|r| (0..r as isize).contains(&y)));
+    if let Some(domain) = topology.domain() {
+        prop_assert_eq!(topology.wrap(), Wrap::Both);
+        prop_assert_eq!(domain.len(), topology.columns().unwrap() * topology.rows().unwrap());
+        prop_assert!(domain.contains(&start));
+    }
+
+    let neighbours = |coord: &C| {
+        coord
+            .allowed_directions(DirectionType::Face)
+            .iter()
+            .filter_map(|dir| topology.move_in_direction(coord, DirectionType::Face, dir))
+            .collect::<Vec<_>>()
+    };
+    for dir in coord1.allowed_directions(DirectionType::Face).iter() {
+        let next = topology.move_in_direction(&coord1, DirectionType::Face, dir).unwrap();
+        prop_assert!(topology.is_normalized(&next));
+        prop_assert_eq!(
+            topology.move_in_direction(&next, DirectionType::Face, dir.opposite()),
+            Some(start)
+        );
+        let steps: Vec<C> =
+            topology.direction_iterator(&coord1, DirectionType::Face, dir, ..=12).collect();
+        prop_assert_eq!(steps.len(), 13);
+        prop_assert_eq!(steps[0], start);
+        prop_assert_eq!(steps[1], next);
+        prop_assert!(steps.iter().all(|coord| topology.is_normalized(coord)));
+    }
+
+    // The wrapped distance should match a breadth first search stepping
+    // between wrapped neighbours.
+    let distance = topology.distance(&coord1, &coord2);
+    prop_assert_eq!(distance, topology.distance(&coord2, &coord1));
+    prop_assert!(distance <= coord1.distance(&coord2));
+    let mut reached = HashSet::from([start]);
+    let mut frontier = vec![start];
+    let mut steps = 0;
+    while !reached.contains(&end) {
+        frontier = frontier
+            .iter()
+            .flat_map(neighbours)
+            .filter(|next| reached.insert(*next))
+            .collect();
+        steps += 1;
+    }
+    prop_assert_eq!(distance, steps, "Expected {} steps from {} to {}.", steps, start, end);
+
+    let path: Vec<C> = topology.path_iterator(&coord1, &coord2).collect();
+    prop_assert_eq!(path.len(), distance + 1);
+    prop_assert_eq!(path.first(), Some(&start));
+    prop_assert_eq!(path.last(), Some(&end));
+    for (prev, next) in path.iter().zip(path.iter().skip(1)) {
+        let adjacent = neighbours(prev).contains(next);
+        prop_assert!(adjacent, "{} and {} should be neighbours.", prev, next);
+    }
+
+    Ok(())
+}
+
+fn bounded_board(
+    kind: dynamic::Kind,
+    dir_type: DirectionType,
+    seed: u64,
+) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &dynamic::Coord| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    let shape: HashShape<dynamic::Coord> = dynamic::Coord::range(kind, 4)
+        .into_iter()
+        .filter(|coord| hash_of(coord) % 4 != 0)
+        .collect();
+    let board = Board::new(shape.clone());
+    prop_assert_eq!(board.shape(), &shape);
+    prop_assert_eq!(board.iter().count(), shape.len());
+
+    // Include coordinates just off the board.
+    for coord in dynamic::Coord::range(kind, 6).iter() {
+        prop_assert_eq!(board.contains(coord), shape.contains(coord));
+        let allowed = board.allowed_directions(coord, dir_type);
+        let mut neighbours = Vec::new();
+        for dir in coord.allowed_directions(dir_type).iter() {
+            let next = coord.move_in_direction(dir_type, dir).unwrap();
+            let on_board = shape.contains(coord) && shape.contains(&next);
+            let moved = board.move_in_direction(coord, dir_type, dir);
+            prop_assert_eq!(moved, on_board.then_some(next));
+            prop_assert_eq!(allowed.contains(dir), on_board);
+            prop_assert_eq!(board.allowed_direction(coord, dir_type, dir), on_board);
+            if on_board {
+                neighbours.push(next);
+            }
+
+            // Iteration should stop at the edge of the board.
+            let unbounded: Vec<_> = coord.direction_iterator(dir_type, dir, ..20).collect();
+            let bounded: Vec<_> = board.direction_iterator(coord, dir_type, dir, ..20).collect();
+            let expected = unbounded.iter().take_while(|c| shape.contains(c)).count();
+            prop_assert_eq!(&bounded[..], &unbounded[..expected]);
+        }
+        prop_assert!(allowed.is_subset(coord.allowed_directions(dir_type)));
+        prop_assert_eq!(board.neighbours(coord, dir_type).collect::<Vec<_>>(), neighbours);
+
+        for axis in kind.axes() {
+            for positive in [false, true] {
+                let next = coord.move_on_axis(axis, positive);
+                let on_board = shape.contains(coord) && shape.contains(&next);
+                let moved = board.move_on_axis(coord, axis, positive);
+                prop_assert_eq!(moved, on_board.then_some(next));
+                let unbounded: Vec<_> = coord.axis_iterator(axis, positive, ..20).collect();
+                let bounded: Vec<_> = board.axis_iterator(coord, axis, positive, ..20).collect();
+                let expected = unbounded.iter().take_while(|c| shape.contains(c)).count();
+                prop_assert_eq!(&bounded[..], &unbounded[..expected]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn symmetries<C: SymmetricCoord + ArrayOffsetCoord>(
+    coord: C,
+    other: C,
+    axes: &[C::Axes],
+) -> Result<(), TestCaseError> {
+    let all = Transform::<C>::all();
+    prop_assert_eq!(all.len(), 2 * C::ROTATIONS);
+    prop_assert_eq!(all.iter().collect::<HashSet<_>>().len(), all.len());
+    prop_assert_eq!(Transform::<C>::rotations().len(), C::ROTATIONS);
+    prop_assert!(Transform::<C>::rotations().iter().all(|t| !t.is_reflection()));
+    prop_assert_eq!(Transform::<C>::rotation(1).apply(&coord), coord.rotate_clockwise());
+    prop_assert_eq!(Transform::<C>::rotation(-1).apply(&coord), coord.rotate_counterclockwise());
+    prop_assert_eq!(Transform::<C>::rotation(C::ROTATIONS as isize).apply(&coord), coord);
+    for axis in axes {
+        prop_assert_eq!(Transform::reflection(*axis).apply(&coord), coord.reflect(*axis));
+    }
+
+    let shape: HashShape<C> =
+        [coord, other, coord.rotate_clockwise(), other.reflect(axes[0])].into();
+    let container: ArrayShapeContainer<C, usize> =
+        shape.iter().enumerate().map(|(index, coord)| (*coord, index)).collect();
+    for t1 in all.iter() {
+        prop_assert_eq!(t1.inverse().apply(&t1.apply(&coord)), coord);
+        prop_assert_eq!(t1.compose(&t1.inverse()), Transform::identity());
+        prop_assert_eq!(t1.compose(&Transform::identity()), *t1);
+        for t2 in all.iter() {
+            let composed = t1.compose(t2);
+            prop_assert!(all.contains(&composed));
+            prop_assert_eq!(composed.apply(&coord), t1.apply(&t2.apply(&coord)));
+            prop_assert_eq!(composed.is_reflection(), t1.is_reflection() != t2.is_reflection());
+        }
+
+        // Transforms preserve distances and neighbours.
+        prop_assert_eq!(t1.apply(&coord).distance(&t1.apply(&other)), coord.distance(&other));
+        for dir_type in [DirectionType::Face, DirectionType::Vertex] {
+            for dir in Direction::VALUES.iter() {
+                let moved = t1.apply_direction(&coord, dir_type, dir);
+                prop_assert_eq!(moved.is_some(), coord.allowed_direction(dir_type, dir));
+                if let Some(moved) = moved {
+                    prop_assert_eq!(
+                        t1.apply(&coord).move_in_direction(dir_type, moved),
+                        coord.move_in_direction(dir_type, dir).map(|next| t1.apply(&next))
+                    );
+                }
+            }
+        }
+
+        let transformed = t1.apply_shape(&shape);
+        prop_assert_eq!(transformed.len(), shape.len());
+        prop_assert!(shape.iter().all(|c| transformed.contains(&t1.apply(c))));
+        let transformed = t1.apply_container(&container);
+        prop_assert_eq!(transformed.len(), container.len());
+        for (c, value) in container.iter() {
+            prop_assert_eq!(transformed.get(&t1.apply(c)), Some(value));
+        }
+    }
+
+    Ok(())
+}
+
+fn isometries<MC: SymmetricCoord + ModuleCoord>(
+    coord: MC,
+    pivot: MC,
+    offset: MC,
+) -> Result<(), TestCaseError>
+where
+    for<'a, 'b> &'a MC: std::ops::Add<&'b MC, Output=MC>,
+    for<'a, 'b> &'a MC: std::ops::Sub<&'b MC, Output=MC>,
+{
+    let all = Transform::<MC>::all();
+    prop_assert_eq!(Isometry::translation(offset).apply(&coord), coord + offset);
+    for transform in all.iter() {
+        let isometry = Isometry::new(*transform, offset);
+        prop_assert_eq!(isometry.apply(&coord), transform.apply(&coord) + offset);
+        prop_assert_eq!(isometry.inverse().apply(&isometry.apply(&coord)), coord);
+        prop_assert_eq!(Isometry::from(*transform).apply(&coord), transform.apply(&coord));
+
+        // Rotating or reflecting about a pivot is the same as doing so
+        // about the origin, relative to the pivot.
+        let about = Isometry::about(*transform, &pivot);
+        prop_assert_eq!(about.apply(&pivot), pivot);
+        prop_assert_eq!(about.apply(&coord), transform.apply(&(coord - pivot)) + pivot);
+        prop_assert_eq!(about.apply(&coord).distance(&pivot), coord.distance(&pivot));
+
+        for other in all.iter() {
+            let other = Isometry::about(*other, &offset);
+            prop_assert_eq!(
+                isometry.compose(&other).apply(&coord),
+                isometry.apply(&other.apply(&coord))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn canonical_forms<C: CanonicalCoord>(
+    range: HashShape<C>,
+    seed: u64,
+    index: usize,
+    offset: C,
+) -> Result<(), TestCaseError> {
+    let hash_of = |coord: &C| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, coord).hash(&mut hasher);
+        hasher.finish()
+    };
+    let shape: HashShape<C> = range.into_iter().filter(|coord| hash_of(coord) % 3 == 0).collect();
+    let canonical = canonicalize(&shape);
+    prop_assert_eq!(canonical.form.len(), shape.len());
+    let expected: HashShape<C> = shape.iter().map(|coord| canonical.apply(coord)).collect();
+    prop_assert_eq!(&canonical.form, &expected);
+    prop_assert_eq!(&canonicalize(&canonical.form).form, &canonical.form);
+
+    // Moving the shape by any symmetry of the grid should not change its
+    // canonical form.
+    let transform = Transform::<C>::all()[index % (2 * C::ROTATIONS)];
+    let move_coord = |coord: &C| {
+        let coord = if index.is_multiple_of(2) {
+            coord.half_turn().unwrap_or(*coord)
+        } else {
+            *coord
+        };
+        transform.apply(&coord).translate_from(&offset)
+    };
+    let moved: HashShape<C> = shape.iter().map(move_coord).collect();
+    prop_assert_eq!(&canonicalize(&moved).form, &canonical.form);
+    prop_assert!(is_congruent(&shape, &moved));
+    if let Some(coord) = shape.iter().next() {
+        let mut smaller = moved.clone();
+        smaller.remove(&move_coord(coord));
+        prop_assert!(!is_congruent(&shape, &smaller));
+    }
+
+    // The forms under fewer symmetries are only the same for moves among
+    // those symmetries.
+    let fixed = canonicalize_with(&shape, Equivalence::Fixed);
+    prop_assert!(!fixed.half_turn);
+    prop_assert_eq!(fixed.transform, Transform::identity());
+    let translated: HashShape<C> =
+        shape.iter().map(|coord| coord.translate_from(&offset)).collect();
+    prop_assert_eq!(&canonicalize_with(&translated, Equivalence::Fixed).form, &fixed.form);
+    let one_sided = canonicalize_with(&shape, Equivalence::OneSided);
+    prop_assert!(!one_sided.transform.is_reflection());
+    let rotated: HashShape<C> = shape
+        .iter()
+        .map(|coord| Transform::rotation(index as isize).apply(coord).translate_from(&offset))
+        .collect();
+    prop_assert_eq!(&canonicalize_with(&rotated, Equivalence::OneSided).form, &one_sided.form);
+
+    // Half turns must preserve neighbours.
+    for coord in shape.iter() {
+        if let Some(turned) = coord.half_turn() {
+            prop_assert_eq!(turned.half_turn(), Some(*coord));
+            for next in shape.iter() {
+                prop_assert_eq!(turned.distance(&next.half_turn().unwrap()), coord.distance(next));
+            }
+        }
+    }
+
+    let container: HashShapeContainer<C, u64> =
+        shape.iter().map(|coord| (*coord, hash_of(coord) % 2)).collect();
+    let canonical = canonicalize_container(&container);
+    for (coord, value) in container.iter() {
+        prop_assert_eq!(canonical.form.get(&canonical.apply(coord)), Some(value));
+    }
+    let moved: HashShapeContainer<C, u64> =
+        container.iter().map(|(coord, value)| (move_coord(coord), *value)).collect();
+    prop_assert_eq!(&canonicalize_container(&moved).form, &canonical.form);
+    prop_assert!(is_container_congruent(&container, &moved));
+
+    Ok(())
+}
+
+fn tiling_solutions<C: CanonicalCoord>(
+    range: HashShape<C>,
+    seed: u64,
+    equivalence: Equivalence,
+) -> Result<(), TestCaseError> {
+    let hash_of = |value: usize| {
+        let mut hasher = std::hash::DefaultHasher::new();
+        (seed, value).hash(&mut hasher);
+        hasher.finish() as usize
+    };
+    let target: HashShape<C> = range
+        .into_iter()
+        .filter(|coord| hash_of(coord.grid_to_array_offset().0 as usize) % 3 != 0)
+        .collect();
+    // Choose small pieces, including some that are interchangeable.
+    let pieces: Vec<(HashShape<C>, Equivalence)> = (0..target.len())
+        .map(|index| {
+            let size = 1 + hash_of(index) % 3;
+            let forms = polyform::polyforms::<C>(size, equivalence);
+            (forms[hash_of(index + 1000) % forms.len()].clone(), equivalence)
+        })
+        .collect();
+
+    let solutions: Vec<HashShapeContainer<C, usize>> = tiling::tilings(&target, &pieces).collect();
+    prop_assert_eq!(tiling::count_tilings(&target, &pieces), solutions.len());
+    prop_assert_eq!(tiling::first_tiling(&target, &pieces), solutions.first().cloned());
+    let distinct: HashSet<&HashShapeContainer<C, usize>> = solutions.iter().collect();
+    prop_assert_eq!(distinct.len(), solutions.len());
+    for solution in solutions.iter() {
+        prop_assert_eq!(solution.as_shape(), target.clone());
+        let mut placed: HashMap<usize, HashShape<C>> = HashMap::new();
+        for (coord, piece) in solution.iter() {
+            placed.entry(*piece).or_insert_with(HashShape::new).insert(*coord);
+        }
+        for (piece, cells) in placed {
+            let (shape, equivalence) = &pieces[piece];
+            prop_assert_eq!(
+                canonicalize_with(&cells, *equivalence).form,
+                canonicalize_with(shape, *equivalence).form
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn coord_notation(coord: dynamic::Coord) -> Result<(), TestCaseError> {
+    use dynamic::Coord::*;
+    // The tagged syntax round trips, with or without the alternate flag.
+    let tagged: dynamic::Coord = coord.tagged().to_string().parse().unwrap();
+    prop_assert_eq!(tagged, coord);
+    let alternate: dynamic::Coord = format!("{:#}", coord.tagged()).parse().unwrap();
+    prop_assert_eq!(alternate, coord);
+
+    let text = coord.to_string();
+    let spaced = text.replace(',', " , ").replace('(', "( ");
+    match coord {
+        Square(square) => {
+            prop_assert_eq!(text.parse::<square::Coord>(), Ok(square));
+            prop_assert_eq!(spaced.parse::<square::Coord>(), Ok(square));
+            let label = square.to_label();
+            let (x, y) = (square.to_ivec2().x, square.to_ivec2().y);
+            prop_assert_eq!(label.is_some(), x >= 0 && y >= 0);
+            if let Some(label) = label {
+                prop_assert_eq!(square::Coord::from_label(&label), Ok(square));
+                let upper = label.to_uppercase();
+                prop_assert_eq!(square::Coord::from_label(&upper), Ok(square));
+            }
+        }
+        Hex(hex) => {
+            prop_assert_eq!(text.parse::<hex::Coord>(), Ok(hex));
+            prop_assert_eq!(spaced.parse::<hex::Coord>(), Ok(hex));
+            use hex::OffsetScheme::*;
+            for scheme in [OddR, EvenR, OddQ, EvenQ] {
+                let offset = hex.to_offset(scheme);
+                let label = hex.to_label(scheme);
+                prop_assert_eq!(label.is_some(), offset.x >= 0 && offset.y >= 0);
+                if let Some(label) = label {
+                    prop_assert_eq!(hex::Coord::from_label(scheme, &label), Ok(hex));
+                }
+            }
+        }
+        Triangle(triangle) => {
+            prop_assert_eq!(text.parse::<triangle::Coord>(), Ok(triangle));
+            prop_assert_eq!(spaced.parse::<triangle::Coord>(), Ok(triangle));
+            let alternate = format!("{triangle:#}");
+            prop_assert_eq!(alternate.parse::<triangle::Coord>(), Ok(triangle));
+        }
+    }
+    // Without the tag the kind is ambiguous.
+    prop_assert!(text.parse::<dynamic::Coord>().is_err());
+
+    Ok(())
+}
+
+fn text_art<C: TextCoord, G: SizedGrid<Coord=C>>(
+    coords: Vec<C>,
+    sized_grid: G,
+) -> Result<(), TestCaseError> {
+    let container: HashShapeContainer<C, char> = coords
+        .iter()
+        .enumerate()
+        .map(|(index, coord)| (*coord, (b'a' + (index % 26) as u8) as char))
+        .collect();
+
+    // Rendering from the bottom left corner round trips.
+    let corner = text::bottom_left(&container.as_shape());
+    let art = text::render(&container, &corner, |value| *value);
+    let parsed: HashShapeContainer<C, char> = text::parse(&art, &corner, Some).unwrap();
+    prop_assert_eq!(&parsed, &container);
+    prop_assert!(art.lines().all(|line| line == line.trim_end()));
+
+    // Rendering from the origin omits the coordinates below or to its left.
+    let origin = C::from_text_offset((0, 0));
+    let art = text::render(&container, &origin, |value| *value);
+    let parsed: HashShapeContainer<C, char> = text::parse(&art, &origin, Some).unwrap();
+    let expected: HashShapeContainer<C, char> = container
+        .iter()
+        .filter(|(coord, _)| {
+            let (col, row) = coord.to_text_offset();
+            col >= 0 && row >= 0
+        })
+        .map(|(coord, value)| (*coord, *value))
+        .collect();
+    prop_assert_eq!(parsed, expected);
+
+    // The art is laid out in the same order as the screen.
+    let position = |coord: &C| {
+        let (col, row) = coord.to_text_offset();
+        (C::indent(row) + 2 * (col - corner.to_text_offset().0) as usize, row)
+    };
+    for coord1 in coords.iter() {
+        for coord2 in coords.iter() {
+            let (position1, row1) = position(coord1);
+            let (position2, row2) = position(coord2);
+            let point1 = sized_grid.grid_to_screen(coord1);
+            let point2 = sized_grid.grid_to_screen(coord2);
+            if row1 < row2 {
+                prop_assert!(point1.y < point2.y);
+            } else if row1 == row2 && position1 < position2 {
+                prop_assert!(point1.x < point2.x);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn svg_export(kind: dynamic::Kind, offsets: Vec<(i32, i32)>) -> Result<(), TestCaseError> {
+    use svg::{CellPrimitiveBorderStyle, CellStyle, Rgba, SvgWriter, Theme};
+    let coords: Vec<dynamic::Coord> = offsets
+        .iter()
+        .map(|(x, y)| match kind {
+            dynamic::Kind::Square => square::Coord::new(*x, *y).into(),
+            dynamic::Kind::Hex => hex::Coord::new(*x, *y).into(),
+            dynamic::Kind::Triangle => {
+                let point = if (x + y) % 2 == 0 { TrianglePoint::Up } else { TrianglePoint::Down };
+                triangle::Coord::new(*x, *y, point).into()
+            }
+        })
+        .collect();
+    let shape: HashShape<dynamic::Coord> = coords.iter().cloned().collect();
+    let reversed: HashShape<dynamic::Coord> = coords.iter().rev().cloned().collect();
+    let sized_grid = dynamic::SizedGrid::new(kind, 10.0);
+
+    let draw = |shape: &HashShape<dynamic::Coord>| {
+        let mut writer = SvgWriter::new(sized_grid).with_background(Rgba::WHITE);
+        writer.grid(
+            shape,
+            |coord| Theme::Map.cell_style(coord, false),
+            |coord| Some(coord.to_string()),
+        );
+        writer.to_svg()
+    };
+    let document = draw(&shape);
+    // The output does not depend upon the order of iteration.
+    prop_assert_eq!(&document, &draw(&reversed));
+    prop_assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    prop_assert!(document.ends_with("</svg>\n"));
+    prop_assert_eq!(document.matches("<polygon").count(), shape.len());
+    // Each label has a shadow.
+    prop_assert_eq!(document.matches("<text").count(), 2 * shape.len());
+
+    // The view box contains every vertex.
+    let view_box: Vec<f32> = document
+        .split("viewBox=\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap()
+        .split(' ')
+        .map(|n| n.parse().unwrap())
+        .collect();
+    for coord in shape.iter() {
+        for vertex in sized_grid.vertices(coord) {
+            prop_assert!(vertex.x >= view_box[0] && vertex.x <= view_box[0] + view_box[2]);
+            prop_assert!(-vertex.y >= view_box[1] && -vertex.y <= view_box[1] + view_box[3]);
+        }
+    }
+
+    // Shapes only stroke the edges on their boundary.
+    let style = CellStyle {
+        fill_color: None,
+        border: svg::CellBorderStyle::uniform(1.0, Rgba::BLACK),
+        label: None,
+    };
+    let mut writer = SvgWriter::new(sized_grid);
+    writer.shape(&shape, &style, None);
+    let document = writer.to_svg();
+    let boundary = outline::boundary_edges(&shape);
+    prop_assert_eq!(document.matches("<polyline").count(), boundary.len());
+    let mut writer = SvgWriter::new(sized_grid);
+    writer.shape_outline(&shape, &CellPrimitiveBorderStyle::Uniform(1.0, Rgba::BLACK));
+    let outlines = outline::outlines(&sized_grid, &shape);
+    let polygons: usize = outlines.iter().map(|outline| 1 + outline.holes.len()).sum();
+    prop_assert_eq!(writer.to_svg().matches("<polygon").count(), polygons);
+
+    Ok(())
+}
+
+fn grid_parts(
+    coord: dynamic::Coord,
+    orientation: hex::Orientation,
+) -> Result<(), TestCaseError> {
+    let sized_grid = dynamic::SizedGrid::with_orientation(coord.kind(), 10.0, orientation);
+    let close = |a: Vec2, b: Vec2| a.distance(b) < 1e-3;
+    let cells_at_vertex = match coord.kind() {
+        dynamic::Kind::Square => 4,
+        dynamic::Kind::Hex => 3,
+        dynamic::Kind::Triangle => 6,
+    };
+
+    let edges = coord.edges();
+    let vertices = coord.vertices();
+    prop_assert_eq!(edges.len(), sized_grid.vertices(&coord).len());
+    prop_assert_eq!(vertices.len(), edges.len());
+    for vertex in &vertices {
+        let point = sized_grid.vertex_to_screen(vertex);
+        prop_assert!(sized_grid.vertices(&coord).iter().any(|v| close(*v, point)));
+    }
+
+    for dir in coord.allowed_directions(DirectionType::Face).iter() {
+        let neighbour = coord.move_in_direction(DirectionType::Face, dir).unwrap();
+        let edge = Edge::from_face(coord, dir).unwrap();
+        // Both cells sharing the edge agree upon its identity.
+        prop_assert_eq!(Some(edge), Edge::from_face(neighbour, !dir));
+        prop_assert!(edges.contains(&edge));
+        prop_assert!(neighbour.edges().contains(&edge));
+        let mut faces = edge.faces();
+        faces.sort_by_key(|c| c.to_string());
+        let mut expected = [coord, neighbour];
+        expected.sort_by_key(|c| c.to_string());
+        prop_assert_eq!(faces, expected);
+
+        // The geometry of the edge matches that of the cell.
+        let (start, end) = sized_grid.edge_to_screen(&edge);
+        let (a, b) = sized_grid.edges(&coord)[&dir];
+        prop_assert!((close(start, a) && close(end, b)) || (close(start, b) && close(end, a)));
+        for vertex in edge.vertices() {
+            prop_assert!(vertices.contains(&vertex));
+            prop_assert!(vertex.edges().contains(&edge));
+            let point = sized_grid.vertex_to_screen(&vertex);
+            prop_assert!(close(point, a) || close(point, b));
+        }
+        for other in edge.neighbours() {
+            prop_assert_ne!(other, edge);
+            prop_assert!(other.neighbours().contains(&edge));
+        }
+    }
+    prop_assert!(Edge::from_face(coord, Direction::East).is_some()
+        == coord.allowed_directions(DirectionType::Face).contains(Direction::East));
+
+    for vertex in vertices {
+        let point = sized_grid.vertex_to_screen(&vertex);
+        let faces = vertex.faces();
+        prop_assert_eq!(faces.len(), cells_at_vertex);
+        prop_assert!(faces.contains(&coord));
+        // Every cell meeting at the vertex agrees upon its identity and
+        // position.
+        for face in &faces {
+            prop_assert!(face.vertices().contains(&vertex));
+            prop_assert!(sized_grid.vertices(face).iter().any(|v| close(*v, point)));
+        }
+        let edges = vertex.edges();
+        prop_assert_eq!(edges.len(), cells_at_vertex);
+        for edge in &edges {
+            prop_assert!(edge.vertices().contains(&vertex));
+        }
+        let neighbours = vertex.neighbours();
+        prop_assert_eq!(neighbours.len(), cells_at_vertex);
+        for other in neighbours {
+            prop_assert!(other.neighbours().contains(&vertex));
+            let distance = sized_grid.vertex_to_screen(&other).distance(point);
+            prop_assert!((distance - sized_grid.edge_length()).abs() < 1e-3);
+        }
+        prop_assert_eq!(Vertex::from_corner(vertex.coord(), vertex.direction()), Some(vertex));
+    }
+
+    Ok(())
+}
+
+fn layered_coords<LC: LayeredCoord + Copy>(coord1: LC, coord2: LC) -> Result<(), TestCaseError> {
+    // Moving vertically keeps the planar coordinate.
+    let up = coord1.move_vertically(Vertical::Up);
+    prop_assert_eq!(up.level(), coord1.level() + 1);
+    prop_assert_eq!(up.planar(), coord1.planar());
+    prop_assert_eq!(up.move_vertically(Vertical::Down), coord1);
+    let down = LC::from_planar(coord1.planar(), coord1.level() - 1);
+    prop_assert_eq!(coord1.move_vertically(!Vertical::Up), down);
+    prop_assert_eq!(LC::from_planar(coord1.planar(), coord1.level()), coord1);
+    prop_assert_eq!(coord1.distance(&up), 1);
+    prop_assert_ne!(coord1.to_color(), up.to_color());
+    prop_assert_ne!(coord1.grid_to_array_offset(), up.grid_to_array_offset());
+
+    let adjacent = |a: &LC, b: &LC| {
+        [Vertical::Up, Vertical::Down].into_iter().any(|dir| a.move_vertically(dir) == *b)
+            || a.allowed_directions(DirectionType::Face)
+                .iter()
+                .any(|dir| a.move_in_direction(DirectionType::Face, dir).as_ref() == Some(b))
+    };
+    let distance = coord1.distance(&coord2);
+    prop_assert_eq!(distance, coord2.distance(&coord1));
+    prop_assert!(distance >= coord1.level().abs_diff(coord2.level()) as usize);
+    prop_assert!(coord1.vertex_distance(&coord2) <= distance);
+    prop_assert!(coord1.euclidean_distance(&coord2) <= distance as f32 + 1e-3);
+
+    // Paths step between coordinates within and between levels.
+    let path: Vec<LC> = coord1.path_iterator(&coord2).collect();
+    prop_assert_eq!(path.first(), Some(&coord1));
+    prop_assert_eq!(path.last(), Some(&coord2));
+    prop_assert_eq!(path.len(), distance + 1);
+    prop_assert_eq!(path.iter().collect::<HashSet<_>>().len(), path.len());
+    for (prev, next) in path.iter().zip(path.iter().skip(1)) {
+        prop_assert!(adjacent(prev, next), "{} and {} should be adjacent.", prev, next);
+    }
+
+    // Lines may also take the steps of `vertex_distance`.
+    let line: Vec<LC> = coord1.line_iterator(&coord2).collect();
+    prop_assert_eq!(line.first(), Some(&coord1));
+    prop_assert_eq!(line.last(), Some(&coord2));
+    prop_assert!(line.len() <= distance + 1);
+    prop_assert_eq!(line.iter().collect::<HashSet<_>>().len(), line.len());
+    for (prev, next) in line.iter().zip(line.iter().skip(1)) {
+        prop_assert_eq!(prev.vertex_distance(next), 1, "{} and {} should neighbour.", prev, next);
+    }
+
+    let supercover: Vec<LC> = coord1.supercover_iterator(&coord2).collect();
+    let cover_set: HashSet<LC> = supercover.iter().cloned().collect();
+    prop_assert_eq!(supercover.first(), Some(&coord1));
+    prop_assert_eq!(supercover.last(), Some(&coord2));
+    prop_assert_eq!(cover_set.len(), supercover.len(), "There should be no duplicates.");
+    prop_assert!(supercover.len() > distance, "The supercover should cover a path.");
+    prop_assert_eq!(&cover_set, &coord2.supercover_iterator(&coord1).collect::<HashSet<_>>());
+    let mut reached = HashSet::from([coord1]);
+    let mut frontier = vec![coord1];
+    while let Some(coord) = frontier.pop() {
+        for next in cover_set.iter() {
+            if adjacent(&coord, next) && reached.insert(*next) {
+                frontier.push(*next);
+            }
+        }
+    }
+    prop_assert_eq!(&reached, &cover_set, "The supercover should be connected.");
+
+    // Layers of a container can be separated and stacked again.
+    let container: HashShapeContainer<LC, usize> = path.iter().copied().zip(0..).collect();
+    let levels: HashSet<i32> = path.iter().map(|coord| coord.level()).collect();
+    let layers = levels.iter().map(|level| (*level, layered::layer(&container, *level)));
+    let stacked: HashShapeContainer<LC, usize> = layered::stack(layers);
+    prop_assert_eq!(stacked, container);
+
+    Ok(())
+}
+
+/// Helper to check that the given `range` and `ring` functions produce the
+/// balls and spheres of the given `metric` from the origin.
+fn layered_shapes<LC: LayeredCoord + Copy>(
+    range: impl Fn(usize) -> HashShape<LC>,
+    ring: impl Fn(usize) -> HashShape<LC>,
+    metric: impl Fn(&LC) -> usize,
+    radius: usize,
+) -> Result<(), TestCaseError> {
+    let shape = range(radius);
+    let rings: Vec<HashShape<LC>> = (0..=radius).map(&ring).collect();
+    prop_assert_eq!(rings.iter().map(|ring| ring.len()).sum::<usize>(), shape.len());
+    for (distance, ring) in rings.iter().enumerate() {
+        prop_assert!(ring.iter().all(|coord| metric(coord) == distance));
+        prop_assert!(ring.iter().all(|coord| shape.contains(coord)));
+    }
+    // Every neighbour within the radius should also be in the range.
+    for coord in shape.iter() {
+        let vertical = [Vertical::Up, Vertical::Down].map(|dir| coord.move_vertically(dir));
+        let dirs = coord.allowed_directions(DirectionType::Face);
+        let planar = dirs.iter().map(|dir| coord.move_in_direction(DirectionType::Face, dir));
+        for next in planar.flatten().chain(vertical) {
+            prop_assert!(metric(&next) > radius || shape.contains(&next));
+        }
+    }
+
+    Ok(())
+}
+
+//////////////////////////////////////////////////////////////////////////////
+
+proptest! {
+    #[test]
+    fn hex_unary_op(coord1 in hexcoord_strategy()) {
+        coord_neg(coord1)?;
+        coord_add_ident(coord1)?;
+        coord_sub_unit(coord1)?;
+        coord_mul_unit(coord1)?;
+
+        // Verify that the origin is the same as the default coordinate.
+        prop_assert_eq!(dynamic::Coord::Hex(hex::Coord::default()),
+        dynamic::Coord::origin(dynamic::Kind::Hex))
+    }
+
+    #[test]
+    fn square_unary_op(coord1 in squarecoord_strategy()) {
+        coord_neg(coord1)?;
+        coord_add_ident(coord1)?;
+        coord_sub_unit(coord1)?;
+        coord_mul_unit(coord1)?;
+
+        // Verify that the origin is the same as the default coordinate.
+        prop_assert_eq!(dynamic::Coord::Square(square::Coord::default()),
+        dynamic::Coord::origin(dynamic::Kind::Square))
+    }
+
+    #[test]
+    fn hex_mul_assoc_distrib(coord in hexcoord_strategy(), x in -100..100isize, y in -100..100isize) {
+        coord_mul_assoc(coord, x, y)?;
+        coord_mul_distributive_ring(coord, x, y)?;
+    }
+
+    #[test]
+    fn hex_mul_distrib(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy(), x in -100..100isize) {
+        coord_mul_distributive_coord(coord1, coord2, x)?;
+    }
+
+    #[test]
+    fn square_mul_assoc_distrib(coord in squarecoord_strategy(), x in -100..100isize, y in -100..100isize) {
+        coord_mul_assoc(coord, x, y)?;
+        coord_mul_distributive_ring(coord, x, y)?;
+    }
+
+    #[test]
+    fn square_mul_distrib(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy(), x in -100..100isize) {
+        coord_mul_distributive_coord(coord1, coord2, x)?;
+    }
+
+    #[test]
+    fn triangle_unary_op(coord1 in trianglecoord_strategy()) {
+        // Also verify the `is_up` method for triangle coordinates.
+        let coord_point = coord1.to_ivec2().1;
+        prop_assert!(!coord1.is_up() || matches!(coord_point, TrianglePoint::Up),
+            "The is_up method does not match the TrianglePoint value.");
+                // Also verify the `is_up` method for triangle coordinates.
+        prop_assert!(!matches!(coord_point, TrianglePoint::Up) || coord1.is_up(),
+            "The is_up method does not match the TrianglePoint value.");
+
+        // Verify that the origin is the same as the default coordinate.
+        prop_assert_eq!(dynamic::Coord::Triangle(triangle::Coord::default()),
+        dynamic::Coord::origin(dynamic::Kind::Triangle))
+    }
+
+    #[test]
+    fn hex_binary_op(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy()) {
+        coord_add_comm(coord1, coord2)?;
+        coord_sub_anticomm(coord1, coord2)?;
+    }
+
+    #[test]
+    fn square_binary_op(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy()) {
+        coord_add_comm(coord1, coord2)?;
+        coord_sub_anticomm(coord1, coord2)?;
+    }
+
+    #[test]
+    fn hex_assoc(coord1 in hexcoord_strategy(), coord2 in hexcoord_strategy(), coord3 in hexcoord_strategy()) {
+        coord_add_assoc(coord1, coord2, coord3)?;
+    }
+
+    #[test]
+    fn square_assoc(coord1 in squarecoord_strategy(), coord2 in squarecoord_strategy(), coord3 in squarecoord_strategy()) {
+        coord_add_assoc(coord1, coord2, coord3)?;
+    }
+
+    #[test]
+    fn test_grid_color(coord in dynamic_coord_strategy()) {
+        grid_color(coord)?
+    }
+
+    #[test]
+    fn test_grid_rotation(coord in dynamic_coord_strategy()) {
+        grid_rotation(coord)?
+    }
+
+    #[test]
+    fn test_grid_reflection(coord in dynamic_coord_strategy()) {
+        grid_reflection(coord,coord.kind().axes().as_slice())?
+    }
+
+    #[test]
+    fn test_grid_direction(coord in dynamic_coord_strategy()) {
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            grid_direction(coord, dt)?;
+            grid_angle_to_direction(coord, dt)?;
+        }
+    }
+
+    #[test]
+    fn test_grid_direction_iterator(coord in small_dynamic_coord_strategy()) {
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            grid_direction_iterator(coord, dt)?;
+        }
+    }
+
+    #[test]
+    fn test_grid_axis_iterator(coord in small_dynamic_coord_strategy()) {
+        grid_axis_iterator(coord, coord.kind().axes().as_slice())?;
+    }
+
+    #[test]
+    fn hex_grid_to_array_offset(coord in hexcoord_strategy()) {
+        let array_offset = coord.grid_to_array_offset();
+        prop_assert_eq!(coord, hex::Coord::array_offset_to_grid(array_offset),
+        "With array offset {:?}", array_offset);
+
+        let dyn_coord = dynamic::Coord::Hex(coord);
+        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+    }
+
+    #[test]
+    fn square_grid_to_array_offset(coord in squarecoord_strategy()) {
+        let array_offset = coord.grid_to_array_offset();
+        prop_assert_eq!(coord, square::Coord::array_offset_to_grid(array_offset),
+        "With array offset {:?}", array_offset);
+
+        let dyn_coord = dynamic::Coord::Square(coord);
+        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+    }
+
+    #[test]
+    fn triangle_grid_to_array_offset(coord in trianglecoord_strategy()) {
+        let array_offset = coord.grid_to_array_offset();
+        prop_assert_eq!(coord, triangle::Coord::array_offset_to_grid(array_offset),
+        "With array offset {:?}", array_offset);
+
+        let dyn_coord = dynamic::Coord::Triangle(coord);
+        prop_assert_eq!(dyn_coord.grid_to_array_offset(), array_offset);
+    }
+
+    #[test]
+    fn test_path(coord1 in small_dynamic_coord_strategy(), coord2 in small_dynamic_coord_strategy()) {
+        prop_assume!(coord1.kind() == coord2.kind(), "Coordinates should be of the same kind.");
+        grid_path(coord1, coord2)?;
+    }
+
+    #[test]
+    fn test_lines(coord1 in small_dynamic_coord_strategy(), coord2 in small_dynamic_coord_strategy()) {
+        prop_assume!(coord1.kind() == coord2.kind(), "Coordinates should be of the same kind.");
+        grid_lines(coord1, coord2)?;
+    }
+
+    #[test]
+    fn test_supercover_geometry(coord1 in tiny_dynamic_coord_strategy(),
+        coord2 in tiny_dynamic_coord_strategy()) {
+        prop_assume!(coord1.kind() == coord2.kind(), "Coordinates should be of the same kind.");
+        supercover_geometry(coord1, coord2)?;
+    }
+
+    #[test]
+    fn test_sized_grid_commutation(size in &SIZE_RANGE,
+        coord in dynamic_coord_strategy()) {
+        let sized_grid = dynamic::SizedGrid::new(coord.kind(), size);
+        prop_assert_eq!(sized_grid.kind(), coord.kind(),
+            "Sized grid kind should match coordinate kind.");
+        let fudge = match coord {
+            dynamic::Coord::Hex(_) => sized_grid.edge_length(),
+            _ => 0.0,
+        };
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            sized_grid_commutation(sized_grid, coord, dt, fudge)?;
+        }
+        sized_grid_radius(sized_grid)?;
+        sized_grid_identity(sized_grid, coord)?;
+    }
+
+    #[test]
+    fn test_sized_grid_orientation(size in &SIZE_RANGE, coord in dynamic_coord_strategy(),
+        pointy in any::<bool>()) {
+        let orientation =
+            if pointy { hex::Orientation::PointyTop } else { hex::Orientation::FlatTop };
+        let sized_grid = dynamic::SizedGrid::with_orientation(coord.kind(), size, orientation);
+        let fudge = match coord {
+            dynamic::Coord::Hex(_) => sized_grid.edge_length(),
+            _ => 0.0,
+        };
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            sized_grid_commutation(sized_grid, coord, dt, fudge)?;
+        }
+        sized_grid_radius(sized_grid)?;
+        sized_grid_identity(sized_grid, coord)?;
+        sized_grid_vertices(sized_grid, &coord, coord.kind().num_vertices())?;
+        sized_grid_orientation(sized_grid, coord)?;
+        if let dynamic::Coord::Hex(hex_coord) = coord {
+            let hex_grid = hex::SizedGrid::with_orientation(size, orientation);
+            prop_assert_eq!(hex_grid.orientation(), orientation);
+            prop_assert_eq!(hex::SizedGrid::new(size), hex::SizedGrid::with_orientation(size,
+                hex::Orientation::FlatTop));
+            sized_grid_orientation(hex_grid, hex_coord)?;
+        }
+    }
+
+    #[test]
+    fn test_sized_grid_rect(kind in kind_strategy(), pointy in any::<bool>(),
+        min in (-10.0..10.0f32, -10.0..10.0f32), size in (0.0..12.0f32, 0.0..12.0f32)) {
+        let orientation =
+            if pointy { hex::Orientation::PointyTop } else { hex::Orientation::FlatTop };
+        let grid = dynamic::SizedGrid::with_orientation(kind, 1.0, orientation);
+        sized_grid_rect(grid, Vec2::new(min.0, min.1), Vec2::new(size.0, size.1))?;
+    }
+
+    #[test]
+    fn sized_vertices(size in &SIZE_RANGE,
+        coord in dynamic_coord_strategy()) {
         let kind = coord.kind();
         sized_grid_vertices(dynamic::SizedGrid::new(kind, size), &coord, kind.num_vertices())?;
     }
@@ -1127,6 +3086,326 @@ proptest! {
     fn shapes(kind in kind_strategy()) {
         grid_shapes(kind)?;
     }
+
+    #[test]
+    fn square_array_shape_container(coords in prop::collection::vec(tiny_squarecoord_strategy(), 0..50),
+        offset in tiny_squarecoord_strategy()) {
+        array_shape_container(coords.clone())?;
+        array_shape_container_translate(coords, offset)?;
+    }
+
+    #[test]
+    fn hex_array_shape_container(coords in prop::collection::vec(tiny_hexcoord_strategy(), 0..50),
+        offset in tiny_hexcoord_strategy()) {
+        array_shape_container(coords.clone())?;
+        array_shape_container_translate(coords, offset)?;
+    }
+
+    #[test]
+    fn square_bit_shape(coords1 in prop::collection::vec(tiny_squarecoord_strategy(), 0..100),
+        coords2 in prop::collection::vec(tiny_squarecoord_strategy(), 0..100),
+        offset in tiny_squarecoord_strategy()) {
+        bit_shape(coords1.clone(), coords2)?;
+        let shape: HashShape<_> = coords1.into_iter().collect();
+        prop_assert_eq!(HashShape::from(BitShape::from(&shape).translate(&offset)),
+            shape.translate(&offset));
+    }
+
+    #[test]
+    fn hex_bit_shape(coords1 in prop::collection::vec(tiny_hexcoord_strategy(), 0..100),
+        coords2 in prop::collection::vec(tiny_hexcoord_strategy(), 0..100),
+        offset in tiny_hexcoord_strategy()) {
+        bit_shape(coords1.clone(), coords2)?;
+        let shape: HashShape<_> = coords1.into_iter().collect();
+        prop_assert_eq!(HashShape::from(BitShape::from(&shape).translate(&offset)),
+            shape.translate(&offset));
+    }
+
+    #[test]
+    fn triangle_bit_shape(coords1 in prop::collection::vec(tiny_trianglecoord_strategy(), 0..100),
+        coords2 in prop::collection::vec(tiny_trianglecoord_strategy(), 0..100)) {
+        bit_shape(coords1, coords2)?;
+    }
+
+    #[test]
+    fn square_chunked_shape_container(coords in prop::collection::vec(tiny_squarecoord_strategy(), 0..50),
+        offset in tiny_squarecoord_strategy(), chunk_size in 1usize..5) {
+        chunked_shape_container(coords, offset, chunk_size)?;
+    }
+
+    #[test]
+    fn hex_chunked_shape_container(coords in prop::collection::vec(tiny_hexcoord_strategy(), 0..50),
+        offset in tiny_hexcoord_strategy(), chunk_size in 1usize..5) {
+        chunked_shape_container(coords, offset, chunk_size)?;
+    }
+
+    #[test]
+    fn triangle_array_shape_container(coords in prop::collection::vec(tiny_trianglecoord_strategy(), 0..50)) {
+        array_shape_container(coords)?;
+    }
+
+    #[test]
+    fn square_shape_algebra(coords1 in prop::collection::vec(tiny_squarecoord_strategy(), 0..50),
+        coords2 in prop::collection::vec(tiny_squarecoord_strategy(), 0..50)) {
+        shape_algebra::<_, HashShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_algebra::<_, BitShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, HashShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ArrayShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ChunkedShapeContainer<_, _>>(coords1, coords2)?;
+    }
+
+    #[test]
+    fn hex_shape_algebra(coords1 in prop::collection::vec(tiny_hexcoord_strategy(), 0..50),
+        coords2 in prop::collection::vec(tiny_hexcoord_strategy(), 0..50)) {
+        shape_algebra::<_, HashShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_algebra::<_, BitShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, HashShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ArrayShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ChunkedShapeContainer<_, _>>(coords1, coords2)?;
+    }
+
+    #[test]
+    fn triangle_shape_algebra(coords1 in prop::collection::vec(tiny_trianglecoord_strategy(), 0..50),
+        coords2 in prop::collection::vec(tiny_trianglecoord_strategy(), 0..50)) {
+        shape_algebra::<_, HashShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_algebra::<_, BitShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, HashShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ArrayShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, ChunkedShapeContainer<_, _>>(coords1, coords2)?;
+    }
+
+    #[test]
+    fn test_pathfinding(kind in kind_strategy(), seed in any::<u64>(), goal_index in any::<usize>(),
+        dir_type in prop_oneof![Just(DirectionType::Face), Just(DirectionType::Vertex)]) {
+        pathfinding(kind, dir_type, seed, goal_index)?;
+    }
+
+    #[test]
+    fn test_distance_flow_fields(kind in kind_strategy(), seed in any::<u64>(),
+        source_indices in prop::collection::vec(any::<usize>(), 0..4),
+        dir_type in prop_oneof![Just(DirectionType::Face), Just(DirectionType::Vertex)]) {
+        distance_flow_fields(kind, dir_type, seed, source_indices)?;
+    }
+
+    #[test]
+    fn test_line_of_sight(kind in kind_strategy(), seed in any::<u64>(),
+        from_index in any::<usize>()) {
+        line_of_sight(kind, seed, from_index)?;
+    }
+
+    #[test]
+    fn test_connectivity(kind in kind_strategy(), seed in any::<u64>(),
+        dir_type in prop_oneof![Just(DirectionType::Face), Just(DirectionType::Vertex)]) {
+        connectivity(kind, dir_type, seed)?;
+    }
+
+    #[test]
+    fn test_outlines(kind in kind_strategy(), size in 0.5..50.0f32, seed in any::<u64>()) {
+        outlines(kind, size, seed)?;
+    }
+
+    #[test]
+    fn test_shape_constructors(kind in kind_strategy(), first_len in 0..8usize,
+        second_len in 0..8usize) {
+        shape_constructors(kind, first_len, second_len)?;
+    }
+
+    #[test]
+    fn test_metric_distances(coord1 in tiny_dynamic_coord_strategy(),
+        coord2 in tiny_dynamic_coord_strategy()) {
+        prop_assume!(coord1.kind() == coord2.kind(), "Coordinates should be of the same kind.");
+        metric_distances(coord1, coord2)?;
+    }
+
+    #[test]
+    fn test_metric_shapes(kind in kind_strategy(), radius in 0..7usize) {
+        metric_shapes(kind, radius)?;
+    }
+
+    #[test]
+    fn test_hex_offsets(coord in small_hexcoord_strategy()) {
+        hex_offsets(coord)?;
+    }
+
+    #[test]
+    fn test_triangle_offsets(coord in small_trianglecoord_strategy()) {
+        triangle_offsets(coord)?;
+    }
+
+    #[test]
+    fn test_wrapping(wrap in prop_oneof![Just(Wrap::X), Just(Wrap::Y), Just(Wrap::Both)],
+        columns in 1..8usize, rows in 1..8usize,
+        square1 in tiny_squarecoord_strategy(), square2 in tiny_squarecoord_strategy(),
+        hex1 in tiny_hexcoord_strategy(), hex2 in tiny_hexcoord_strategy()) {
+        fn topology<C: WrappingCoord>(wrap: Wrap, columns: usize, rows: usize) -> Topology<C> {
+            match wrap {
+                Wrap::X => Topology::wrap_x(columns),
+                Wrap::Y => Topology::wrap_y(rows),
+                Wrap::Both => Topology::wrap_both(columns, rows),
+            }
+        }
+        wrapping(topology(wrap, columns, rows), square1, square2)?;
+        wrapping(topology(wrap, 2 * columns, rows), hex1, hex2)?;
+    }
+
+    #[test]
+    fn test_bounded_board(kind in kind_strategy(), seed in any::<u64>(),
+        dir_type in prop_oneof![Just(DirectionType::Face), Just(DirectionType::Vertex)]) {
+        bounded_board(kind, dir_type, seed)?;
+    }
+
+    #[test]
+    fn test_symmetries(square1 in tiny_squarecoord_strategy(),
+        square2 in tiny_squarecoord_strategy(), square3 in tiny_squarecoord_strategy(),
+        hex1 in tiny_hexcoord_strategy(), hex2 in tiny_hexcoord_strategy(),
+        hex3 in tiny_hexcoord_strategy(),
+        triangle1 in tiny_trianglecoord_strategy(), triangle2 in tiny_trianglecoord_strategy()) {
+        use square::Axes::*;
+        symmetries(square1, square2, &[X, Y])?;
+        use hex::Axes::*;
+        symmetries(hex1, hex2, &[Q, R, S])?;
+        use triangle::Axes::*;
+        symmetries(triangle1, triangle2, &[A, B, C])?;
+        isometries(square1, square2, square3)?;
+        isometries(hex1, hex2, hex3)?;
+    }
+
+    #[test]
+    fn test_canonical_forms(seed in any::<u64>(), index in any::<usize>(),
+        square in tiny_squarecoord_strategy(), hex in tiny_hexcoord_strategy(),
+        triangle in tiny_coord_strategy()) {
+        canonical_forms(square::Coord::range(3), seed, index, square)?;
+        canonical_forms(hex::Coord::range(3), seed, index, hex)?;
+        let (x, y) = triangle;
+        let triangle = triangle::Coord::new(x, y, TrianglePoint::Up);
+        canonical_forms(triangle::Coord::range(3), seed, index, triangle)?;
+    }
+
+    #[test]
+    fn test_tiling_solutions(seed in any::<u64>(),
+        equivalence in prop_oneof![Just(Equivalence::Fixed), Just(Equivalence::OneSided),
+            Just(Equivalence::Free)]) {
+        tiling_solutions(square::Coord::rectangle(3, 3), seed, equivalence)?;
+        tiling_solutions(hex::Coord::range(1), seed, equivalence)?;
+        tiling_solutions(triangle::Coord::hexagon(1), seed, equivalence)?;
+    }
+
+    #[test]
+    fn test_coord_notation(coord in dynamic_coord_strategy()) {
+        coord_notation(coord)?;
+    }
+
+    #[test]
+    fn test_text_art(squares in prop::collection::vec(tiny_squarecoord_strategy(), 0..30),
+        hexes in prop::collection::vec(tiny_hexcoord_strategy(), 0..30),
+        triangles in prop::collection::vec(tiny_trianglecoord_strategy(), 0..30)) {
+        text_art(squares, square::SizedGrid::new(1.0))?;
+        text_art(hexes, hex::SizedGrid::with_orientation(1.0, hex::Orientation::PointyTop))?;
+        text_art(triangles, triangle::SizedGrid::new(1.0))?;
+    }
+
+    #[test]
+    fn test_svg_export(kind in kind_strategy(),
+        offsets in prop::collection::vec(tiny_coord_strategy(), 0..20)) {
+        svg_export(kind, offsets)?;
+    }
+
+    #[test]
+    fn test_grid_parts(coord in tiny_dynamic_coord_strategy(), pointy in any::<bool>()) {
+        let orientation =
+            if pointy { hex::Orientation::PointyTop } else { hex::Orientation::FlatTop };
+        grid_parts(coord, orientation)?;
+    }
+
+    #[test]
+    fn test_layered_coords(coord1 in tiny_layered_coord_strategy(),
+        coord2 in tiny_layered_coord_strategy()) {
+        prop_assume!(coord1.planar().kind() == coord2.planar().kind());
+        layered_coords(coord1, coord2)?;
+        grid_direction(coord1, DirectionType::Face)?;
+        grid_rotation(coord1)?;
+        grid_color(coord1)?;
+        let axes = coord1.planar().kind().axes();
+        let axes: Vec<_> = axes.into_iter().map(layered::Axes::Planar).collect();
+        grid_reflection(coord1, &axes)?;
+        grid_axis_iterator(coord1, &axes)?;
+        shape_container_algebra::<_, HashShapeContainer<_, _>>(vec![coord1], vec![coord2])?;
+    }
+
+    #[test]
+    fn test_cubic_coords(coord1 in tiny_cubiccoord_strategy(),
+        coord2 in tiny_cubiccoord_strategy(), radius in 0..4usize) {
+        layered_coords(coord1, coord2)?;
+        coord_neg(coord1)?;
+        coord_add_comm(coord1, coord2)?;
+        coord_sub_anticomm(coord1, coord2)?;
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            grid_direction(coord1, dt)?;
+            grid_angle_to_direction(coord1, dt)?;
+            grid_direction_iterator(coord1, dt)?;
+        }
+        grid_rotation(coord1)?;
+        grid_color(coord1)?;
+        // Within a level, the supercover matches that of the planar grid.
+        let flat = cubic::Coord::from_planar(coord2.planar(), coord1.level());
+        let planar: Vec<_> = coord1.planar().supercover_iterator(&coord2.planar()).collect();
+        let cover: Vec<_> = coord1.supercover_iterator(&flat).map(|c| c.planar()).collect();
+        prop_assert_eq!(cover, planar);
+        grid_reflection(coord1, &[cubic::Axes::X, cubic::Axes::Y])?;
+        grid_axis_iterator(coord1, &[cubic::Axes::X, cubic::Axes::Y])?;
+        let offset = coord2.to_ivec3() - coord1.to_ivec3();
+        let vertex_distance = offset.truncate().abs().max_element() + offset.z.abs();
+        prop_assert_eq!(coord1.vertex_distance(&coord2) as i32, vertex_distance);
+        let line_length = coord1.line_iterator(&coord2).count() as i32;
+        prop_assert_eq!(line_length, vertex_distance + 1);
+        let metric = |c: &cubic::Coord| c.to_ivec3().abs().max_element() as usize;
+        layered_shapes(cubic::Coord::range, cubic::Coord::ring, metric, radius)?;
+    }
+
+    #[test]
+    fn test_prism_coords(coord1 in tiny_prismcoord_strategy(),
+        coord2 in tiny_prismcoord_strategy(), radius in 0..4usize) {
+        layered_coords(coord1, coord2)?;
+        coord_neg(coord1)?;
+        coord_add_comm(coord1, coord2)?;
+        coord_sub_anticomm(coord1, coord2)?;
+        for dt in [DirectionType::Face, DirectionType::Vertex] {
+            grid_direction(coord1, dt)?;
+            grid_angle_to_direction(coord1, dt)?;
+            grid_direction_iterator(coord1, dt)?;
+        }
+        grid_rotation(coord1)?;
+        grid_color(coord1)?;
+        let axes = [prism::Axes::Q, prism::Axes::R, prism::Axes::S];
+        // Within a level, the supercover matches that of the planar grid.
+        let flat = prism::Coord::from_planar(coord2.planar(), coord1.level());
+        let planar: Vec<_> = coord1.planar().supercover_iterator(&coord2.planar()).collect();
+        let cover: Vec<_> = coord1.supercover_iterator(&flat).map(|c| c.planar()).collect();
+        prop_assert_eq!(cover, planar);
+        grid_reflection(coord1, &axes)?;
+        grid_axis_iterator(coord1, &axes)?;
+        let origin = hex::Coord::default();
+        let metric = |c: &prism::Coord| {
+            c.planar().distance(&origin).max(c.level().unsigned_abs() as usize)
+        };
+        layered_shapes(prism::Coord::range, prism::Coord::ring, metric, radius)?;
+        let hex_range = |radius| layered::Coord::range(radius, hex::Coord::range);
+        let hex_ring = |radius| layered::Coord::ring(radius, hex::Coord::ring);
+        let origin = layered::Coord::new(origin, 0);
+        layered_shapes(hex_range, hex_ring, |c| c.distance(&origin), radius)?;
+    }
+
+    #[test]
+    fn dynamic_shape_algebra(coords1 in prop::collection::vec(tiny_dynamic_coord_strategy(), 0..50),
+        coords2 in prop::collection::vec(tiny_dynamic_coord_strategy(), 0..50)) {
+        shape_algebra::<_, HashShape<_>>(coords1.clone(), coords2.clone())?;
+        shape_container_algebra::<_, HashShapeContainer<_, _>>(coords1.clone(), coords2.clone())?;
+        let shape1: HashShape<_> = coords1.into_iter().collect();
+        let shape2: HashShape<_> = coords2.into_iter().collect();
+        prop_assert_eq!(&shape1 | &shape2, shape1.union(&shape2));
+        prop_assert_eq!(&shape1 & &shape2, shape1.intersection(&shape2));
+        prop_assert_eq!(shape1.clone() ^ shape2.clone(), shape1.symmetric_difference(&shape2));
+    }
 }
 
 #[test]
@@ -1134,6 +3413,615 @@ fn triangle_point() {
     assert_ne!(TrianglePoint::Up, !TrianglePoint::Up);
 }
 
+#[test]
+fn square_line_of_sight() {
+    let origin = square::Coord::new(0, 0);
+    let walls = HashShape::from([square::Coord::new(1, 0)]);
+    let blocks = |coord: &square::Coord| walls.contains(coord);
+    // Walls themselves are visible, but not what lies behind them.
+    assert!(visibility::has_line_of_sight(&origin, &square::Coord::new(1, 0), blocks));
+    assert!(!visibility::has_line_of_sight(&origin, &square::Coord::new(2, 0), blocks));
+    // A diagonal passing the corner of a single wall is not blocked.
+    assert!(visibility::has_line_of_sight(&origin, &square::Coord::new(2, 2), blocks));
+    // Whereas a diagonal passing between two walls meeting at a corner is.
+    let walls = HashShape::from([square::Coord::new(1, 0), square::Coord::new(0, 1)]);
+    let blocks = |coord: &square::Coord| walls.contains(coord);
+    assert!(!visibility::has_line_of_sight(&origin, &square::Coord::new(2, 2), blocks));
+    // Even long sight lines are walked exactly.
+    let far = square::Coord::new(100_000, 3);
+    assert!(visibility::has_line_of_sight(&origin, &far, |_| false));
+    let wall = square::Coord::new(40_000, 1);
+    assert!(!visibility::has_line_of_sight(&origin, &far, |coord| *coord == wall));
+}
+
+#[test]
+fn square_field_of_view() {
+    let origin = square::Coord::new(0, 0);
+    let walls = HashShape::from([square::Coord::new(1, 0), square::Coord::new(0, 1)]);
+    let visible =
+        visibility::field_of_view(&origin, Metric::Vertex, 2, |coord| walls.contains(coord));
+    // The walls hide the squares behind them, along with the diagonal that
+    // passes through the corner where they meet.
+    let hidden = [(2, -1), (2, 0), (2, 1), (1, 1), (2, 2), (1, 2), (0, 2), (-1, 2)]
+        .map(|(x, y)| square::Coord::new(x, y));
+    let expected = &square::Coord::metric_range(Metric::Vertex, 2) - &HashShape::from(hidden);
+    assert_eq!(visible, expected);
+}
+
+#[test]
+fn hex_field_of_view() {
+    let origin = hex::Coord::new(0, 0);
+    let walls = HashShape::from([hex::Coord::new(1, 0), hex::Coord::new(0, 1)]);
+    let visible =
+        visibility::field_of_view(&origin, Metric::Face, 2, |coord| walls.contains(coord));
+    // The hexagon between the two walls can only be seen along the edge
+    // they share, so it is hidden along with those directly behind them.
+    // Those either side can still be seen past the other edge of a wall.
+    let hidden = [(2, 0), (1, 1), (0, 2)].map(|(x, y)| hex::Coord::new(x, y));
+    let expected = &hex::Coord::metric_range(Metric::Face, 2) - &HashShape::from(hidden);
+    assert_eq!(visible, expected);
+}
+
+#[test]
+fn triangle_field_of_view() {
+    use TrianglePoint::*;
+    let origin = triangle::Coord::new(0, 0, Up);
+    let walls = HashShape::from([(0, 0), (0, -1)].map(|(x, y)| triangle::Coord::new(x, y, Down)));
+    let visible =
+        visibility::field_of_view(&origin, Metric::Face, 3, |coord| walls.contains(coord));
+    // Walls across two edges hide the triangles behind them, along with the
+    // triangle opposite the vertex where they meet.  Everything past the
+    // third edge remains in view.
+    let hidden = [
+        (0, -1, Up),
+        (0, 1, Up),
+        (1, -1, Up),
+        (1, 0, Up),
+        (0, -2, Down),
+        (0, 1, Down),
+        (1, -1, Down),
+        (1, -2, Down),
+        (1, 0, Down),
+    ]
+    .map(|(x, y, point)| triangle::Coord::new(x, y, point));
+    let expected = &triangle::Coord::metric_range(Metric::Face, 3) - &HashShape::from(hidden);
+    assert_eq!(visible, expected);
+
+    // A sight line passing through a vertex crosses two triangles to either
+    // side of it, so one wall to each side is enough to block it.
+    let walls =
+        HashShape::from([triangle::Coord::new(0, -2, Up), triangle::Coord::new(-1, -1, Down)]);
+    let blocks = |coord: &triangle::Coord| walls.contains(coord);
+    let target = triangle::Coord::new(-1, -3, Down);
+    assert!(!visibility::has_line_of_sight(&origin, &target, blocks));
+    assert!(!visibility::field_of_view(&origin, Metric::Face, 7, blocks).contains(&target));
+}
+
+#[test]
+fn square_lines() {
+    let origin = square::Coord::new(0, 0);
+    let line: Vec<_> = origin.line_iterator(&square::Coord::new(2, 2)).collect();
+    assert_eq!(line, [(0, 0), (1, 1), (2, 2)].map(|(x, y)| square::Coord::new(x, y)));
+    // A diagonal passing exactly through corners touches the squares to
+    // either side.
+    let cover: HashSet<_> = origin.supercover_iterator(&square::Coord::new(2, 2)).collect();
+    let expected = [(0, 0), (1, 0), (0, 1), (1, 1), (2, 1), (1, 2), (2, 2)];
+    assert_eq!(cover, expected.map(|(x, y)| square::Coord::new(x, y)).into());
+    let line: Vec<_> = origin.line_iterator(&square::Coord::new(4, 1)).collect();
+    assert_eq!(line.len(), 5);
+    let cover: Vec<_> = origin.supercover_iterator(&square::Coord::new(4, 1)).collect();
+    assert_eq!(cover.len(), 6);
+}
+
+#[test]
+fn square_metrics() {
+    let origin = square::Coord::default();
+    let king = square::Coord::new(3, -2);
+    assert_eq!(origin.distance(&king), 5);
+    assert_eq!(origin.vertex_distance(&king), 3);
+    assert_eq!(origin.euclidean_distance(&king), 13f32.sqrt());
+    // A diamond, a square and a disc respectively.
+    assert_eq!(square::Coord::metric_range(Metric::Face, 3).len(), 25);
+    assert_eq!(square::Coord::metric_range(Metric::Vertex, 3).len(), 49);
+    assert_eq!(square::Coord::metric_range(Metric::Euclidean, 3).len(), 29);
+    assert_eq!(square::Coord::metric_ring(Metric::Euclidean, 3).len(), 16);
+}
+
+#[test]
+fn square_wrapping() {
+    let torus = Topology::wrap_both(8, 8);
+    let corner = square::Coord::new(7, 7);
+    assert_eq!(torus.distance(&square::Coord::default(), &corner), 2);
+    assert_eq!(torus.normalize(&square::Coord::new(-1, 9)), square::Coord::new(7, 1));
+    let path: Vec<_> = torus.path_iterator(&square::Coord::default(), &corner).collect();
+    assert_eq!(path.len(), 3);
+    // Only the columns wrap around on a cylinder.
+    let cylinder = Topology::wrap_x(8);
+    assert_eq!(cylinder.distance(&square::Coord::default(), &corner), 8);
+    assert_eq!(cylinder.normalize(&square::Coord::new(-1, 9)), square::Coord::new(7, 9));
+}
+
+#[test]
+#[should_panic]
+fn hex_wrapping_odd_columns() {
+    Topology::<hex::Coord>::wrap_x(7);
+}
+
+#[test]
+fn square_canonical_forms() {
+    let shape = |coords: &[(i32, i32)]| -> HashShape<square::Coord> {
+        coords.iter().map(|(x, y)| square::Coord::new(*x, *y)).collect()
+    };
+    let s_tetromino = shape(&[(0, 0), (1, 0), (1, 1), (2, 1)]);
+    let z_tetromino = shape(&[(0, 1), (1, 1), (1, 0), (2, 0)]);
+    let l_tetromino = shape(&[(0, 0), (0, 1), (0, 2), (1, 0)]);
+    let j_tetromino = shape(&[(5, 5), (5, 6), (5, 7), (4, 5)]);
+    let t_tetromino = shape(&[(0, 0), (1, 0), (2, 0), (1, 1)]);
+    assert!(is_congruent(&s_tetromino, &z_tetromino));
+    assert!(is_congruent(&l_tetromino, &j_tetromino));
+    assert!(!is_congruent(&l_tetromino, &t_tetromino));
+    assert!(!is_congruent(&s_tetromino, &t_tetromino));
+
+    // The canonical form has its least coordinate at the origin.
+    let canonical = canonicalize(&j_tetromino);
+    assert!(canonical.form.contains(&square::Coord::default()));
+    assert_eq!(canonical.isometry().apply_shape(&j_tetromino), canonical.form);
+    assert_eq!(canonicalize(&l_tetromino).form, canonical.form);
+}
+
+#[test]
+fn triangle_canonical_forms() {
+    // Upward and downward facing triangles are exchanged by a half turn.
+    let up = HashShape::from([triangle::Coord::new(0, 0, TrianglePoint::Up)]);
+    let down = HashShape::from([triangle::Coord::new(3, -2, TrianglePoint::Down)]);
+    assert!(is_congruent(&up, &down));
+    let (up, down) = (canonicalize(&up), canonicalize(&down));
+    assert_eq!(up.form, down.form);
+    assert_ne!(up.half_turn, down.half_turn);
+}
+
+#[test]
+fn polyform_counts() {
+    use dynamic::Kind::*;
+    use Equivalence::*;
+    // The number of polyforms of sizes zero to six, from the OEIS.
+    let expected = [
+        (Square, Fixed, [1, 1, 2, 6, 19, 63, 216]),          // A001168
+        (Square, OneSided, [1, 1, 1, 2, 7, 18, 60]),         // A000988
+        (Square, Free, [1, 1, 1, 2, 5, 12, 35]),             // A000105
+        (Hex, Fixed, [1, 1, 3, 11, 44, 186, 814]),           // A001207
+        (Hex, OneSided, [1, 1, 1, 3, 10, 33, 147]),          // A006535
+        (Hex, Free, [1, 1, 1, 3, 7, 22, 82]),                // A000228
+        (Triangle, Fixed, [1, 2, 3, 6, 14, 36, 94]),         // A001420
+        (Triangle, OneSided, [1, 1, 1, 1, 4, 6, 19]),        // A006534
+        (Triangle, Free, [1, 1, 1, 1, 3, 4, 12]),            // A000577
+    ];
+    for (kind, equivalence, counts) in expected {
+        for (size, count) in counts.into_iter().enumerate() {
+            let forms = dynamic::Coord::polyforms(kind, size, equivalence);
+            assert_eq!(
+                forms.len(),
+                count,
+                "Expected {} {} polyforms of size {} on {} grids",
+                count,
+                equivalence,
+                size,
+                kind
+            );
+            for form in forms.iter() {
+                assert_eq!(form.len(), size);
+                assert!(connectivity::is_connected(form, DirectionType::Face));
+            }
+        }
+    }
+
+    // Each free polyomino should be congruent to exactly one of the others.
+    let free = polyform::polyforms::<square::Coord>(5, Free);
+    for form in polyform::polyforms::<square::Coord>(5, Fixed) {
+        assert_eq!(free.iter().filter(|other| is_congruent(&form, other)).count(), 1);
+        assert!(form.contains(&square::Coord::default()));
+    }
+    assert_eq!(free, polyform::polyforms::<square::Coord>(5, Free));
+}
+
+#[test]
+fn tiling_counts() {
+    use Equivalence::*;
+    let domino: HashShape<square::Coord> =
+        [square::Coord::new(0, 0), square::Coord::new(1, 0)].into();
+    let dominoes = |count: usize| vec![(domino.clone(), Free); count];
+    // Interchangeable pieces should not multiply the number of tilings.
+    assert_eq!(tiling::count_tilings(&square::Coord::rectangle(2, 3), &dominoes(3)), 3);
+    assert_eq!(tiling::count_tilings(&square::Coord::rectangle(2, 4), &dominoes(4)), 5);
+    assert_eq!(tiling::count_tilings(&square::Coord::rectangle(2, 4), &dominoes(3)), 0);
+    // Without rotations, dominoes can only be stacked.
+    let fixed = vec![(domino.clone(), Fixed); 4];
+    assert_eq!(tiling::count_tilings(&square::Coord::rectangle(2, 4), &fixed), 1);
+
+    // The L tetromino has eight orientations, and the square only one.
+    let tetrominoes: Vec<HashShape<square::Coord>> = polyform::polyforms(4, Free);
+    let counts: Vec<usize> =
+        tetrominoes.iter().map(|shape| orientations(shape, Free).len()).collect();
+    assert_eq!(counts.iter().filter(|count| **count == 8).count(), 1);
+    assert_eq!(counts.iter().filter(|count| **count == 1).count(), 1);
+
+    // The twelve free pentominoes tile a three by twenty rectangle in two
+    // ways, up to its four symmetries.
+    let pentominoes: Vec<(HashShape<square::Coord>, Equivalence)> =
+        polyform::polyforms(5, Free).into_iter().map(|shape| (shape, Free)).collect();
+    let target = square::Coord::rectangle(20, 3);
+    assert_eq!(tiling::count_tilings(&target, &pentominoes), 8);
+    let first = tiling::first_tiling(&target, &pentominoes).unwrap();
+    let used: HashSet<usize> = first.iter().map(|(_, piece)| *piece).collect();
+    assert_eq!(used.len(), 12);
+
+    // A hexagon of six triangles can be tiled by three diamonds in two ways.
+    let diamond: HashShape<triangle::Coord> = polyform::polyforms(2, Free).remove(0);
+    let diamonds = vec![(diamond, Free); 3];
+    assert_eq!(tiling::count_tilings(&triangle::Coord::hexagon(1), &diamonds), 2);
+
+    // The empty region has a single, empty, tiling.
+    assert_eq!(
+        tiling::tilings(&HashShape::<hex::Coord>::new(), &[]).collect::<Vec<_>>(),
+        vec![HashShapeContainer::new()]
+    );
+}
+
+#[test]
+fn coord_parsing() {
+    use endgame_grid::notation::{column_letters, parse_column_letters, ParseCoordError};
+    assert_eq!("(3,-4)".parse(), Ok(square::Coord::new(3, -4)));
+    assert_eq!(" ( -1 , 2 ) ".parse(), Ok(hex::Coord::new(-1, 2)));
+    assert_eq!("(0,1,▼)".parse(), Ok(triangle::Coord::new(0, 1, TrianglePoint::Down)));
+    assert_eq!("(0,1,⏶)".parse(), Ok(triangle::Coord::new(0, 1, TrianglePoint::Up)));
+    assert_eq!("Hex(1,-2)".parse(), Ok(dynamic::Coord::from(hex::Coord::new(1, -2))));
+    assert_eq!("square(1,-2)".parse(), Ok(dynamic::Coord::from(square::Coord::new(1, -2))));
+    assert_eq!(
+        dynamic::Coord::from(triangle::Coord::new(2, 0, TrianglePoint::Up)).tagged().to_string(),
+        "Triangle(2,0,▲)"
+    );
+    assert_eq!("triangle".parse(), Ok(dynamic::Kind::Triangle));
+
+    // Malformed input produces descriptive errors.
+    assert!(matches!("(1,2,3)".parse::<square::Coord>(), Err(ParseCoordError::Syntax { .. })));
+    assert!(matches!("1,2".parse::<hex::Coord>(), Err(ParseCoordError::Syntax { .. })));
+    assert!(matches!("(1,x)".parse::<hex::Coord>(), Err(ParseCoordError::Integer { .. })));
+    assert!(matches!(
+        "(1,99999999999)".parse::<square::Coord>(),
+        Err(ParseCoordError::Integer { .. })
+    ));
+    assert_eq!(
+        "(1,2,?)".parse::<triangle::Coord>(),
+        Err(ParseCoordError::Point("?".to_string()))
+    );
+    assert_eq!(
+        "Cube(1,2)".parse::<dynamic::Coord>(),
+        Err(ParseCoordError::Kind("Cube".to_string()))
+    );
+    let err = "(1,2)".parse::<triangle::Coord>().unwrap_err();
+    assert!(err.to_string().contains("(x,y,▲)"), "Unexpected message: {err}");
+    let err = "(1,y)".parse::<square::Coord>().unwrap_err();
+    assert!(std::error::Error::source(&err).is_some());
+
+    // Chess-like labels for square grids.
+    assert_eq!(square::Coord::new(4, 3).to_label(), Some("e4".to_string()));
+    assert_eq!(square::Coord::new(26, 0).to_label(), Some("aa1".to_string()));
+    assert_eq!(square::Coord::new(-1, 0).to_label(), None);
+    assert_eq!(square::Coord::from_label("H8"), Ok(square::Coord::new(7, 7)));
+    for bad in ["", "a", "7", "a0", "a-1", "a+1", "1a", "a1b", "é1"] {
+        assert_eq!(square::Coord::from_label(bad), Err(ParseCoordError::Label(bad.to_string())));
+    }
+
+    // Lettered columns for hex grids follow the offset scheme.
+    use hex::OffsetScheme::*;
+    let coord = hex::Coord::from_offset(OddQ, IVec2::new(1, 2));
+    assert_eq!(coord.to_label(OddQ), Some("b3".to_string()));
+    assert_eq!(hex::Coord::from_label(OddQ, "b3"), Ok(coord));
+
+    for index in [0, 1, 25, 26, 27, 701, 702, u32::MAX] {
+        assert_eq!(parse_column_letters(&column_letters(index)), Ok(index));
+    }
+    assert_eq!(column_letters(701), "zz");
+}
+
+#[test]
+fn text_fixtures() {
+    use endgame_grid::text::ParseTextError;
+    let piece = |c: char| match c {
+        'X' | 'O' => Some(Some(c)),
+        '.' => Some(None),
+        _ => None,
+    };
+    let draw = |value: &Option<char>| value.unwrap_or('.');
+
+    let art = "\
+X . O
+. X
+O . X
+";
+    let origin = square::Coord::default();
+    let board: HashShapeContainer<square::Coord, Option<char>> =
+        text::parse(art, &origin, piece).unwrap();
+    assert_eq!(board.len(), 8);
+    assert_eq!(board.get(&square::Coord::new(0, 2)), Some(&Some('X')));
+    assert_eq!(board.get(&square::Coord::new(2, 0)), Some(&Some('X')));
+    assert_eq!(board.get(&square::Coord::new(0, 0)), Some(&Some('O')));
+    assert_eq!(board.get(&square::Coord::new(2, 1)), None);
+    assert_eq!(text::render(&board, &origin, draw), art);
+
+    // Odd rows of hexagons are staggered.
+    let art = " O .\nX . O\n";
+    let origin = hex::Coord::default();
+    let board: HashShapeContainer<hex::Coord, Option<char>> =
+        text::parse(art, &origin, piece).unwrap();
+    assert_eq!(board.get(&hex::Coord::new(0, 0)), Some(&Some('X')));
+    assert_eq!(board.get(&hex::Coord::new(0, 1)), Some(&Some('O')));
+    assert_eq!(text::render(&board, &origin, draw), art);
+
+    // Triangles are drawn with their glyphs.
+    let art = "\
+▼.▲O
+▲X▼.
+";
+    let origin = triangle::Coord::default();
+    let board: HashShapeContainer<triangle::Coord, Option<char>> =
+        text::parse(art, &origin, piece).unwrap();
+    assert_eq!(board.get(&origin), Some(&Some('X')));
+    assert_eq!(board.get(&triangle::Coord::new(0, 1, TrianglePoint::Up)), Some(&Some('O')));
+    assert_eq!(text::render(&board, &origin, draw), art);
+
+    // Malformed art is reported with its position.
+    let parse_square = |art: &str| {
+        let origin = square::Coord::default();
+        text::parse::<_, _, HashShapeContainer<square::Coord, Option<char>>>(art, &origin, piece)
+    };
+    assert_eq!(
+        parse_square("X .\n. ?"),
+        Err(ParseTextError::UnknownValue { line: 2, column: 3, found: '?' })
+    );
+    assert_eq!(
+        parse_square("X.\n"),
+        Err(ParseTextError::Misplaced { line: 1, column: 2, found: '.' })
+    );
+    let parse_triangle = |art: &str| {
+        text::parse::<_, _, HashShapeContainer<triangle::Coord, Option<char>>>(art, &origin, piece)
+    };
+    assert_eq!(
+        parse_triangle("▼X"),
+        Err(ParseTextError::Misplaced { line: 1, column: 1, found: '▼' })
+    );
+    assert_eq!(
+        parse_triangle("▲ ▼."),
+        Err(ParseTextError::Misplaced { line: 1, column: 1, found: '▲' })
+    );
+    assert_eq!(
+        parse_triangle(" X"),
+        Err(ParseTextError::Misplaced { line: 1, column: 2, found: 'X' })
+    );
+    let empty = HashShapeContainer::<hex::Coord, char>::new();
+    assert_eq!(text::render(&empty, &hex::Coord::default(), |c| *c), "");
+}
+
+#[test]
+fn svg_snapshot() {
+    use svg::{ArrowStyle, CellBorderStyle, CellStyle, Rgba, SvgWriter, Theme};
+    let mut writer = SvgWriter::new(square::SizedGrid::new(10.0)).with_background(Rgba::WHITE);
+    let origin = square::Coord::new(0, 0);
+    let east = square::Coord::new(1, 0);
+    writer.cell(&origin, &Theme::GraphPaper.cell_style(&origin, false), Some("a<b"));
+    let style = CellStyle {
+        fill_color: Some(Rgba::new(255, 0, 0, 128)),
+        border: CellBorderStyle::none(),
+        label: None,
+    };
+    writer.cell(&east, &style, None);
+    let style = ArrowStyle {
+        color: Rgba::BLACK,
+        width: 2.0,
+        to_head: true,
+        from_head: false,
+        label: None,
+    };
+    writer.arrow(&origin, &east, &style, None);
+    let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-20 -20 60 40" width="60" height="40">
+<rect x="-20" y="-20" width="60" height="40" fill="#ffffff"/>
+<polygon points="10,-10 -10,-10 -10,10 10,10" fill="#fffffa" stroke="#62d5fa" stroke-width="4" stroke-linejoin="round" stroke-linecap="round"/>
+<text x="1" y="1" font-family="monospace" font-size="8" text-anchor="middle" dominant-baseline="central" fill="#a0a0a0">a&lt;b</text>
+<text x="0" y="0" font-family="monospace" font-size="8" text-anchor="middle" dominant-baseline="central" fill="#62d5fa">a&lt;b</text>
+<polygon points="30,-10 10,-10 10,10 30,10" fill="#ff0000" fill-opacity="0.5" stroke="none"/>
+<polyline points="3.3,0 16.7,0" fill="none" stroke="#000000" stroke-width="2" stroke-linejoin="round" stroke-linecap="round"/>
+<polygon points="16.7,0 11.5,3 11.5,-3" fill="#000000"/>
+</svg>
+"##;
+    assert_eq!(writer.to_svg(), expected);
+    assert_eq!(writer.to_string(), expected);
+
+    // An empty document is just the margin around the origin.
+    let writer = SvgWriter::new(hex::SizedGrid::new(10.0));
+    assert_eq!(
+        writer.to_svg(),
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-10 -10 20 20\" width=\"20\" \
+         height=\"20\">\n</svg>\n"
+    );
+}
+
+#[test]
+fn svg_self_loop_snapshot() {
+    use svg::{ArrowStyle, LabelStyle, Rgba, SvgWriter};
+    let mut writer = SvgWriter::new(square::SizedGrid::new(10.0)).with_margin(0.0);
+    let origin = square::Coord::new(0, 0);
+    let style = ArrowStyle {
+        color: Rgba::BLACK,
+        width: 1.0,
+        to_head: true,
+        from_head: false,
+        label: Some(LabelStyle {
+            color: Rgba::BLACK,
+            font_size: 4.0,
+            add_shadow: Some(Rgba::GRAY),
+        }),
+    };
+    writer.arrow(&origin, &origin, &style, Some("loop"));
+    // The label, and its shadow, lie above the loop within the document.
+    let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-3.3 -11.3 6.6 14.6" width="6.6" height="14.6">
+<circle cx="0" cy="0" r="3.3" fill="none" stroke="#000000" stroke-width="1" stroke-linejoin="round" stroke-linecap="round"/>
+<text x="1" y="-10.3" font-family="monospace" font-size="4" text-anchor="middle" dominant-baseline="central" fill="#a0a0a0">loop</text>
+<text x="0" y="-11.3" font-family="monospace" font-size="4" text-anchor="middle" dominant-baseline="central" fill="#000000">loop</text>
+</svg>
+"##;
+    assert_eq!(writer.to_svg(), expected);
+}
+
+#[test]
+fn grid_part_fixtures() {
+    use Direction::*;
+    let origin = square::Coord::new(0, 0);
+    let east = square::Coord::new(1, 0);
+    // The edge is represented from the cell with the least array offset.
+    let edge = Edge::from_face(east, West).unwrap();
+    assert_eq!(Edge::from_face(origin, East), Some(edge));
+    assert_eq!(edge.to_string(), "Edge((0,0) East)");
+    assert_eq!(Edge::from_face(origin, NorthEast), None);
+    let vertex = Vertex::from_corner(east, NorthWest).unwrap();
+    assert_eq!(Vertex::from_corner(origin, NorthEast), Some(vertex));
+    assert_eq!(vertex.to_string(), "Vertex((0,0) NorthEast)");
+    assert_eq!(edge.vertices()[0], vertex);
+    assert_eq!(Vertex::from_corner(origin, North), None);
+    let sized_grid = square::SizedGrid::new(1.0);
+    assert!(sized_grid.vertex_to_screen(&vertex).abs_diff_eq(Vec2::new(1.0, 1.0), 1e-5));
+    let (start, end) = sized_grid.edge_to_screen(&edge);
+    assert!((start + end).abs_diff_eq(Vec2::new(2.0, 0.0), 1e-5));
+    assert!((start - end).abs().abs_diff_eq(Vec2::new(0.0, 2.0), 1e-5));
+
+    let hex_origin = hex::Coord::new(0, 0);
+    let vertex = Vertex::from_corner(hex_origin, East).unwrap();
+    let mut faces: Vec<String> = vertex.faces().iter().map(|c| c.to_string()).collect();
+    faces.sort();
+    assert_eq!(faces, vec!["(0,0)", "(1,-1)", "(1,0)"]);
+
+    let up = triangle::Coord::new(0, 0, TrianglePoint::Up);
+    let vertex = Vertex::from_corner(up, North).unwrap();
+    assert_eq!(vertex.faces().len(), 6);
+    assert_eq!(up.edges().len(), 3);
+    assert_eq!(Vertex::from_corner(up, South), None);
+}
+
+#[test]
+fn layered_fixtures() {
+    use layered::Axes::{Level, Planar};
+    let coord = layered::Coord::new(square::Coord::new(1, -2), 3);
+    assert_eq!(coord.to_string(), "(1,-2)@3");
+    assert_eq!("(1, -2) @ 3".parse(), Ok(coord));
+    assert!("(1,-2)".parse::<layered::Coord<square::Coord>>().is_err());
+    assert_eq!(coord.reflect(Level), layered::Coord::new(square::Coord::new(1, -2), -3));
+    assert_eq!(coord.reflect(Planar(square::Axes::X)).level(), 3);
+    let levels: Vec<i32> = coord.axis_iterator(Level, false, ..3).map(|c| c.level()).collect();
+    assert_eq!(levels, vec![3, 2, 1]);
+    assert_eq!(coord.move_on_axis(Level, true), coord.move_vertically(Vertical::Up));
+
+    // Diagonal steps do not shorten the climb between the levels of either
+    // a layered grid or a cubic lattice.
+    let origin = layered::Coord::new(square::Coord::new(0, 0), 0);
+    let other = layered::Coord::new(square::Coord::new(2, 1), 2);
+    assert_eq!((origin.distance(&other), origin.vertex_distance(&other)), (5, 4));
+    let origin = cubic::Coord::default();
+    let other = cubic::Coord::new(2, 1, 2);
+    assert_eq!((origin.distance(&other), origin.vertex_distance(&other)), (5, 4));
+    assert_eq!(other.to_string(), "(2,1,2)");
+    assert_eq!("(2,1,2)".parse(), Ok(other));
+    assert_eq!(other.move_on_axis(cubic::Axes::Z, false), cubic::Coord::new(2, 1, 1));
+    assert_eq!(other.reflect(cubic::Axes::Z), cubic::Coord::new(2, 1, -2));
+    // A segment through the corner shared by eight cubes touches them all.
+    let corner = cubic::Coord::new(1, 1, 1);
+    assert_eq!(origin.supercover_iterator(&corner).count(), 8);
+
+    assert_eq!(cubic::Coord::range(1).len(), 27);
+    assert_eq!(cubic::Coord::ring(1).len(), 26);
+    assert_eq!(prism::Coord::range(1).len(), 21);
+    assert_eq!(prism::Coord::ring(1).len(), 20);
+    assert_eq!(layered::Coord::range(1, hex::Coord::range).len(), 9);
+    assert_eq!(layered::Coord::ring(2, square::Coord::ring).len(), 16 + 2 * 8 + 2);
+    let prism = prism::Coord::new(1, -1, 4);
+    assert_eq!(prism.to_string().parse(), Ok(prism));
+    assert_eq!(prism.planar(), hex::Coord::new(1, -1));
+}
+
+#[test]
+fn chunked_fixtures() {
+    // A load hook generating terrain fills each chunk as it is loaded.
+    let saved = Arc::new(Mutex::new(Vec::new()));
+    let log = saved.clone();
+    let mut world = ChunkedShapeContainer::with_chunk_size(4)
+        .on_load(|chunk| {
+            let coords: Vec<square::Coord> = chunk.coords().collect();
+            for coord in coords {
+                chunk.insert(coord, 0);
+            }
+        })
+        .on_unload(move |chunk| log.lock().unwrap().push(chunk.key()));
+    assert_eq!(world.chunk_size(), 4);
+    assert_eq!(world.chunk_key(&square::Coord::new(3, 4)), (0, 1));
+    assert_eq!(world.chunk_key(&square::Coord::new(-1, -4)), (-1, -1));
+    assert_eq!(world.chunk_key(&square::Coord::new(-5, 0)), (-2, 0));
+    assert!(world.is_empty());
+
+    assert_eq!(world.insert(square::Coord::new(5, -1), 7), Some(0));
+    assert!(world.is_loaded((1, -1)));
+    assert_eq!(world.len(), 16);
+    assert_eq!(world.get(&square::Coord::new(5, -1)), Some(&7));
+    assert_eq!(world.get(&square::Coord::new(4, -4)), Some(&0));
+    assert_eq!(world.get(&square::Coord::new(3, -1)), None);
+
+    // Explicitly loading a chunk populates it, but only the first time.
+    assert_eq!(world.load_chunk((0, 0)).len(), 16);
+    assert_eq!(world.len(), 32);
+    world.remove(&square::Coord::new(0, 0));
+    assert_eq!(world.load_chunk((0, 0)).len(), 15);
+    assert_eq!(world.insert(square::Coord::new(0, 0), 1), None);
+
+    // Retain the chunk around the origin, unloading the other.
+    world.retain_chunks(|chunk| chunk.key() == (0, 0));
+    assert_eq!(*saved.lock().unwrap(), vec![(1, -1)]);
+    assert!(!world.is_loaded((1, -1)));
+    assert_eq!(world.len(), 16);
+    let unloaded = world.unload_chunk((0, 0)).expect("Chunk should be loaded");
+    assert_eq!(unloaded.get(&square::Coord::new(0, 0)), Some(&1));
+    assert!(world.unload_chunk((0, 0)).is_none());
+    assert!(world.is_empty());
+
+    // Chunks of hexagonal grids tile array offsets, so odd columns are
+    // offset by half a cell.
+    let hexes: ChunkedShapeContainer<hex::Coord, ()> =
+        ChunkedShapeContainer::from_shape_value(hex::Coord::range(3), ());
+    assert_eq!(hexes.len(), 37);
+    assert_eq!(hexes.chunks().count(), 4);
+    assert_eq!(hexes.chunk_key(&hex::Coord::new(1, -1)), (0, 0));
+    assert_eq!(hexes.chunk_key(&hex::Coord::new(1, -2)), (0, -1));
+}
+
+#[test]
+fn square_connectivity() {
+    let diagonal = HashShape::from([square::Coord::new(0, 0), square::Coord::new(1, 1)]);
+    assert_eq!(connectivity::connected_components(&diagonal, DirectionType::Face).len(), 2);
+    assert!(!connectivity::is_connected(&diagonal, DirectionType::Face));
+    assert!(connectivity::is_connected(&diagonal, DirectionType::Vertex));
+}
+
+#[test]
+fn square_outline() {
+    let grid = square::SizedGrid::new(1.0);
+    // A ring of squares has a single outline with a single hole.
+    let ring = square::Coord::ring(1);
+    let outlines = outline::outlines(&grid, &ring);
+    assert_eq!(outlines.len(), 1);
+    assert_eq!(outlines[0].exterior.len(), 12);
+    assert_eq!(outlines[0].holes.len(), 1);
+    assert_eq!(outlines[0].holes[0].len(), 4);
+    // Squares meeting only at a corner have separate outlines.
+    let diagonal = HashShape::from([square::Coord::new(0, 0), square::Coord::new(1, 1)]);
+    assert_eq!(outline::outlines(&grid, &diagonal).len(), 2);
+}
+
 #[test]
 fn test_color_try_from_usize_success() {
     use endgame_grid::Color::*;
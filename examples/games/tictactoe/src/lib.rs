@@ -1,7 +1,8 @@
 use endgame_direction::Direction;
+use endgame_grid::board::Board;
 use endgame_grid::shape::{HashShapeContainer, HashShapeContainerIterator};
 use endgame_grid::square;
-use endgame_grid::{Coord, DirectionType, ShapeContainer};
+use endgame_grid::{DirectionType, ShapeContainer};
 use endgame_ludic::game;
 use endgame_ludic::payoffs::Payoffs;
 use ordered_float::OrderedFloat;
@@ -114,14 +115,7 @@ impl State {
     fn new(size: usize) -> Self {
         assert!(size > 0, "The board must not be zero sized.");
 
-        // TODO Annoying that we cannot use `range` function for this.  Look into adding
-        //   a shape creation function for this case.
-        let mut board = HashShapeContainer::new();
-        for x in 0..size {
-            for y in 0..size {
-                board.insert(square::Coord::new(x as i32, y as i32), None);
-            }
-        }
+        let board = HashShapeContainer::from_shape_value(square::Coord::rectangle(size, size), None);
         Self {
             size,
             turns: 0,
@@ -133,9 +127,12 @@ impl State {
 
     /// Check to see if the given `Player` has won.
     fn winner(&self, player: Player) -> bool {
+        // Lines are walked on a board of the same size, so that they stop at
+        // its edge.
+        let bounds = Board::new(square::Coord::rectangle(self.size, self.size));
         let check_line = |x: usize, y: usize, dir_type: DirectionType, dir: Direction| {
-            square::Coord::new(x as i32, y as i32)
-                .direction_iterator(dir_type, dir, ..self.size)
+            bounds
+                .direction_iterator(&square::Coord::new(x as i32, y as i32), dir_type, dir, ..)
                 .take_while(|c| self.board.get(c) == Some(&Some(player)))
                 .count()
                 >= self.size
@@ -153,7 +150,7 @@ impl State {
             check_line(0, 0, DirectionType::Vertex, Direction::NorthEast)
             ||
             // Check the lower-left to upper-right diagonal
-            check_line(self.size, 0, DirectionType::Vertex, Direction::NorthWest)
+            check_line(self.size - 1, 0, DirectionType::Vertex, Direction::NorthWest)
     }
 
     pub fn board(&self) -> &HashShapeContainer<square::Coord, Option<Player>> {
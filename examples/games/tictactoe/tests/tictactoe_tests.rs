@@ -146,4 +146,23 @@ fn test_random_strategy_is_deterministic_with_seed() {
     } else {
         unreachable!("On the initial state there should always be a legal move for X.");
     }
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_anti_diagonal_win() {
+    use std::collections::HashMap;
+    let mut state = Game::default().start();
+    // X claims the diagonal from the lower right to the upper left, while O
+    // plays along the bottom row.
+    let moves = [(2, 0), (0, 0), (1, 1), (1, 0), (0, 2)];
+    for (index, (x, y)) in moves.into_iter().enumerate() {
+        let player = if index % 2 == 0 { Player::X } else { Player::O };
+        assert!(!state.is_over(), "The game should not be over before move {index}.");
+        state = state
+            .next(&HashMap::from([(player, Move(square::Coord::new(x, y)))]))
+            .expect("The move should be legal.");
+    }
+    assert!(state.is_over(), "X should have won along the anti-diagonal.");
+    let (x, o) = payoffs_tuple(&state);
+    assert!(x > 0.0 && o < 0.0, "X should have the winning payoff.");
+}